@@ -0,0 +1,180 @@
+//! Order alerts.
+//!
+//! An [`AlertRule`] (stored in the `alert_rules` table) describes the public
+//! orders a user is waiting for: kind, currency, payment methods, a fiat
+//! range, premium bounds and a minimum maker rating. `watchalerts` follows
+//! the order book and hands every new or updated order to [`rule_matches`];
+//! matches go to the rule's [`AlertNotifier`], once per rule and order.
+
+use serde_json::json;
+use std::str::FromStr;
+
+use crate::db::AlertRule;
+use crate::fiat::{FiatAmount, FiatRange};
+use crate::nip33::OrderView;
+use crate::parser::orders::OrderFilter;
+
+pub mod notify;
+
+pub use notify::{AlertNotifier, NotifierKind};
+
+/// The payment method, premium and rating parts of `rule`, as the filter
+/// `listorders` uses.
+fn rule_filter(rule: &AlertRule) -> OrderFilter {
+    OrderFilter {
+        payment_methods: rule
+            .payment_methods
+            .split(',')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(str::to_string)
+            .collect(),
+        min_premium: rule.min_premium,
+        max_premium: rule.max_premium,
+        min_rating: rule.min_rating,
+        ..Default::default()
+    }
+}
+
+fn fiat_bound(bound: &Option<String>) -> Option<FiatAmount> {
+    bound.as_deref().and_then(|b| FiatAmount::parse(b).ok())
+}
+
+/// Whether `view` is an order `rule` is waiting for at unix time `now`.
+///
+/// The fiat bounds match any order that can be traded for some amount
+/// between them: a fixed amount inside, or a range overlapping them.
+pub fn rule_matches(rule: &AlertRule, view: &OrderView, now: i64) -> bool {
+    let order = &view.order;
+    let kind_ok = rule.kind.as_deref().is_none_or(|kind| {
+        order
+            .kind
+            .is_some_and(|k| k.to_string().eq_ignore_ascii_case(kind))
+    });
+    let currency_ok = rule
+        .fiat_code
+        .as_deref()
+        .is_none_or(|code| order.fiat_code.eq_ignore_ascii_case(code));
+    let fiat = view.fiat_range();
+    let order_max = fiat.max.unwrap_or(fiat.min);
+    let fiat_ok = fiat_bound(&rule.min_fiat).is_none_or(|min| order_max >= min)
+        && fiat_bound(&rule.max_fiat).is_none_or(|max| fiat.min <= max);
+    kind_ok && currency_ok && fiat_ok && rule_filter(rule).matches_view(view, now)
+}
+
+/// Short text of what `rule` looks for, e.g.
+/// `sell · USD · zelle · 50-500 · premium ≤ 1%`.
+pub fn rule_description(rule: &AlertRule) -> String {
+    let mut parts = Vec::new();
+    parts.push(rule.kind.clone().unwrap_or_else(|| "buy/sell".to_string()));
+    parts.push(
+        rule.fiat_code
+            .clone()
+            .unwrap_or_else(|| "any currency".to_string()),
+    );
+    if !rule.payment_methods.is_empty() {
+        parts.push(rule.payment_methods.clone());
+    }
+    match (&rule.min_fiat, &rule.max_fiat) {
+        (Some(min), Some(max)) if min == max => parts.push(min.clone()),
+        (Some(min), Some(max)) => parts.push(format!("{}-{}", min, max)),
+        (Some(min), None) => parts.push(format!("≥ {}", min)),
+        (None, Some(max)) => parts.push(format!("≤ {}", max)),
+        (None, None) => {}
+    }
+    match (rule.min_premium, rule.max_premium) {
+        (Some(min), Some(max)) => parts.push(format!("premium {}% to {}%", min, max)),
+        (Some(min), None) => parts.push(format!("premium ≥ {}%", min)),
+        (None, Some(max)) => parts.push(format!("premium ≤ {}%", max)),
+        (None, None) => {}
+    }
+    if let Some(rating) = rule.min_rating {
+        parts.push(format!("rating ≥ {}", rating));
+    }
+    parts.join(" · ")
+}
+
+/// JSON describing a match, as sent to exec hooks and webhooks.
+pub fn alert_payload(rule: &AlertRule, view: &OrderView, now: i64) -> serde_json::Value {
+    let order = &view.order;
+    json!({
+        "rule": {
+            "id": rule.id,
+            "name": rule.name,
+        },
+        "order": {
+            "id": order.id.map(|id| id.to_string()),
+            "kind": order.kind.map(|k| k.to_string()),
+            "status": order.status.map(|s| s.to_string()),
+            "fiat_code": order.fiat_code,
+            "fiat_amount": view.fiat_amount_text(),
+            "amount": order.amount,
+            "payment_method": order.payment_method,
+            "premium": order.premium,
+            "created_at": order.created_at,
+            "expires_at": view.expires_at,
+            "maker_rating": view.maker_rating.as_ref().map(|r| json!({
+                "total_reviews": r.total_reviews,
+                "total_rating": r.total_rating,
+                "days": r.days,
+            })),
+        },
+        "matched_at": now,
+    })
+}
+
+/// Build the rule `addalert` saves, checking its parts make sense.
+#[allow(clippy::too_many_arguments)]
+pub fn new_alert_rule(
+    name: &str,
+    kind: Option<&str>,
+    fiat_code: Option<&str>,
+    payment_methods: &[String],
+    fiat_amount: Option<&FiatRange>,
+    min_premium: Option<i64>,
+    max_premium: Option<i64>,
+    min_rating: Option<f64>,
+    notifier: &AlertNotifier,
+) -> anyhow::Result<AlertRule> {
+    let kind = kind
+        .map(|k| {
+            mostro_core::order::Kind::from_str(&k.to_lowercase())
+                .map(|k| k.to_string())
+                .map_err(|_| anyhow::anyhow!("Invalid order kind '{}'", k))
+        })
+        .transpose()?;
+    if let (Some(min), Some(max)) = (min_premium, max_premium) {
+        if min > max {
+            return Err(anyhow::anyhow!(
+                "--min-premium {} is above --max-premium {}",
+                min,
+                max
+            ));
+        }
+    }
+    if let Some(rating) = min_rating {
+        if !(0.0..=5.0).contains(&rating) {
+            return Err(anyhow::anyhow!("--min-rating must be between 0 and 5"));
+        }
+    }
+    Ok(AlertRule {
+        name: name.to_string(),
+        kind,
+        fiat_code: fiat_code.map(str::to_uppercase),
+        payment_methods: payment_methods
+            .iter()
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+            .collect::<Vec<_>>()
+            .join(","),
+        min_fiat: fiat_amount.map(|f| f.min.to_string()),
+        max_fiat: fiat_amount.map(|f| f.max.unwrap_or(f.min).to_string()),
+        min_premium,
+        max_premium,
+        min_rating,
+        notifier: notifier.kind().to_string(),
+        target: notifier.target().map(str::to_string),
+        allow_remote: notifier.allows_remote(),
+        ..Default::default()
+    })
+}
@@ -0,0 +1,192 @@
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+
+use super::{alert_payload, rule_description};
+use crate::db::AlertRule;
+use crate::nip33::OrderView;
+use crate::parser::order_feed::{clock_text, order_summary};
+use crate::util::run_hook;
+
+/// Longest an exec hook may run, and a webhook may take to answer.
+pub const ALERT_HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where a rule's matches are announced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum NotifierKind {
+    /// Print a line on the terminal running `watchalerts`
+    #[default]
+    Stdout,
+    /// Run a shell command with the match in its environment
+    Exec,
+    /// POST the match as JSON to a URL
+    Webhook,
+}
+
+impl std::fmt::Display for NotifierKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            NotifierKind::Stdout => "stdout",
+            NotifierKind::Exec => "exec",
+            NotifierKind::Webhook => "webhook",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for NotifierKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stdout" => Ok(NotifierKind::Stdout),
+            "exec" => Ok(NotifierKind::Exec),
+            "webhook" => Ok(NotifierKind::Webhook),
+            _ => Err(anyhow!("Invalid alert notifier: {}", s)),
+        }
+    }
+}
+
+/// A notifier with its target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertNotifier {
+    Stdout,
+    /// Command run with `sh -c`. The match is in `MOSTRO_ALERT` (JSON),
+    /// `MOSTRO_ALERT_RULE` and `MOSTRO_ORDER_ID`, and on stdin.
+    Exec(String),
+    /// `http(s)` URL the JSON match is POSTed to. Only loopback hosts
+    /// unless `allow_remote`, so order alerts don't leave the machine by
+    /// accident.
+    Webhook {
+        url: String,
+        allow_remote: bool,
+    },
+}
+
+/// Whether `url` points at this machine: `localhost` or a loopback address.
+fn is_local_url(url: &reqwest::Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+impl AlertNotifier {
+    /// Pair `kind` with its `target`: a command for `exec`, a URL for
+    /// `webhook`, nothing for `stdout`. A webhook on another host needs
+    /// `allow_remote`.
+    pub fn new(kind: NotifierKind, target: Option<&str>, allow_remote: bool) -> Result<Self> {
+        let target = target.map(str::trim).filter(|t| !t.is_empty());
+        match (kind, target) {
+            (NotifierKind::Stdout, None) => Ok(AlertNotifier::Stdout),
+            (NotifierKind::Stdout, Some(_)) => {
+                Err(anyhow!("--target is only used by exec and webhook alerts"))
+            }
+            (NotifierKind::Exec, Some(command)) => Ok(AlertNotifier::Exec(command.to_string())),
+            (NotifierKind::Webhook, Some(url)) => {
+                let parsed = reqwest::Url::parse(url)
+                    .map_err(|e| anyhow!("Invalid webhook URL '{}': {}", url, e))?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    return Err(anyhow!("Webhook URL must be http or https: {}", url));
+                }
+                if !allow_remote && !is_local_url(&parsed) {
+                    return Err(anyhow!(
+                        "Webhook {} is not on this machine; pass --allowremote to send alerts to it",
+                        url
+                    ));
+                }
+                Ok(AlertNotifier::Webhook {
+                    url: url.to_string(),
+                    allow_remote,
+                })
+            }
+            (kind, None) => Err(anyhow!("{} alerts need a --target", kind)),
+        }
+    }
+
+    /// The notifier saved with `rule`.
+    pub fn for_rule(rule: &AlertRule) -> Result<Self> {
+        Self::new(
+            rule.notifier.parse()?,
+            rule.target.as_deref(),
+            rule.allow_remote,
+        )
+    }
+
+    pub fn kind(&self) -> NotifierKind {
+        match self {
+            AlertNotifier::Stdout => NotifierKind::Stdout,
+            AlertNotifier::Exec(_) => NotifierKind::Exec,
+            AlertNotifier::Webhook { .. } => NotifierKind::Webhook,
+        }
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            AlertNotifier::Stdout => None,
+            AlertNotifier::Exec(target) | AlertNotifier::Webhook { url: target, .. } => {
+                Some(target)
+            }
+        }
+    }
+
+    /// Whether a webhook may post to another host.
+    pub fn allows_remote(&self) -> bool {
+        matches!(
+            self,
+            AlertNotifier::Webhook {
+                allow_remote: true,
+                ..
+            }
+        )
+    }
+
+    /// Announce that `view` matched `rule` at unix time `now`.
+    pub async fn notify(&self, rule: &AlertRule, view: &OrderView, now: i64) -> Result<()> {
+        match self {
+            AlertNotifier::Stdout => {
+                println!(
+                    "[{}] 🔔 {} ({}): {}",
+                    clock_text(now),
+                    rule.name,
+                    rule_description(rule),
+                    order_summary(view)
+                );
+                Ok(())
+            }
+            AlertNotifier::Exec(command) => {
+                let json = alert_payload(rule, view, now).to_string();
+                let env = [
+                    ("MOSTRO_ALERT", json.clone()),
+                    ("MOSTRO_ALERT_RULE", rule.name.clone()),
+                    (
+                        "MOSTRO_ORDER_ID",
+                        view.order.id.map(|id| id.to_string()).unwrap_or_default(),
+                    ),
+                ];
+                run_hook(command, &env, &json, Some(ALERT_HOOK_TIMEOUT))
+                    .await
+                    .map_err(|e| anyhow!("Alert hook failed: {}", e))
+            }
+            AlertNotifier::Webhook { url, .. } => {
+                let client = reqwest::Client::builder()
+                    .timeout(ALERT_HOOK_TIMEOUT)
+                    .build()?;
+                let response = client
+                    .post(url)
+                    .json(&alert_payload(rule, view, now))
+                    .send()
+                    .await
+                    .map_err(|e| anyhow!("Could not reach webhook {}: {}", url, e))?;
+                let status = response.status();
+                if !status.is_success() {
+                    return Err(anyhow!("Webhook {} returned HTTP {}", url, status));
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Rule-based order taking for `autotake`.
+//!
+//! [`AutoTakeRules`] decide which pending orders to take and for how much
+//! fiat. The [`AutoTakeLedger`] of past takes (the `autotakes` table) keeps
+//! the bot within its [`TradeLimits`] on open trades and daily volume, and
+//! follows each taken order until its trade is over.
+
+use mostro_core::prelude::*;
+use uuid::Uuid;
+
+use crate::cli::take_order::MakerRequirements;
+use crate::db::AutoTake;
+use crate::fiat::{FiatAmount, FiatRange};
+use crate::nip33::OrderView;
+use crate::parser::orders::OrderFilter;
+
+/// Span the daily volume limit is counted over, in seconds.
+pub const VOLUME_WINDOW_SECS: i64 = 86_400;
+
+/// Which orders to take.
+#[derive(Debug, Clone, Default)]
+pub struct AutoTakeRules {
+    /// Kind of the orders to take; both when unset.
+    pub kind: Option<mostro_core::order::Kind>,
+    pub fiat_code: String,
+    /// Fiat bounds of a take: fixed-amount orders must fall inside them,
+    /// range orders are taken for as much as both allow.
+    pub fiat: Option<FiatRange>,
+    /// Payment methods and premium bounds.
+    pub filter: OrderFilter,
+    pub maker: MakerRequirements,
+}
+
+/// How much of an order to take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TakeAmount {
+    /// Fiat traded.
+    pub fiat: FiatAmount,
+    /// Fiat amount sent with the take; only range orders need one.
+    pub amount: Option<u32>,
+}
+
+impl AutoTakeRules {
+    /// Whether `view` is a pending order these rules want at unix time `now`,
+    /// leaving the amount to [`AutoTakeRules::take_amount`].
+    pub fn matches(&self, view: &OrderView, now: i64) -> bool {
+        let order = &view.order;
+        order.status == Some(Status::Pending)
+            && self.kind.is_none_or(|k| order.kind == Some(k))
+            && order.fiat_code.eq_ignore_ascii_case(&self.fiat_code)
+            && self.filter.matches_view(view, now)
+            && self.maker.problems(view.maker_rating.as_ref()).is_empty()
+    }
+
+    /// Amount to take `view` for when at most `room` fiat may still be
+    /// traded today. Range orders are taken for the largest whole amount the
+    /// order, the rules and `room` allow; `None` when nothing fits.
+    pub fn take_amount(&self, view: &OrderView, room: Option<FiatAmount>) -> Option<TakeAmount> {
+        let range = view.fiat_range();
+        let rule_min = self.fiat.map(|f| f.min);
+        let rule_max = self.fiat.map(|f| f.max.unwrap_or(f.min));
+        match range.max {
+            None => {
+                let fiat = range.min;
+                let fits = rule_min.is_none_or(|min| fiat >= min)
+                    && rule_max.is_none_or(|max| fiat <= max)
+                    && room.is_none_or(|room| fiat <= room);
+                fits.then_some(TakeAmount { fiat, amount: None })
+            }
+            Some(max) => {
+                let high = [Some(max), rule_max, room]
+                    .into_iter()
+                    .flatten()
+                    .min()?
+                    .floor();
+                let low = [Some(range.min), rule_min]
+                    .into_iter()
+                    .flatten()
+                    .max()?
+                    .ceil();
+                if high < low.max(1) {
+                    return None;
+                }
+                Some(TakeAmount {
+                    fiat: FiatAmount::from_whole(high),
+                    amount: Some(u32::try_from(high).ok()?),
+                })
+            }
+        }
+    }
+}
+
+/// Bounds on how much the bot trades. Unset bounds are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TradeLimits {
+    /// Most trades in progress at once.
+    pub max_open: Option<usize>,
+    /// Most fiat taken in any [`VOLUME_WINDOW_SECS`].
+    pub daily_volume: Option<FiatAmount>,
+}
+
+/// Whether the trade of an order taken at `taken_at` is over, judging by
+/// the order's newest revision: it reached a final status, or it is pending
+/// again because the trade fell through and Mostro put it back in the book.
+pub fn trade_finished(view: &OrderView, taken_at: i64) -> bool {
+    match view.order.status {
+        Some(
+            Status::Success
+            | Status::Canceled
+            | Status::CanceledByAdmin
+            | Status::CooperativelyCanceled
+            | Status::SettledByAdmin
+            | Status::CompletedByAdmin
+            | Status::Expired,
+        ) => true,
+        Some(Status::Pending) => view.order.created_at.is_some_and(|at| at > taken_at),
+        _ => false,
+    }
+}
+
+/// Orders taken recently and trades still open.
+#[derive(Debug, Default)]
+pub struct AutoTakeLedger {
+    takes: Vec<AutoTake>,
+}
+
+impl AutoTakeLedger {
+    pub fn new(takes: Vec<AutoTake>) -> Self {
+        Self { takes }
+    }
+
+    pub fn takes(&self) -> &[AutoTake] {
+        &self.takes
+    }
+
+    pub fn contains(&self, order_id: &Uuid) -> bool {
+        let id = order_id.to_string();
+        self.takes.iter().any(|t| t.order_id == id)
+    }
+
+    /// Note that `view` was taken for `take` at `now`; the new entry.
+    pub fn record(&mut self, view: &OrderView, take: &TakeAmount, now: i64) -> AutoTake {
+        let order = &view.order;
+        let entry = AutoTake {
+            order_id: order.id.map(|id| id.to_string()).unwrap_or_default(),
+            kind: order.kind.map(|k| k.to_string()).unwrap_or_default(),
+            fiat_code: order.fiat_code.clone(),
+            fiat_amount: take.fiat.to_string(),
+            status: order
+                .status
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| Status::Pending.to_string()),
+            finished: false,
+            taken_at: now,
+        };
+        self.takes.push(entry.clone());
+        entry
+    }
+
+    /// Drop the take of `order_id`, once Mostro refused it.
+    pub fn forget(&mut self, order_id: &Uuid) {
+        let id = order_id.to_string();
+        self.takes.retain(|t| t.order_id != id);
+    }
+
+    /// Follow a taken order to its newest revision `view`. Returns the entry
+    /// when its status changed, so the caller can store it.
+    pub fn update(&mut self, view: &OrderView) -> Option<&AutoTake> {
+        let id = view.order.id?.to_string();
+        let status = view.order.status?.to_string();
+        let take = self
+            .takes
+            .iter_mut()
+            .find(|t| t.order_id == id && !t.finished)?;
+        let finished = trade_finished(view, take.taken_at);
+        if take.status == status && !finished {
+            return None;
+        }
+        take.status = status;
+        take.finished = finished;
+        Some(take)
+    }
+
+    /// Close the open take of `order_id` whose order event is gone from the
+    /// relays, keeping its last status. Returns the entry so the caller can
+    /// store it.
+    pub fn expire(&mut self, order_id: &Uuid) -> Option<&AutoTake> {
+        let id = order_id.to_string();
+        let take = self
+            .takes
+            .iter_mut()
+            .find(|t| t.order_id == id && !t.finished)?;
+        take.finished = true;
+        Some(take)
+    }
+
+    /// Trades taken and not yet over.
+    pub fn open_count(&self) -> usize {
+        self.takes.iter().filter(|t| !t.finished).count()
+    }
+
+    /// Fiat taken from unix time `since` on. Trades that fell through still
+    /// count: the limit caps what the bot commits to, not what settles.
+    pub fn volume_since(&self, since: i64) -> FiatAmount {
+        self.takes
+            .iter()
+            .filter(|t| t.taken_at >= since)
+            .filter_map(|t| FiatAmount::parse(&t.fiat_amount).ok())
+            .fold(FiatAmount::default(), |sum, a| sum.saturating_add(a))
+    }
+
+    /// Fiat that may still be taken at `now`; `None` without a volume limit.
+    pub fn room(&self, limits: &TradeLimits, now: i64) -> Option<FiatAmount> {
+        let limit = limits.daily_volume?;
+        Some(limit.saturating_sub(self.volume_since(now - VOLUME_WINDOW_SECS)))
+    }
+
+    /// Why nothing may be taken at `now`, if a limit is reached.
+    pub fn limit_reached(&self, limits: &TradeLimits, now: i64) -> Option<String> {
+        if let Some(max) = limits.max_open {
+            if self.open_count() >= max {
+                return Some(format!("{} trades open, the maximum", self.open_count()));
+            }
+        }
+        if let (Some(limit), Some(room)) = (limits.daily_volume, self.room(limits, now)) {
+            if room == FiatAmount::default() {
+                return Some(format!("daily volume of {} reached", limit));
+            }
+        }
+        None
+    }
+}
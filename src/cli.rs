@@ -1,27 +1,49 @@
 pub mod add_bond_invoice;
 pub mod add_invoice;
 pub mod adm_send_dm;
+pub mod alerts;
+pub mod autotake;
+pub mod bonds;
+pub mod book;
 pub mod conversation_key;
+pub mod decode_invoice;
 pub mod dm_to_user;
 pub mod get_dm;
 pub mod get_dm_user;
 pub mod last_trade_index;
 pub mod list_disputes;
 pub mod list_orders;
+pub mod maker;
 pub mod new_order;
+pub mod node_info;
+pub mod order_timeline;
 pub mod orders_info;
+pub mod price;
 pub mod rate_user;
 pub mod restore;
 pub mod send_admin_dm_attach;
 pub mod send_dm;
 pub mod send_msg;
+pub mod show_order;
 pub mod take_dispute;
 pub mod take_order;
+pub mod template;
+pub mod trade;
+pub mod wait_for;
 
+use crate::alerts::NotifierKind;
+use crate::autotake::{AutoTakeRules, TradeLimits};
 use crate::cli::add_bond_invoice::execute_add_bond_invoice;
 use crate::cli::add_invoice::execute_add_invoice;
 use crate::cli::adm_send_dm::execute_adm_send_dm;
+use crate::cli::alerts::{
+    execute_add_alert, execute_delete_alert, execute_list_alerts, execute_watch_alerts,
+};
+use crate::cli::autotake::execute_autotake;
+use crate::cli::bonds::execute_bonds;
+use crate::cli::book::execute_book;
 use crate::cli::conversation_key::execute_conversation_key;
+use crate::cli::decode_invoice::execute_decode_invoice;
 use crate::cli::dm_to_user::execute_dm_to_user;
 use crate::cli::get_dm::execute_get_dm;
 use crate::cli::get_dm_user::execute_get_dm_user;
@@ -30,18 +52,36 @@ use crate::cli::last_trade_index::{
 };
 use crate::cli::list_disputes::execute_list_disputes;
 use crate::cli::list_orders::execute_list_orders;
+use crate::cli::maker::{
+    execute_add_offer, execute_delete_offer, execute_list_offers, execute_maker,
+    execute_offer_history,
+};
 use crate::cli::new_order::execute_new_order;
+use crate::cli::node_info::execute_node_info;
+use crate::cli::order_timeline::execute_order_timeline;
 use crate::cli::orders_info::execute_orders_info;
+use crate::cli::price::execute_price;
 use crate::cli::rate_user::execute_rate_user;
 use crate::cli::restore::execute_restore;
 use crate::cli::send_admin_dm_attach::execute_send_admin_dm_attach;
 use crate::cli::send_dm::execute_send_dm;
+use crate::cli::show_order::execute_show_order;
 use crate::cli::take_dispute::execute_take_dispute;
-use crate::cli::take_order::execute_take_order;
+use crate::cli::take_order::{execute_take_order, MakerRequirements};
+use crate::cli::template::{
+    execute_delete_template, execute_list_templates, execute_save_template, resolve_new_order,
+    NewOrderTerms, OrderTerms,
+};
+use crate::cli::trade::execute_trade;
+use crate::cli::wait_for::{execute_wait_for, parse_action};
 use crate::db::{connect, User};
+use crate::fiat::{FiatAmount, FiatRange};
+use crate::lightning::LnurlOptions;
+use crate::parser::orders::{OrderFilter, OrderSort};
 use crate::util;
+use crate::util::NodeInfo;
 
-use anyhow::{Error, Result};
+use anyhow::Result;
 use clap::{Parser, Subcommand};
 use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
@@ -59,6 +99,39 @@ pub struct Context {
     pub pool: SqlitePool,
     pub context_keys: Option<Keys>,
     pub mostro_pubkey: PublicKey,
+    /// Node info event, fetched on first use by [`Context::node_info`].
+    pub node_info: tokio::sync::OnceCell<Option<NodeInfo>>,
+    /// Where invoices to pay are rendered as QR codes, resolved once from
+    /// `MOSTRO_QR`/`MOSTRO_QR_FILE` and the `getdm --qr`/`--qr-file` flags.
+    pub qr: util::QrOptions,
+}
+
+impl Context {
+    /// The Mostro node's kind-38385 instance info. Fetched once per run and
+    /// shared by every later caller; `None` when the node publishes none.
+    pub async fn node_info(&self) -> Option<&NodeInfo> {
+        self.node_info
+            .get_or_init(|| util::fetch_node_info_with(&self.client, self.mostro_pubkey))
+            .await
+            .as_ref()
+    }
+
+    /// This context with the next unused trade keys, for commands that start
+    /// several trades in one run. The node info fetched so far is kept.
+    pub async fn for_next_trade(&self) -> Result<Context> {
+        let (trade_keys, trade_index) = User::get_next_trade_keys(&self.pool).await?;
+        Ok(Context {
+            client: self.client.clone(),
+            identity_keys: self.identity_keys.clone(),
+            trade_keys,
+            trade_index,
+            pool: self.pool.clone(),
+            context_keys: self.context_keys.clone(),
+            mostro_pubkey: self.mostro_pubkey,
+            node_info: self.node_info.clone(),
+            qr: self.qr.clone(),
+        })
+    }
 }
 
 #[derive(Parser)]
@@ -113,10 +186,245 @@ pub enum Commands {
         /// Choose an order kind
         #[arg(short, long)]
         kind: Option<String>,
+        /// Only orders accepting any of these payment methods (comma separated)
+        #[arg(short = 'm', long, value_delimiter = ',')]
+        payment_method: Vec<String>,
+        /// Only orders that can be taken for this fiat amount
+        #[arg(short, long)]
+        fiat_amount: Option<FiatAmount>,
+        /// Minimum premium, in percent
+        #[arg(long, allow_hyphen_values = true)]
+        min_premium: Option<i64>,
+        /// Maximum premium, in percent
+        #[arg(long, allow_hyphen_values = true)]
+        max_premium: Option<i64>,
+        /// Minimum maker rating (0-5); unrated makers are excluded
+        #[arg(long, value_parser = check_rating)]
+        min_rating: Option<f64>,
+        /// Only orders published in the last N minutes
+        #[arg(long)]
+        max_age: Option<i64>,
+        /// Sort the results
+        #[arg(long, value_enum, default_value_t = OrderSort::Age)]
+        sort: OrderSort,
+        /// Reverse the sort order
+        #[arg(long)]
+        reverse: bool,
+        /// Show at most N orders
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// Start of the window: a duration back (2h, 30d), a date (2024-05-01) or @unix; default 7d
+        #[arg(long)]
+        since: Option<String>,
+        /// End of the window, same formats as --since; default now
+        #[arg(long)]
+        until: Option<String>,
+        /// Keep running and print orders as they are published, taken, canceled or expire
+        #[arg(long, conflicts_with = "until")]
+        follow: bool,
+    },
+    /// Show every detail of a public order: maker rating, expiration, network and all event tags
+    ShowOrder {
+        /// Order id
+        #[arg(short, long)]
+        order_id: Uuid,
+    },
+    /// Show pending orders as an order book: asks and bids per currency, best price first
+    Book {
+        /// Currency selected
+        #[arg(short, long)]
+        currency: Option<String>,
+        /// Only orders accepting any of these payment methods (comma separated)
+        #[arg(short = 'm', long, value_delimiter = ',')]
+        payment_method: Vec<String>,
+        /// Only orders that can be taken for this fiat amount
+        #[arg(short, long)]
+        fiat_amount: Option<FiatAmount>,
     },
+    /// Save an alert rule for orders you are waiting for
+    AddAlert {
+        /// Name shown when the alert fires
+        #[arg(short, long)]
+        name: String,
+        /// Order kind (buy or sell)
+        #[arg(short, long)]
+        kind: Option<String>,
+        /// Currency
+        #[arg(short, long)]
+        currency: Option<String>,
+        /// Orders accepting any of these payment methods (comma separated)
+        #[arg(short = 'm', long, value_delimiter = ',')]
+        payment_method: Vec<String>,
+        /// Fiat amount or range the order must allow, e.g. 50-500
+        #[arg(short, long)]
+        #[clap(value_parser=check_fiat_range)]
+        fiat_amount: Option<FiatRange>,
+        /// Minimum premium, in percent
+        #[arg(long, allow_hyphen_values = true)]
+        min_premium: Option<i64>,
+        /// Maximum premium, in percent
+        #[arg(long, allow_hyphen_values = true)]
+        max_premium: Option<i64>,
+        /// Minimum maker rating (0-5)
+        #[arg(long, value_parser = check_rating)]
+        min_rating: Option<f64>,
+        /// How to be notified
+        #[arg(long, value_enum, default_value_t = NotifierKind::Stdout)]
+        notify: NotifierKind,
+        /// Command to run (exec) or URL to POST to (webhook)
+        #[arg(long)]
+        target: Option<String>,
+        /// Let a webhook post to another host than this machine
+        #[arg(long)]
+        allow_remote: bool,
+    },
+    /// List saved alert rules
+    ListAlerts,
+    /// Delete an alert rule
+    DeleteAlert {
+        /// Alert id, from listalerts
+        #[arg(short, long)]
+        id: i64,
+    },
+    /// Listen for orders matching the saved alerts and notify them
+    WatchAlerts,
+    /// Take pending orders matching the rules, within trade limits, until Ctrl-C
+    AutoTake {
+        /// Currency of the orders to take
+        #[arg(short, long)]
+        currency: String,
+        /// Order kind to take (buy or sell); both when unset
+        #[arg(short, long)]
+        kind: Option<String>,
+        /// Only orders accepting any of these payment methods (comma separated)
+        #[arg(short = 'm', long, value_delimiter = ',')]
+        payment_method: Vec<String>,
+        /// Fiat amount or range to trade per order, e.g. 50-500
+        #[arg(short, long)]
+        #[clap(value_parser=check_fiat_range)]
+        fiat_amount: Option<FiatRange>,
+        /// Minimum premium, in percent
+        #[arg(long, allow_hyphen_values = true)]
+        min_premium: Option<i64>,
+        /// Maximum premium, in percent
+        #[arg(long, allow_hyphen_values = true)]
+        max_premium: Option<i64>,
+        /// Refuse makers rated below this (0-5); overrides MIN_MAKER_RATING
+        #[arg(long, value_parser = check_rating)]
+        min_rating: Option<f64>,
+        /// Refuse makers with fewer reviews; overrides MIN_MAKER_REVIEWS
+        #[arg(long)]
+        min_reviews: Option<u64>,
+        /// Most trades in progress at once
+        #[arg(long)]
+        max_trades: Option<usize>,
+        /// Most fiat to take in 24 hours
+        #[arg(long)]
+        daily_volume: Option<FiatAmount>,
+        /// Lightning address or LNURL receiving the sats of sell orders taken
+        #[arg(long)]
+        lightning_address: Option<String>,
+        /// Shell command paying hold and bond invoices; gets MOSTRO_INVOICE
+        #[arg(long)]
+        pay_command: Option<String>,
+        /// Only print the orders that would be taken
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Save a standing offer for the maker daemon to keep published
+    AddOffer {
+        /// Name to recognize the offer by
+        #[arg(short, long)]
+        name: String,
+        /// Order kind (buy or sell)
+        #[arg(short, long)]
+        kind: String,
+        /// Currency of the orders
+        #[arg(short = 'c', long)]
+        fiat_code: String,
+        /// Fiat amount or range, e.g. 50-500
+        #[arg(short, long)]
+        #[clap(value_parser=check_fiat_range)]
+        fiat_amount: FiatRange,
+        /// Payment methods (comma separated)
+        #[arg(short = 'm', long)]
+        payment_method: String,
+        /// Premium on the market price, in percent
+        #[arg(short, long)]
+        #[clap(default_value_t = 0)]
+        #[clap(allow_hyphen_values = true)]
+        premium: i64,
+        /// Publish a fixed sats amount computed from the current price
+        #[arg(long)]
+        fixed_price: bool,
+        /// Republish a fixed-price order when the price moves this many percent
+        #[arg(long, requires = "fixed_price")]
+        reprice_threshold: Option<f64>,
+        /// Expiration time of each pending order, in days
+        #[arg(short, long)]
+        #[clap(default_value_t = 0)]
+        expiration_days: i64,
+        /// Lightning address or LNURL receiving the sats of buy orders
+        #[arg(short, long)]
+        invoice: Option<String>,
+    },
+    /// List the standing offers and their live orders
+    ListOffers,
+    /// Delete a standing offer; its live order stays published
+    DeleteOffer {
+        /// Offer id
+        #[arg(short, long)]
+        id: i64,
+    },
+    /// Show every order published for a standing offer
+    OfferHistory {
+        /// Offer id
+        #[arg(short, long)]
+        id: i64,
+    },
+    /// Keep the standing offers published until Ctrl-C
+    Maker,
     /// Create a new buy/sell order on Mostro
     NewOrder {
+        /// Start from a saved template; the other flags override its fields
+        #[arg(short, long)]
+        template: Option<String>,
         /// Choose an order kind
+        #[arg(short, long, required_unless_present = "template")]
+        kind: Option<String>,
+        /// Sats amount - leave empty for market price
+        #[arg(short, long)]
+        amount: Option<i64>,
+        /// Currency selected
+        #[arg(short = 'c', long, required_unless_present = "template")]
+        fiat_code: Option<String>,
+        /// Fiat amount
+        #[arg(short, long, required_unless_present = "template")]
+        #[clap(value_parser=check_fiat_range)]
+        fiat_amount: Option<FiatRange>,
+        /// Payment method
+        #[arg(short = 'm', long, required_unless_present = "template")]
+        payment_method: Option<String>,
+        /// Premium on price
+        #[arg(short, long)]
+        #[clap(allow_hyphen_values = true)]
+        premium: Option<i64>,
+        /// Invoice string
+        #[arg(short, long)]
+        invoice: Option<String>,
+        /// Expiration time of a pending Order, in days
+        #[arg(short, long)]
+        expiration_days: Option<i64>,
+        /// Send the order without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Save the flags of an order as a named template for neworder --template
+    SaveTemplate {
+        /// Template name; saving an existing name replaces it
+        #[arg(short, long)]
+        name: String,
+        /// Order kind (buy or sell)
         #[arg(short, long)]
         kind: String,
         /// Sats amount - leave empty for market price
@@ -126,11 +434,11 @@ pub enum Commands {
         /// Currency selected
         #[arg(short = 'c', long)]
         fiat_code: String,
-        /// Fiat amount
+        /// Fiat amount or range
         #[arg(short, long)]
         #[clap(value_parser=check_fiat_range)]
-        fiat_amount: (i64, Option<i64>),
-        /// Payment method
+        fiat_amount: FiatRange,
+        /// Payment methods (comma separated)
         #[arg(short = 'm', long)]
         payment_method: String,
         /// Premium on price
@@ -138,7 +446,7 @@ pub enum Commands {
         #[clap(default_value_t = 0)]
         #[clap(allow_hyphen_values = true)]
         premium: i64,
-        /// Invoice string
+        /// Lightning address or LNURL receiving the sats of buy orders
         #[arg(short, long)]
         invoice: Option<String>,
         /// Expiration time of a pending Order, in days
@@ -146,6 +454,14 @@ pub enum Commands {
         #[clap(default_value_t = 0)]
         expiration_days: i64,
     },
+    /// List the saved order templates
+    ListTemplates,
+    /// Delete a saved order template
+    DeleteTemplate {
+        /// Template name
+        #[arg(short, long)]
+        name: String,
+    },
     /// Take a sell order from a Mostro pubkey
     TakeSell {
         /// Order id
@@ -157,6 +473,18 @@ pub enum Commands {
         /// Amount of fiat to buy
         #[arg(short, long)]
         amount: Option<u32>,
+        /// Resolve a Lightning address or LNURL to a bolt11 before sending it
+        #[arg(long)]
+        fetch_invoice: bool,
+        /// Comment for the LNURL service when fetching a bolt11
+        #[arg(long, requires = "fetch_invoice")]
+        comment: Option<String>,
+        /// Refuse makers rated below this (0-5); overrides MIN_MAKER_RATING
+        #[arg(long, value_parser = check_rating)]
+        min_rating: Option<f64>,
+        /// Refuse makers with fewer reviews; overrides MIN_MAKER_REVIEWS
+        #[arg(long)]
+        min_reviews: Option<u64>,
     },
     /// Take a buy order from a Mostro pubkey
     TakeBuy {
@@ -166,6 +494,12 @@ pub enum Commands {
         /// Amount of fiat to sell
         #[arg(short, long)]
         amount: Option<u32>,
+        /// Refuse makers rated below this (0-5); overrides MIN_MAKER_RATING
+        #[arg(long, value_parser = check_rating)]
+        min_rating: Option<f64>,
+        /// Refuse makers with fewer reviews; overrides MIN_MAKER_REVIEWS
+        #[arg(long)]
+        min_reviews: Option<u64>,
     },
     /// Buyer add a new invoice to receive the payment
     AddInvoice {
@@ -175,6 +509,12 @@ pub enum Commands {
         /// Invoice string
         #[arg(short, long)]
         invoice: String,
+        /// Resolve a Lightning address or LNURL to a bolt11 before sending it
+        #[arg(long)]
+        fetch_invoice: bool,
+        /// Comment for the LNURL service when fetching a bolt11
+        #[arg(long, requires = "fetch_invoice")]
+        comment: Option<String>,
     },
     /// Reply to a bond payout request with an invoice for your share of a slashed bond
     AddBondInvoice {
@@ -185,6 +525,39 @@ pub enum Commands {
         #[arg(short, long)]
         invoice: String,
     },
+    /// Show bitcoin prices from the price oracle, optionally converting amounts
+    Price {
+        /// Currency code; lists every currency when omitted
+        #[arg(short, long)]
+        currency: Option<String>,
+        /// Fiat amount to convert to sats
+        #[arg(short, long, requires = "currency")]
+        fiat_amount: Option<f64>,
+        /// Sats amount to convert to fiat
+        #[arg(short, long, requires = "currency")]
+        sats: Option<i64>,
+        /// Ignore cached prices younger than PRICE_CACHE_TTL
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Show the limits, fees and settings the Mostro node publishes
+    NodeInfo {},
+    /// List bonds you posted or are owed, with claim deadlines
+    Bonds {
+        /// Since time of the bond messages to sync, in minutes
+        #[arg(short, long)]
+        #[clap(default_value_t = 43200)]
+        since: i64,
+    },
+    /// Decode a bolt11 invoice, optionally cross-checking it against a local order
+    DecodeInvoice {
+        /// Invoice string
+        #[arg(short, long)]
+        invoice: String,
+        /// Order id to cross-check the invoice against
+        #[arg(short, long)]
+        order_id: Option<Uuid>,
+    },
     /// Get the latest direct messages
     GetDm {
         /// Since time of the messages in minutes
@@ -194,6 +567,12 @@ pub enum Commands {
         /// If true, get messages from counterparty, otherwise from Mostro
         #[arg(short, long)]
         from_user: bool,
+        /// Show invoices to pay as QR codes in the terminal
+        #[arg(long)]
+        qr: bool,
+        /// Also write the invoice QR code to a .png or .svg file
+        #[arg(long)]
+        qr_file: Option<std::path::PathBuf>,
     },
     /// Get direct messages sent to any trade keys
     GetDmUser {
@@ -277,6 +656,34 @@ pub enum Commands {
         #[arg(short, long)]
         order_id: Uuid,
     },
+    /// Walk one order through the trade, prompting for each next step
+    Trade {
+        /// Order id
+        #[arg(short, long)]
+        order_id: Uuid,
+    },
+    /// Wait until Mostro sends one of the given actions for an order, and print it as JSON
+    WaitFor {
+        /// Order id
+        #[arg(short, long)]
+        order_id: Uuid,
+        /// Actions to wait for, comma separated (e.g. hold-invoice-payment-accepted)
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        #[clap(value_parser = parse_action)]
+        action: Vec<Action>,
+        /// Seconds to wait before giving up; 0 waits forever
+        #[arg(long, default_value_t = 300)]
+        timeout: u64,
+        /// Also accept messages received in the last N minutes
+        #[arg(long, default_value_t = 0)]
+        since: i64,
+    },
+    /// Show every protocol message sent or received for an order
+    OrderTimeline {
+        /// Order id
+        #[arg(short, long)]
+        order_id: Uuid,
+    },
     /// Cancel an order (only admin)
     AdmCancel {
         /// Order id
@@ -302,7 +709,14 @@ pub enum Commands {
         slash_buyer: bool,
     },
     /// Requests open disputes from Mostro pubkey
-    ListDisputes {},
+    ListDisputes {
+        /// Start of the window: a duration back (2h, 30d), a date (2024-05-01) or @unix; default 7d
+        #[arg(long)]
+        since: Option<String>,
+        /// End of the window, same formats as --since; default now
+        #[arg(long)]
+        until: Option<String>,
+    },
     /// Add a new dispute's solver (only admin)
     AdmAddSolver {
         /// npubkey
@@ -382,36 +796,20 @@ fn get_env_var(cli: &Cli) {
     }
 }
 
-// Check range with two values value
-fn check_fiat_range(s: &str) -> Result<(i64, Option<i64>)> {
-    if s.contains('-') {
-        // Get values from CLI
-        let values: Vec<&str> = s.split('-').collect();
-
-        // Check if more than two values
-        if values.len() > 2 {
-            return Err(Error::msg("Wrong amount syntax"));
-        };
-
-        // Get ranged command
-        let min = values[0]
-            .parse::<i64>()
-            .map_err(|e| anyhow::anyhow!("Invalid min value: {}", e))?;
-        let max = values[1]
-            .parse::<i64>()
-            .map_err(|e| anyhow::anyhow!("Invalid max value: {}", e))?;
-
-        // Check min below max
-        if min >= max {
-            return Err(Error::msg("Range of values must be 100-200 for example..."));
-        };
-        Ok((min, Some(max)))
-    } else {
-        match s.parse::<i64>() {
-            Ok(s) => Ok((s, None)),
-            Err(e) => Err(e.into()),
-        }
+// Check range with two values value; amounts may have decimals
+fn check_fiat_range(s: &str) -> Result<FiatRange> {
+    FiatRange::parse(s)
+}
+
+// Maker ratings go from 0 to 5; NaN and negatives are refused
+fn check_rating(s: &str) -> Result<f64> {
+    let rating: f64 = s
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid rating '{}'", s))?;
+    if !(0.0..=5.0).contains(&rating) {
+        return Err(anyhow::anyhow!("Rating must be between 0 and 5"));
     }
+    Ok(rating)
 }
 
 pub async fn run() -> Result<()> {
@@ -514,9 +912,24 @@ async fn init_context(cli: &Cli) -> Result<Context> {
         pool,
         context_keys,
         mostro_pubkey,
+        node_info: Default::default(),
+        qr: resolve_qr_options(cli.command.as_ref()),
     })
 }
 
+/// QR output for the run: `MOSTRO_QR`/`MOSTRO_QR_FILE`, with the `getdm --qr`/`--qr-file`
+/// flags switching it on for that command.
+fn resolve_qr_options(command: Option<&Commands>) -> util::QrOptions {
+    let env = util::QrOptions::from_env();
+    match command {
+        Some(Commands::GetDm { qr, qr_file, .. }) => util::QrOptions {
+            terminal: *qr || env.terminal,
+            file: qr_file.clone().or(env.file),
+        },
+        _ => env,
+    }
+}
+
 /// Resolve the wire transport into the `TRANSPORT` env var the messaging layer
 /// reads (`parse_transport_env`). An explicit `--transport` / `TRANSPORT` is
 /// authoritative and skips the network probe; otherwise the node's advertised
@@ -575,6 +988,14 @@ impl Commands {
             | Commands::Cancel { order_id } => {
                 crate::util::run_simple_order_msg(self.clone(), Some(*order_id), ctx).await
             }
+            Commands::Trade { order_id } => execute_trade(order_id, ctx).await,
+            Commands::WaitFor {
+                order_id,
+                action,
+                timeout,
+                since,
+            } => execute_wait_for(order_id, action, *timeout, *since, ctx).await,
+            Commands::OrderTimeline { order_id } => execute_order_timeline(order_id, ctx).await,
             // Last trade index commands
             Commands::GetLastTradeIndex {} => {
                 execute_last_trade_index(&ctx.identity_keys, ctx.mostro_pubkey, ctx).await
@@ -625,61 +1046,311 @@ impl Commands {
                 status,
                 currency,
                 kind,
-            } => execute_list_orders(kind, currency, status, ctx).await,
-            Commands::NewOrder {
+                payment_method,
+                fiat_amount,
+                min_premium,
+                max_premium,
+                min_rating,
+                max_age,
+                sort,
+                reverse,
+                limit,
+                since,
+                until,
+                follow,
+            } => {
+                let window = util::HistoryWindow::parse(
+                    since.as_deref(),
+                    until.as_deref(),
+                    chrono::Utc::now().timestamp(),
+                )?;
+                let filter = OrderFilter {
+                    payment_methods: payment_method.clone(),
+                    fiat_amount: *fiat_amount,
+                    min_premium: *min_premium,
+                    max_premium: *max_premium,
+                    min_rating: *min_rating,
+                    max_age_secs: max_age.map(|minutes| minutes * 60),
+                };
+                execute_list_orders(
+                    kind, currency, status, &filter, *sort, *reverse, *limit, &window, *follow, ctx,
+                )
+                .await
+            }
+            Commands::ShowOrder { order_id } => execute_show_order(order_id, ctx).await,
+            Commands::Book {
+                currency,
+                payment_method,
+                fiat_amount,
+            } => execute_book(currency, payment_method, fiat_amount, ctx).await,
+            Commands::AddAlert {
+                name,
+                kind,
+                currency,
+                payment_method,
+                fiat_amount,
+                min_premium,
+                max_premium,
+                min_rating,
+                notify,
+                target,
+                allow_remote,
+            } => {
+                execute_add_alert(
+                    name,
+                    kind,
+                    currency,
+                    payment_method,
+                    fiat_amount,
+                    *min_premium,
+                    *max_premium,
+                    *min_rating,
+                    *notify,
+                    target,
+                    *allow_remote,
+                    ctx,
+                )
+                .await
+            }
+            Commands::ListAlerts => execute_list_alerts(ctx).await,
+            Commands::DeleteAlert { id } => execute_delete_alert(*id, ctx).await,
+            Commands::WatchAlerts => execute_watch_alerts(ctx).await,
+            Commands::AutoTake {
+                currency,
+                kind,
+                payment_method,
+                fiat_amount,
+                min_premium,
+                max_premium,
+                min_rating,
+                min_reviews,
+                max_trades,
+                daily_volume,
+                lightning_address,
+                pay_command,
+                dry_run,
+            } => {
+                let kind = kind
+                    .as_deref()
+                    .map(|k| {
+                        mostro_core::order::Kind::from_str(k)
+                            .map_err(|e| anyhow::anyhow!("Not valid order kind '{}': {:?}", k, e))
+                    })
+                    .transpose()?;
+                let rules = AutoTakeRules {
+                    kind,
+                    fiat_code: currency.to_uppercase(),
+                    fiat: *fiat_amount,
+                    filter: OrderFilter {
+                        payment_methods: payment_method.clone(),
+                        min_premium: *min_premium,
+                        max_premium: *max_premium,
+                        ..Default::default()
+                    },
+                    maker: MakerRequirements::from_env().with_overrides(*min_rating, *min_reviews),
+                };
+                let limits = TradeLimits {
+                    max_open: *max_trades,
+                    daily_volume: *daily_volume,
+                };
+                execute_autotake(
+                    &rules,
+                    &limits,
+                    lightning_address,
+                    pay_command,
+                    *dry_run,
+                    ctx,
+                )
+                .await
+            }
+            Commands::AddOffer {
+                name,
                 kind,
                 fiat_code,
-                amount,
                 fiat_amount,
                 payment_method,
                 premium,
-                invoice,
+                fixed_price,
+                reprice_threshold,
                 expiration_days,
+                invoice,
             } => {
-                execute_new_order(
+                execute_add_offer(
+                    name,
                     kind,
                     fiat_code,
                     fiat_amount,
-                    amount,
                     payment_method,
-                    premium,
+                    *premium,
+                    *fixed_price,
+                    *reprice_threshold,
+                    *expiration_days,
                     invoice,
                     ctx,
-                    expiration_days,
                 )
                 .await
             }
+            Commands::ListOffers => execute_list_offers(ctx).await,
+            Commands::DeleteOffer { id } => execute_delete_offer(*id, ctx).await,
+            Commands::OfferHistory { id } => execute_offer_history(*id, ctx).await,
+            Commands::Maker => execute_maker(ctx).await,
+            Commands::NewOrder {
+                template,
+                kind,
+                fiat_code,
+                amount,
+                fiat_amount,
+                payment_method,
+                premium,
+                invoice,
+                expiration_days,
+                yes,
+            } => {
+                let terms = OrderTerms {
+                    kind: kind.clone(),
+                    fiat_code: fiat_code.clone(),
+                    fiat_amount: *fiat_amount,
+                    amount: *amount,
+                    payment_method: payment_method.clone(),
+                    premium: *premium,
+                    invoice: invoice.clone(),
+                    expiration_days: *expiration_days,
+                };
+                let terms = resolve_new_order(template.as_deref(), terms, ctx).await?;
+                execute_new_order(
+                    &terms.kind,
+                    &terms.fiat_code,
+                    &terms.fiat_amount,
+                    &terms.amount,
+                    &terms.payment_method,
+                    &terms.premium,
+                    &terms.invoice,
+                    ctx,
+                    &terms.expiration_days,
+                    *yes,
+                )
+                .await
+            }
+            Commands::SaveTemplate {
+                name,
+                kind,
+                amount,
+                fiat_code,
+                fiat_amount,
+                payment_method,
+                premium,
+                invoice,
+                expiration_days,
+            } => {
+                let terms = NewOrderTerms {
+                    kind: kind.clone(),
+                    fiat_code: fiat_code.clone(),
+                    fiat_amount: *fiat_amount,
+                    amount: *amount,
+                    payment_method: payment_method.clone(),
+                    premium: *premium,
+                    invoice: invoice.clone(),
+                    expiration_days: *expiration_days,
+                };
+                execute_save_template(name, terms, ctx).await
+            }
+            Commands::ListTemplates => execute_list_templates(ctx).await,
+            Commands::DeleteTemplate { name } => execute_delete_template(name, ctx).await,
             Commands::TakeSell {
                 order_id,
                 invoice,
                 amount,
-            } => execute_take_order(order_id, Action::TakeSell, invoice, *amount, ctx).await,
-            Commands::TakeBuy { order_id, amount } => {
-                execute_take_order(order_id, Action::TakeBuy, &None, *amount, ctx).await
+                fetch_invoice,
+                comment,
+                min_rating,
+                min_reviews,
+            } => {
+                let lnurl = LnurlOptions {
+                    fetch_invoice: *fetch_invoice,
+                    comment: comment.clone(),
+                };
+                let maker = MakerRequirements::from_env().with_overrides(*min_rating, *min_reviews);
+                execute_take_order(
+                    order_id,
+                    Action::TakeSell,
+                    invoice,
+                    *amount,
+                    &lnurl,
+                    &maker,
+                    ctx,
+                )
+                .await
+                .map(|_| ())
             }
-            Commands::AddInvoice { order_id, invoice } => {
-                execute_add_invoice(order_id, invoice, ctx).await
+            Commands::TakeBuy {
+                order_id,
+                amount,
+                min_rating,
+                min_reviews,
+            } => {
+                let maker = MakerRequirements::from_env().with_overrides(*min_rating, *min_reviews);
+                execute_take_order(
+                    order_id,
+                    Action::TakeBuy,
+                    &None,
+                    *amount,
+                    &LnurlOptions::default(),
+                    &maker,
+                    ctx,
+                )
+                .await
+                .map(|_| ())
+            }
+            Commands::AddInvoice {
+                order_id,
+                invoice,
+                fetch_invoice,
+                comment,
+            } => {
+                let lnurl = LnurlOptions {
+                    fetch_invoice: *fetch_invoice,
+                    comment: comment.clone(),
+                };
+                execute_add_invoice(order_id, invoice, &lnurl, ctx).await
             }
             Commands::AddBondInvoice { order_id, invoice } => {
                 execute_add_bond_invoice(order_id, invoice, ctx).await
             }
             Commands::Rate { order_id, rating } => execute_rate_user(order_id, rating, ctx).await,
+            Commands::Price {
+                currency,
+                fiat_amount,
+                sats,
+                refresh,
+            } => execute_price(currency, fiat_amount, sats, *refresh, ctx).await,
+            Commands::NodeInfo {} => execute_node_info(ctx).await,
+            Commands::Bonds { since } => execute_bonds(since, ctx).await,
+            Commands::DecodeInvoice { invoice, order_id } => {
+                execute_decode_invoice(invoice, order_id, ctx).await
+            }
 
             // DM retrieval commands
-            Commands::GetDm { since, from_user } => {
-                execute_get_dm(since, false, from_user, ctx).await
-            }
+            Commands::GetDm {
+                since, from_user, ..
+            } => execute_get_dm(since, false, from_user, &ctx.qr, ctx).await,
             Commands::GetDmUser {
                 pubkey,
                 order_id,
                 since,
             } => execute_get_dm_user(PublicKey::from_str(pubkey)?, *order_id, since, ctx).await,
             Commands::GetAdminDm { since, from_user } => {
-                execute_get_dm(since, true, from_user, ctx).await
+                execute_get_dm(since, true, from_user, &ctx.qr, ctx).await
             }
 
             // Admin commands
-            Commands::ListDisputes {} => execute_list_disputes(ctx).await,
+            Commands::ListDisputes { since, until } => {
+                let window = util::HistoryWindow::parse(
+                    since.as_deref(),
+                    until.as_deref(),
+                    chrono::Utc::now().timestamp(),
+                )?;
+                execute_list_disputes(&window, ctx).await
+            }
             Commands::AdmAddSolver { npubkey } => execute_admin_add_solver(npubkey, ctx).await,
             Commands::AdmSettle {
                 order_id,
@@ -1,8 +1,9 @@
+use crate::db::{Bond, BondRole, BondStatus, Order};
 use crate::parser::common::{
     create_emoji_field_row, create_field_value_header, create_standard_table,
 };
-use crate::util::{print_dm_events, send_dm, wait_for_dm, WaitForDmTimeout};
-use crate::{cli::Context, db::Order, lightning::is_valid_invoice};
+use crate::util::{print_dm_events, send_dm, wait_for_dm, NodeInfo, WaitForDmTimeout};
+use crate::{cli::Context, lightning::validate_invoice};
 use anyhow::Result;
 use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
@@ -52,10 +53,25 @@ pub async fn execute_add_bond_invoice(order_id: &Uuid, invoice: &str, ctx: &Cont
     // Lightning Addresses are not accepted here (the protocol's "Bond payout
     // invoice" reply is a bolt11): validate locally so a bad input fails fast
     // instead of bouncing back as a `cant-do` / `invalid-invoice` from Mostro.
-    let invoice = is_valid_invoice(invoice)
+    // The node's network and expiry window apply as for any payout invoice,
+    // but not its minimum order amount: a bond share can be smaller.
+    // The requested share is known once the payout request was recorded in
+    // the `bonds` table.
+    let owed = Bond::get(&ctx.pool, &order_id.to_string(), BondRole::Owed).await?;
+    let requirements = ctx
+        .node_info()
+        .await
+        .map(NodeInfo::bond_payout_requirements)
+        .unwrap_or_default()
+        .with_amount(
+            owed.as_ref()
+                .filter(|b| b.amount > 0)
+                .map(|b| b.amount as u64),
+        );
+    let invoice = validate_invoice(invoice, &requirements)
         .map_err(|e| anyhow::anyhow!("Invalid invoice: {}", e))?
         .to_string();
-    let payload = Payload::PaymentRequest(None, invoice, None);
+    let payload = Payload::PaymentRequest(None, invoice.clone(), None);
 
     // Create request id
     let request_id = Uuid::new_v4().as_u128() as u64;
@@ -82,6 +98,7 @@ pub async fn execute_add_bond_invoice(order_id: &Uuid, invoice: &str, ctx: &Cont
         message_json,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for a possible reply. On success Mostro pays the invoice from its
@@ -102,5 +119,20 @@ pub async fn execute_add_bond_invoice(order_id: &Uuid, invoice: &str, ctx: &Cont
         Err(e) => return Err(e),
     }
 
+    let mut bond = owed.unwrap_or_else(|| {
+        Bond::new(
+            &order_id.to_string(),
+            BondRole::Owed,
+            BondStatus::Claimed,
+            0,
+        )
+    });
+    if bond.status() != Some(BondStatus::Paid) {
+        bond.status = BondStatus::Claimed.to_string();
+    }
+    bond.payout_invoice = Some(invoice);
+    bond.updated_at = chrono::Utc::now().timestamp();
+    bond.upsert(&ctx.pool).await?;
+
     Ok(())
 }
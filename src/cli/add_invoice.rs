@@ -1,18 +1,51 @@
+use crate::lightning::{
+    expected_buyer_amount, resolve_payment_request, InvoiceRequirements, LnurlOptions,
+};
 use crate::parser::common::{
     create_emoji_field_row, create_field_value_header, create_standard_table,
 };
-use crate::util::{print_dm_events, send_dm, wait_for_dm};
-use crate::{cli::Context, db::Order, lightning::is_valid_invoice};
+use crate::trade::check_order_action;
+use crate::util::{print_dm_events, send_dm, wait_for_dm, NodeInfo};
+use crate::{cli::Context, db::Order};
 use anyhow::Result;
-use lnurl::lightning_address::LightningAddress;
 use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
-use std::str::FromStr;
 use uuid::Uuid;
 
-pub async fn execute_add_invoice(order_id: &Uuid, invoice: &str, ctx: &Context) -> Result<()> {
+/// Terms the buyer invoice for a locally stored `order` must meet.
+///
+/// Mostro's `add-invoice` request already carries the buyer's net amount and
+/// is persisted with the `waiting-buyer-invoice` status; any other stored
+/// amount is the gross order amount and still has the buyer's half of the
+/// Mostro fee to come off. Market-price orders (`amount == 0`) skip the
+/// amount check.
+fn buyer_invoice_requirements(order: &Order, node_info: Option<&NodeInfo>) -> InvoiceRequirements {
+    let requirements = node_info
+        .map(NodeInfo::invoice_requirements)
+        .unwrap_or_default();
+    if order.amount <= 0 {
+        return requirements;
+    }
+    let net_amount_stored = order.status() == Some(Status::WaitingBuyerInvoice);
+    let expected = if net_amount_stored {
+        Some(order.amount as u64)
+    } else {
+        node_info
+            .and_then(|info| info.fee)
+            .map(|fee| expected_buyer_amount(order.amount, fee))
+    };
+    requirements.with_amount(expected)
+}
+
+pub async fn execute_add_invoice(
+    order_id: &Uuid,
+    invoice: &str,
+    lnurl: &LnurlOptions,
+    ctx: &Context,
+) -> Result<()> {
     // Get order from order id
     let order = Order::get_by_id(&ctx.pool, &order_id.to_string()).await?;
+    check_order_action(&order, &Action::AddInvoice, ctx).await?;
     // Get trade keys of specific order
     let trade_keys = order
         .trade_keys
@@ -43,18 +76,11 @@ pub async fn execute_add_invoice(order_id: &Uuid, invoice: &str, ctx: &Context)
     ));
     println!("{table}");
     println!("💡 Sending lightning invoice to Mostro...\n");
-    // Parse invoice (Lightning address or BOLT11) and build payload
-    let ln_addr = LightningAddress::from_str(invoice);
-    let payload = if ln_addr.is_ok() {
-        Some(Payload::PaymentRequest(None, invoice.to_string(), None))
-    } else {
-        match is_valid_invoice(invoice) {
-            Ok(i) => Some(Payload::PaymentRequest(None, i.to_string(), None)),
-            Err(e) => {
-                return Err(anyhow::anyhow!("Invalid invoice: {}", e));
-            }
-        }
-    };
+    // Check the bolt11, or resolve the Lightning address / LNURL, against the
+    // order and node terms before building the payload
+    let requirements = buyer_invoice_requirements(&order, ctx.node_info().await);
+    let payment_request = resolve_payment_request(invoice, &requirements, lnurl).await?;
+    let payload = Some(Payload::PaymentRequest(None, payment_request, None));
 
     // Create request id
     let request_id = Uuid::new_v4().as_u128() as u64;
@@ -81,6 +107,7 @@ pub async fn execute_add_invoice(order_id: &Uuid, invoice: &str, ctx: &Context)
         message_json,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for the DM to be sent from mostro
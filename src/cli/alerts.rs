@@ -0,0 +1,142 @@
+use crate::alerts::{new_alert_rule, rule_description, rule_matches, AlertNotifier, NotifierKind};
+use crate::cli::Context;
+use crate::db::{alert_hit_exists, record_alert_hit, AlertRule};
+use crate::fiat::FiatRange;
+use crate::nip33::OrderView;
+use crate::parser::alerts::print_alerts_table;
+use crate::parser::common::{print_key_value, print_section_header, print_success_message};
+use crate::parser::order_feed::{OrderChangeKind, OrderFeed};
+use crate::parser::orders::OrderFilter;
+use crate::util::{fetch_order_events, HistoryWindow, OrderStream};
+use anyhow::Result;
+use mostro_core::prelude::*;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_add_alert(
+    name: &str,
+    kind: &Option<String>,
+    currency: &Option<String>,
+    payment_methods: &[String],
+    fiat_amount: &Option<FiatRange>,
+    min_premium: Option<i64>,
+    max_premium: Option<i64>,
+    min_rating: Option<f64>,
+    notify: NotifierKind,
+    target: &Option<String>,
+    allow_remote: bool,
+    ctx: &Context,
+) -> Result<()> {
+    let notifier = AlertNotifier::new(notify, target.as_deref(), allow_remote)?;
+    let rule = new_alert_rule(
+        name,
+        kind.as_deref(),
+        currency.as_deref(),
+        payment_methods,
+        fiat_amount.as_ref(),
+        min_premium,
+        max_premium,
+        min_rating,
+        &notifier,
+    )?
+    .insert(&ctx.pool)
+    .await?;
+
+    print_section_header("🔔 New Alert");
+    print_key_value("🆔", "Id", &rule.id.to_string());
+    print_key_value("🏷️", "Name", &rule.name);
+    print_key_value("🔎", "Looking For", &rule_description(&rule));
+    print_key_value("📣", "Notify", &notifier.kind().to_string());
+    if let Some(target) = notifier.target() {
+        print_key_value("🎯", "Target", target);
+    }
+    println!();
+    print_success_message("Alert saved. Run `mostro-cli watchalerts` to start listening.");
+    Ok(())
+}
+
+pub async fn execute_list_alerts(ctx: &Context) -> Result<()> {
+    print_section_header("🔔 Alerts");
+    let rules = AlertRule::get_all(&ctx.pool).await?;
+    println!("{}", print_alerts_table(&rules));
+    Ok(())
+}
+
+pub async fn execute_delete_alert(id: i64, ctx: &Context) -> Result<()> {
+    if !AlertRule::delete_by_id(&ctx.pool, id).await? {
+        return Err(anyhow::anyhow!("No alert with id {}", id));
+    }
+    print_success_message(&format!("Alert {} deleted", id));
+    Ok(())
+}
+
+/// Notify every rule `view` matches and that has not fired for it yet.
+/// Rules are read again for every order, so rules added or deleted while
+/// watching take effect at once. A failed notification is retried the next
+/// time the order is updated.
+async fn check_rules(view: &OrderView, now: i64, ctx: &Context) -> Result<()> {
+    let Some(order_id) = view.order.id.map(|id| id.to_string()) else {
+        return Ok(());
+    };
+    for rule in AlertRule::get_all(&ctx.pool).await? {
+        if !rule_matches(&rule, view, now)
+            || alert_hit_exists(&ctx.pool, rule.id, &order_id).await?
+        {
+            continue;
+        }
+        let result = match AlertNotifier::for_rule(&rule) {
+            Ok(notifier) => notifier.notify(&rule, view, now).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                record_alert_hit(&ctx.pool, rule.id, &order_id).await?;
+            }
+            Err(e) => println!("⚠️  Alert '{}' could not notify: {}", rule.name, e),
+        }
+    }
+    Ok(())
+}
+
+pub async fn execute_watch_alerts(ctx: &Context) -> Result<()> {
+    let rules = AlertRule::get_all(&ctx.pool).await?;
+    if rules.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No alert rules yet, add one with `mostro-cli addalert`"
+        ));
+    }
+
+    print_section_header("🔔 Watching Alerts");
+    print_key_value("📋", "Rules", &rules.len().to_string());
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("💡", "Stop", "press Ctrl-C");
+    println!();
+
+    // Orders already in the book are checked too: each order fires once per
+    // rule, however many times the listener is restarted
+    let now = chrono::Utc::now().timestamp();
+    let events = fetch_order_events(ctx, &HistoryWindow::default_at(now)).await?;
+    let mut feed = OrderFeed::new(None, Some(Status::Pending), None, OrderFilter::default());
+    feed.seed(&events, now);
+    for view in feed.listed() {
+        check_rules(&view, now, ctx).await?;
+    }
+
+    let mut stream = OrderStream::start(ctx, feed, now).await?;
+    let result = 'watch: loop {
+        match stream.next().await {
+            Ok(Some((changes, now))) => {
+                for change in changes {
+                    if matches!(change.kind, OrderChangeKind::New | OrderChangeKind::Updated) {
+                        if let Err(e) = check_rules(&change.view, now, ctx).await {
+                            break 'watch Err(e);
+                        }
+                    }
+                }
+            }
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        }
+    };
+    stream.close().await;
+    result
+}
@@ -0,0 +1,392 @@
+use anyhow::Result;
+use mostro_core::prelude::*;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::autotake::{AutoTakeLedger, AutoTakeRules, TradeLimits, VOLUME_WINDOW_SECS};
+use crate::cli::take_order::execute_take_order;
+use crate::cli::Context;
+use crate::db::{AutoTake, Order, User};
+use crate::error::CantDoError;
+use crate::lightning::lnurl_pay::LnurlDestination;
+use crate::lightning::LnurlOptions;
+use crate::nip33::OrderView;
+use crate::parser::common::{print_key_value, print_section_header};
+use crate::parser::order_feed::{clock_text, order_summary, OrderChangeKind, OrderFeed};
+use crate::util::{fetch_order_events, fetch_order_view, run_hook, HistoryWindow, OrderStream};
+
+/// How `autotake` settles the trades it starts.
+struct Settlement<'a> {
+    /// Payout destination sent with every `take-sell`.
+    payout: Option<&'a str>,
+    /// Wallet command paying the invoices Mostro answers a take with.
+    pay_command: Option<&'a str>,
+    dry_run: bool,
+}
+
+/// Pay `invoice` with the user's command, in the background: a hold invoice
+/// stays in flight until the trade ends.
+fn spawn_payment(command: &str, order_id: Uuid, invoice: &str, kind: &'static str) {
+    let command = command.to_string();
+    let invoice = invoice.to_string();
+    let env = [
+        ("MOSTRO_INVOICE", invoice.clone()),
+        ("MOSTRO_INVOICE_KIND", kind.to_string()),
+        ("MOSTRO_ORDER_ID", order_id.to_string()),
+    ];
+    tokio::spawn(async move {
+        if let Err(e) = run_hook(&command, &env, &invoice, None).await {
+            println!(
+                "⚠️  Paying the {} invoice of order {} failed: {}",
+                kind, order_id, e
+            );
+        }
+    });
+}
+
+/// Bring the ledger up to date with the newest revisions in `feed`; whether
+/// any trade ended.
+async fn sync_ledger(ledger: &mut AutoTakeLedger, feed: &OrderFeed, ctx: &Context) -> Result<bool> {
+    let open: Vec<Uuid> = ledger
+        .takes()
+        .iter()
+        .filter(|t| !t.finished)
+        .filter_map(|t| Uuid::parse_str(&t.order_id).ok())
+        .collect();
+    let mut ended = false;
+    for id in open {
+        let Some(view) = feed.latest(&id) else {
+            continue;
+        };
+        if let Some(take) = ledger.update(view) {
+            AutoTake::set_status(&ctx.pool, &take.order_id, &take.status, take.finished).await?;
+            if take.finished {
+                ended = true;
+                println!(
+                    "[{}] 🏁 Trade {} is over ({})",
+                    clock_text(chrono::Utc::now().timestamp()),
+                    take.order_id,
+                    take.status
+                );
+            }
+        }
+    }
+    Ok(ended)
+}
+
+/// Look up the open takes whose orders are not in `feed`, e.g. taken before
+/// its history window, so they can't hold a `--max-trades` slot forever.
+/// A take older than the window whose order event is gone from the relays
+/// is over: Mostro sets an expiration on the events of its orders.
+async fn refresh_missing_takes(
+    ledger: &mut AutoTakeLedger,
+    feed: &OrderFeed,
+    window: &HistoryWindow,
+    ctx: &Context,
+) -> Result<()> {
+    let missing: Vec<(Uuid, i64)> = ledger
+        .takes()
+        .iter()
+        .filter(|t| !t.finished)
+        .filter_map(|t| Some((Uuid::parse_str(&t.order_id).ok()?, t.taken_at)))
+        .filter(|(id, _)| feed.latest(id).is_none())
+        .collect();
+    for (id, taken_at) in missing {
+        let take = match fetch_order_view(ctx, &id).await {
+            Ok(Some(view)) => ledger.update(&view),
+            Ok(None) if taken_at < window.since => ledger.expire(&id),
+            Ok(None) => None,
+            Err(e) => {
+                log::warn!("Could not look up taken order {}: {}", id, e);
+                None
+            }
+        };
+        if let Some(take) = take {
+            AutoTake::set_status(&ctx.pool, &take.order_id, &take.status, take.finished).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Take `view` if the rules want it and the limits allow. Every order is
+/// attempted at most once per run; skips are printed when `announce` is set.
+#[allow(clippy::too_many_arguments)]
+async fn consider(
+    view: &OrderView,
+    now: i64,
+    rules: &AutoTakeRules,
+    limits: &TradeLimits,
+    settlement: &Settlement<'_>,
+    ledger: &mut AutoTakeLedger,
+    attempted: &mut HashSet<Uuid>,
+    announce: bool,
+    ctx: &Context,
+) -> Result<()> {
+    let Some(order_id) = view.order.id else {
+        return Ok(());
+    };
+    if !rules.matches(view, now) || attempted.contains(&order_id) || ledger.contains(&order_id) {
+        return Ok(());
+    }
+    // Our own orders are in the local database
+    if Order::get_by_id(&ctx.pool, &order_id.to_string())
+        .await
+        .is_ok()
+    {
+        attempted.insert(order_id);
+        return Ok(());
+    }
+    let skip = |reason: &str| {
+        if announce {
+            println!(
+                "[{}] ⏸️ SKIP {} ({})",
+                clock_text(now),
+                order_summary(view),
+                reason
+            );
+        }
+    };
+    if let Some(reason) = ledger.limit_reached(limits, now) {
+        skip(&reason);
+        return Ok(());
+    }
+    let Some(take) = rules.take_amount(view, ledger.room(limits, now)) else {
+        skip("no amount fits the fiat bounds and daily volume");
+        return Ok(());
+    };
+    attempted.insert(order_id);
+
+    let code = &view.order.fiat_code;
+    if settlement.dry_run {
+        println!(
+            "[{}] 🧪 WOULD TAKE {} for {} {}",
+            clock_text(now),
+            order_summary(view),
+            take.fiat,
+            code
+        );
+        ledger.record(view, &take, now);
+        return Ok(());
+    }
+
+    println!(
+        "[{}] 🤖 TAKING {} for {} {}",
+        clock_text(now),
+        order_summary(view),
+        take.fiat,
+        code
+    );
+    let (action, invoice) = match view.order.kind {
+        Some(mostro_core::order::Kind::Sell) => {
+            (Action::TakeSell, settlement.payout.map(str::to_string))
+        }
+        _ => (Action::TakeBuy, None),
+    };
+    // Counted before sending: a take whose answer is lost may still have
+    // reached Mostro, so only an explicit refusal gives the room back
+    ledger.record(view, &take, now).insert(&ctx.pool).await?;
+    let trade_ctx = ctx.for_next_trade().await?;
+    let result = execute_take_order(
+        &order_id,
+        action,
+        &invoice,
+        take.amount,
+        &LnurlOptions::default(),
+        &rules.maker,
+        &trade_ctx,
+    )
+    .await;
+    User::use_trade_index(&ctx.pool, trade_ctx.trade_index).await?;
+    let reply = match result {
+        Ok(reply) => reply,
+        Err(e) if e.downcast_ref::<CantDoError>().is_some() => {
+            println!("⚠️  Mostro refused order {}: {}", order_id, e);
+            ledger.forget(&order_id);
+            AutoTake::delete(&ctx.pool, &order_id.to_string()).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            println!(
+                "⚠️  Could not take order {}: {} (still counted toward the limits)",
+                order_id, e
+            );
+            return Ok(());
+        }
+    };
+
+    let invoice = match (&reply.action, &reply.payload) {
+        (Action::PayInvoice, Some(Payload::PaymentRequest(_, invoice, _))) => {
+            Some(("hold", invoice))
+        }
+        (Action::PayBondInvoice, Some(Payload::PaymentRequest(_, invoice, _))) => {
+            Some(("bond", invoice))
+        }
+        _ => None,
+    };
+    if let Some((kind, invoice)) = invoice {
+        match settlement.pay_command {
+            Some(command) => {
+                println!("💸 Paying the {} invoice with --pay-command", kind);
+                spawn_payment(command, order_id, invoice, kind);
+            }
+            None => println!(
+                "⚠️  Pay the {} invoice above from your wallet to go on with order {}",
+                kind, order_id
+            ),
+        }
+    }
+    Ok(())
+}
+
+pub async fn execute_autotake(
+    rules: &AutoTakeRules,
+    limits: &TradeLimits,
+    lightning_address: &Option<String>,
+    pay_command: &Option<String>,
+    dry_run: bool,
+    ctx: &Context,
+) -> Result<()> {
+    let takes_sells = rules.kind != Some(mostro_core::order::Kind::Buy);
+    let takes_buys = rules.kind != Some(mostro_core::order::Kind::Sell);
+    if let Some(address) = lightning_address {
+        if LnurlDestination::parse(address).is_none() {
+            return Err(anyhow::anyhow!(
+                "--lightning-address must be a Lightning address or LNURL: a bolt11 can only be paid once"
+            ));
+        }
+    }
+    if !dry_run && takes_sells && lightning_address.is_none() {
+        return Err(anyhow::anyhow!(
+            "Taking sell orders needs a payout --lightning-address; use -k buy to take only buy orders"
+        ));
+    }
+    if !dry_run && takes_buys && pay_command.is_none() {
+        return Err(anyhow::anyhow!(
+            "Taking buy orders needs a --pay-command to pay their hold invoices; use -k sell to take only sell orders"
+        ));
+    }
+    let settlement = Settlement {
+        payout: lightning_address.as_deref(),
+        pay_command: pay_command.as_deref(),
+        dry_run,
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    // A dry run only simulates its limits; real takes count from earlier runs
+    let mut ledger = if dry_run {
+        AutoTakeLedger::default()
+    } else {
+        AutoTakeLedger::new(
+            AutoTake::get_recent(&ctx.pool, &rules.fiat_code, now - VOLUME_WINDOW_SECS).await?,
+        )
+    };
+
+    print_section_header(if dry_run {
+        "🧪 Auto-Take (dry run)"
+    } else {
+        "🤖 Auto-Take"
+    });
+    print_key_value("💱", "Currency", &rules.fiat_code);
+    print_key_value(
+        "📈",
+        "Orders",
+        &rules
+            .kind
+            .map(|k| format!("{} orders", k))
+            .unwrap_or_else(|| "buy and sell orders".to_string()),
+    );
+    if let Some(fiat) = &rules.fiat {
+        print_key_value("💰", "Fiat Amount", &fiat.to_string());
+    }
+    if let Some(max) = limits.max_open {
+        print_key_value(
+            "🔢",
+            "Open Trades",
+            &format!("{} of {}", ledger.open_count(), max),
+        );
+    }
+    if let Some(volume) = limits.daily_volume {
+        print_key_value(
+            "📊",
+            "Daily Volume",
+            &format!(
+                "{} of {} {}",
+                ledger.volume_since(now - VOLUME_WINDOW_SECS),
+                volume,
+                rules.fiat_code
+            ),
+        );
+    }
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("💡", "Stop", "press Ctrl-C");
+    println!();
+
+    let window = HistoryWindow::default_at(now);
+    let events = fetch_order_events(ctx, &window).await?;
+    let mut feed = OrderFeed::new(
+        Some(rules.fiat_code.clone()),
+        Some(Status::Pending),
+        rules.kind,
+        rules.filter.clone(),
+    );
+    feed.seed(&events, now);
+    sync_ledger(&mut ledger, &feed, ctx).await?;
+    refresh_missing_takes(&mut ledger, &feed, &window, ctx).await?;
+
+    let mut attempted = HashSet::new();
+    for view in feed.listed() {
+        consider(
+            &view,
+            now,
+            rules,
+            limits,
+            &settlement,
+            &mut ledger,
+            &mut attempted,
+            true,
+            ctx,
+        )
+        .await?;
+    }
+
+    let mut stream = OrderStream::start(ctx, feed, now).await?;
+    let result = 'watch: loop {
+        let (changes, now) = match stream.next().await {
+            Ok(Some(next)) => next,
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        };
+        let ended = match sync_ledger(&mut ledger, &stream.feed, ctx).await {
+            Ok(ended) => ended,
+            Err(e) => break Err(e),
+        };
+        let mut views: Vec<(OrderView, bool)> = changes
+            .into_iter()
+            .filter(|c| matches!(c.kind, OrderChangeKind::New | OrderChangeKind::Updated))
+            .map(|c| (c.view, true))
+            .collect();
+        // A finished trade frees room for orders skipped earlier
+        if ended {
+            views.extend(stream.feed.listed().into_iter().map(|v| (v, false)));
+        }
+        for (view, announce) in views {
+            if let Err(e) = consider(
+                &view,
+                now,
+                rules,
+                limits,
+                &settlement,
+                &mut ledger,
+                &mut attempted,
+                announce,
+                ctx,
+            )
+            .await
+            {
+                break 'watch Err(e);
+            }
+        }
+    };
+    stream.close().await;
+    result
+}
@@ -0,0 +1,103 @@
+use crate::cli::Context;
+use crate::db::{Bond, BondRole, BondStatus};
+use crate::parser::bonds::{bond_deadline_text, print_bonds_table};
+use crate::parser::common::{print_key_value, print_section_header};
+use crate::util::{fetch_bond_claim_window_days, fetch_events_list, record_bond_message};
+use crate::util::{Event, ListKind};
+use anyhow::Result;
+use mostro_core::prelude::*;
+
+/// Replay the Mostro DMs of the last `since` minutes into the `bonds` table,
+/// so bonds are tracked even when their messages were read elsewhere.
+async fn sync_bonds(since: &i64, ctx: &Context) -> Result<()> {
+    let events = fetch_events_list(
+        ListKind::DirectMessagesUser,
+        None,
+        None,
+        None,
+        ctx,
+        Some(since),
+    )
+    .await?;
+    let mut messages: Vec<(MessageKind, u64)> = events
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::MessageTuple(tuple) if tuple.2 == ctx.mostro_pubkey => {
+                Some((tuple.0.get_inner_message_kind().clone(), tuple.1))
+            }
+            _ => None,
+        })
+        .collect();
+    messages.sort_by_key(|(_, created_at)| *created_at);
+
+    // The claim window only matters for owed shares, so the info event is
+    // fetched only when one is involved.
+    let needs_window = messages
+        .iter()
+        .any(|(m, _)| m.action == Action::AddBondInvoice)
+        || Bond::get_all(&ctx.pool).await?.iter().any(|b| {
+            b.role() == Some(BondRole::Owed)
+                && b.slashed_at.is_some()
+                && b.claim_window_days.is_none()
+        });
+    let claim_window_days = if needs_window {
+        fetch_bond_claim_window_days(ctx).await
+    } else {
+        None
+    };
+
+    for (message, _) in &messages {
+        record_bond_message(&ctx.pool, message, claim_window_days).await?;
+    }
+
+    if let Some(days) = claim_window_days {
+        for mut bond in Bond::get_all(&ctx.pool).await? {
+            if bond.role() == Some(BondRole::Owed) && bond.claim_window_days.is_none() {
+                bond.claim_window_days = Some(days);
+                bond.upsert(&ctx.pool).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn execute_bonds(since: &i64, ctx: &Context) -> Result<()> {
+    print_section_header("🛡️  Bonds");
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value(
+        "💡",
+        "Action",
+        &format!("Syncing bond messages from the last {} minutes...", since),
+    );
+    println!();
+
+    if let Err(e) = sync_bonds(since, ctx).await {
+        println!(
+            "⚠️  Could not sync bond messages, showing stored bonds: {}",
+            e
+        );
+    }
+
+    let bonds = Bond::get_all(&ctx.pool).await?;
+    let now = chrono::Utc::now().timestamp();
+    println!("{}", print_bonds_table(&bonds, now));
+
+    for bond in bonds
+        .iter()
+        .filter(|b| b.status() == Some(BondStatus::Claimable))
+    {
+        println!();
+        println!(
+            "⚠️  Order {}: {} sats are yours to claim, deadline {}",
+            bond.order_id,
+            bond.amount,
+            bond_deadline_text(bond, now)
+        );
+        println!(
+            "💡 Claim with: mostro-cli addbondinvoice -o {} -i <bolt11>",
+            bond.order_id
+        );
+    }
+
+    Ok(())
+}
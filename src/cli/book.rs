@@ -0,0 +1,56 @@
+use crate::cli::Context;
+use crate::fiat::FiatAmount;
+use crate::parser::book::{build_order_book, print_order_book};
+use crate::parser::common::{print_key_value, print_section_header};
+use crate::parser::orders::{parse_order_views, OrderFilter};
+use crate::price::{default_price_provider, PriceProvider};
+use crate::util::{fetch_order_events, HistoryWindow};
+use anyhow::Result;
+use mostro_core::prelude::*;
+
+pub async fn execute_book(
+    currency: &Option<String>,
+    payment_methods: &[String],
+    fiat_amount: &Option<FiatAmount>,
+    ctx: &Context,
+) -> Result<()> {
+    let upper_currency = currency.as_ref().map(|c| c.to_uppercase());
+    let filter = OrderFilter {
+        payment_methods: payment_methods.to_vec(),
+        fiat_amount: *fiat_amount,
+        ..Default::default()
+    };
+
+    print_section_header("📚 Order Book");
+    if let Some(currency) = &upper_currency {
+        print_key_value("💱", "Currency Filter", currency);
+    }
+    if !filter.payment_methods.is_empty() {
+        print_key_value("💳", "Payment Methods", &filter.payment_methods.join(", "));
+    }
+    if let Some(amount) = filter.fiat_amount {
+        print_key_value("💵", "Fiat Amount", &amount.to_string());
+    }
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("💡", "Action", "Fetching pending orders from relays...");
+    println!();
+
+    // Order events rather than `SmallOrder`s, so decimal amounts are exact
+    let window = HistoryWindow::default_at(chrono::Utc::now().timestamp());
+    let events = fetch_order_events(ctx, &window).await?;
+    let views = parse_order_views(events, upper_currency, Some(Status::Pending), None);
+
+    // Without market prices, fixed-sats orders can't be ranked against
+    // market-price ones and go to the end of each side
+    let rates = match default_price_provider(&ctx.pool).btc_rates().await {
+        Ok(rates) => Some(rates),
+        Err(e) => {
+            print_key_value("💡", "Market Prices", &format!("unavailable ({e})"));
+            None
+        }
+    };
+
+    let books = build_order_book(views, rates.as_ref(), &filter);
+    println!("{}", print_order_book(&books));
+    Ok(())
+}
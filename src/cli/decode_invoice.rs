@@ -0,0 +1,150 @@
+use crate::cli::Context;
+use crate::db::Order;
+use crate::lightning::{decode_invoice, mostro_fee_share, network_name, DecodedInvoice};
+use crate::parser::common::{create_invoice_table, print_section_header};
+use anyhow::Result;
+use lightning_invoice::Currency;
+use uuid::Uuid;
+
+/// Outcome of comparing one invoice property with the local order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceCheck {
+    pub passed: bool,
+    pub message: String,
+}
+
+impl InvoiceCheck {
+    fn pass(message: impl Into<String>) -> Self {
+        Self {
+            passed: true,
+            message: message.into(),
+        }
+    }
+
+    fn fail(message: impl Into<String>) -> Self {
+        Self {
+            passed: false,
+            message: message.into(),
+        }
+    }
+}
+
+/// Cross-check a decoded invoice against a local `orders` row.
+///
+/// `fee` and `node_networks` come from the node's info event and are skipped
+/// when unknown. The amount passes when it equals the order amount, the
+/// buyer's payout (order amount minus their half of the fee) or the seller's
+/// hold invoice (order amount plus their half).
+pub fn order_invoice_checks(
+    decoded: &DecodedInvoice,
+    order: &Order,
+    fee: Option<f64>,
+    node_networks: &[Currency],
+) -> Vec<InvoiceCheck> {
+    let mut checks = Vec::new();
+
+    if decoded.is_expired() {
+        checks.push(InvoiceCheck::fail("Invoice has expired"));
+    } else {
+        checks.push(InvoiceCheck::pass("Invoice has not expired"));
+    }
+
+    if !node_networks.is_empty() {
+        let network = network_name(&decoded.network);
+        if node_networks.contains(&decoded.network) {
+            checks.push(InvoiceCheck::pass(format!(
+                "Network {} matches the Mostro node",
+                network
+            )));
+        } else {
+            checks.push(InvoiceCheck::fail(format!(
+                "Network {} does not match the Mostro node",
+                network
+            )));
+        }
+    }
+
+    match (decoded.amount_sats(), order.amount) {
+        (None, _) => checks.push(InvoiceCheck::pass(
+            "Amountless invoice: Mostro sets the amount",
+        )),
+        (Some(_), amount) if amount <= 0 => checks.push(InvoiceCheck::pass(
+            "Market price order: amount not fixed yet",
+        )),
+        (Some(sats), amount) => {
+            let share = fee.map(|fee| mostro_fee_share(amount, fee));
+            let sats = sats as i64;
+            if sats == amount {
+                checks.push(InvoiceCheck::pass(format!(
+                    "Amount {} sats matches the order amount",
+                    sats
+                )));
+            } else if share.is_some_and(|share| sats == amount - share) {
+                checks.push(InvoiceCheck::pass(format!(
+                    "Amount {} sats matches the order amount minus the buyer's Mostro fee",
+                    sats
+                )));
+            } else if share.is_some_and(|share| sats == amount + share) {
+                checks.push(InvoiceCheck::pass(format!(
+                    "Amount {} sats matches the order amount plus the seller's Mostro fee",
+                    sats
+                )));
+            } else {
+                checks.push(InvoiceCheck::fail(format!(
+                    "Amount {} sats does not match the order amount of {} sats",
+                    sats, amount
+                )));
+            }
+        }
+    }
+
+    if let Some(stored) = order.buyer_invoice.as_deref() {
+        if let Ok(stored) = decode_invoice(stored) {
+            if stored.payment_hash == decoded.payment_hash {
+                checks.push(InvoiceCheck::pass(
+                    "Same invoice as the buyer invoice stored for this order",
+                ));
+            }
+        }
+    }
+
+    checks
+}
+
+pub async fn execute_decode_invoice(
+    invoice: &str,
+    order_id: &Option<Uuid>,
+    ctx: &Context,
+) -> Result<()> {
+    let decoded = decode_invoice(invoice).map_err(|e| anyhow::anyhow!("Invalid invoice: {}", e))?;
+
+    print_section_header("🧾 Decoded Lightning Invoice");
+    println!("{}", create_invoice_table(&decoded));
+
+    let Some(order_id) = order_id else {
+        return Ok(());
+    };
+
+    let order = Order::get_by_id(&ctx.pool, &order_id.to_string())
+        .await
+        .map_err(|_| anyhow::anyhow!("Order {} not found in the local database", order_id))?;
+    let node_info = ctx.node_info().await;
+    let fee = node_info.and_then(|info| info.fee);
+    let networks = node_info.map(|info| info.networks()).unwrap_or_default();
+
+    println!();
+    print_section_header(&format!("🔍 Cross-check with order {}", order_id));
+    let checks = order_invoice_checks(&decoded, &order, fee, &networks);
+    for check in &checks {
+        let icon = if check.passed { "✅" } else { "❌" };
+        println!("{} {}", icon, check.message);
+    }
+    if fee.is_none() {
+        println!("💡 Mostro fee unavailable: only the raw order amount was compared");
+    }
+
+    if checks.iter().any(|c| !c.passed) {
+        return Err(anyhow::anyhow!("Invoice does not match order {}", order_id));
+    }
+    Ok(())
+}
@@ -5,14 +5,18 @@ use nostr_sdk::prelude::*;
 use crate::{
     cli::Context,
     parser::common::{print_key_value, print_section_header},
-    parser::dms::print_direct_messages,
-    util::{fetch_bond_claim_window_days, fetch_events_list, Event, ListKind},
+    parser::dms::print_direct_messages_with_qr,
+    util::{
+        fetch_bond_claim_window_days, fetch_events_list, record_bond_message, Event, ListKind,
+        QrOptions,
+    },
 };
 
 pub async fn execute_get_dm(
     since: &i64,
     admin: bool,
     from_user: &bool,
+    qr: &QrOptions,
     ctx: &Context,
 ) -> Result<()> {
     print_section_header("📨 Fetch Direct Messages");
@@ -30,6 +34,8 @@ pub async fn execute_get_dm(
         (false, false) => ListKind::DirectMessagesUser,
     };
 
+    let from_mostro = matches!(list_kind, ListKind::DirectMessagesUser);
+
     // Fetch the requested events
     let all_fetched_events =
         fetch_events_list(list_kind, None, None, None, ctx, Some(since)).await?;
@@ -55,6 +61,20 @@ pub async fn execute_get_dm(
         None
     };
 
-    print_direct_messages(&dm_events, Some(ctx.mostro_pubkey), claim_window_days).await?;
+    // Mostro's own messages also drive the local bond ledger (`bonds` command)
+    if from_mostro {
+        for (message, _, sender) in &dm_events {
+            if *sender != ctx.mostro_pubkey {
+                continue;
+            }
+            let inner = message.get_inner_message_kind();
+            if let Err(e) = record_bond_message(&ctx.pool, inner, claim_window_days).await {
+                println!("⚠️  Failed to update bond records: {}", e);
+            }
+        }
+    }
+
+    print_direct_messages_with_qr(&dm_events, Some(ctx.mostro_pubkey), claim_window_days, qr)
+        .await?;
     Ok(())
 }
@@ -35,6 +35,7 @@ pub async fn execute_last_trade_index(
         message_json,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Print request summary
@@ -48,7 +49,7 @@ pub async fn execute_last_trade_index(
     let recv_event = wait_for_dm(ctx, Some(identity_keys), sent_message).await?;
 
     // Parse the incoming DM
-    let messages = parse_dm_events(recv_event, identity_keys, None, true).await;
+    let messages = parse_dm_events(recv_event, identity_keys, None, true, Some(&ctx.pool)).await;
     if let Some((message, _, _)) = messages.first() {
         let message = message.get_inner_message_kind();
         if message.action == Action::LastTradeIndex {
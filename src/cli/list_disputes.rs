@@ -3,17 +3,22 @@ use anyhow::Result;
 use crate::cli::Context;
 use crate::parser::common::{print_key_value, print_section_header};
 use crate::parser::disputes::print_disputes_table;
-use crate::util::{fetch_events_list, ListKind};
+use crate::parser::parse_dispute_events;
+use crate::util::{fetch_dispute_events, Event, HistoryWindow};
 
-pub async fn execute_list_disputes(ctx: &Context) -> Result<()> {
+pub async fn execute_list_disputes(window: &HistoryWindow, ctx: &Context) -> Result<()> {
     print_section_header("⚖️  List Disputes");
     print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("🗓️", "Window", &window.describe());
     print_key_value("💡", "Action", "Fetching disputes from relays...");
     println!();
 
-    // Get orders from relays
-    let table_of_disputes =
-        fetch_events_list(ListKind::Disputes, None, None, None, ctx, None).await?;
+    // Get disputes from relays, every page of the window
+    let events = fetch_dispute_events(ctx, window).await?;
+    let table_of_disputes = parse_dispute_events(events)
+        .into_iter()
+        .map(Event::Dispute)
+        .collect();
     let table = print_disputes_table(table_of_disputes)?;
     println!("{table}");
 
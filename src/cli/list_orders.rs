@@ -1,16 +1,70 @@
 use crate::cli::Context;
+use crate::nip33::OrderView;
 use crate::parser::common::{print_key_value, print_section_header};
-use crate::parser::orders::print_orders_table;
-use crate::util::{fetch_events_list, ListKind};
+use crate::parser::order_feed::{order_change_line, OrderFeed};
+use crate::parser::orders::{
+    parse_order_views, print_orders_table, sort_orders, OrderFilter, OrderSort,
+};
+use crate::price::{default_price_provider, PriceProvider};
+use crate::util::{fetch_order_events, HistoryWindow, OrderStream};
 use anyhow::Result;
 use mostro_core::prelude::*;
 use std::str::FromStr;
 
+/// Print the client-side filters and sorting that are in effect.
+fn print_filter_summary(
+    filter: &OrderFilter,
+    sort: OrderSort,
+    reverse: bool,
+    limit: Option<usize>,
+) {
+    if !filter.payment_methods.is_empty() {
+        print_key_value("💳", "Payment Methods", &filter.payment_methods.join(", "));
+    }
+    if let Some(amount) = filter.fiat_amount {
+        print_key_value("💵", "Fiat Amount", &amount.to_string());
+    }
+    if filter.min_premium.is_some() || filter.max_premium.is_some() {
+        let bound = |b: Option<i64>| b.map(|v| v.to_string()).unwrap_or_else(|| "*".to_string());
+        print_key_value(
+            "📊",
+            "Premium Range",
+            &format!(
+                "{}% to {}%",
+                bound(filter.min_premium),
+                bound(filter.max_premium)
+            ),
+        );
+    }
+    if let Some(rating) = filter.min_rating {
+        print_key_value("⭐", "Min Maker Rating", &rating.to_string());
+    }
+    if let Some(age) = filter.max_age_secs {
+        print_key_value("⏱️", "Max Age", &format!("{} minutes", age / 60));
+    }
+    if sort != OrderSort::Age || reverse {
+        print_key_value(
+            "🔀",
+            "Sort",
+            &format!("{:?}{}", sort, if reverse { " (reversed)" } else { "" }),
+        );
+    }
+    if let Some(limit) = limit {
+        print_key_value("🔢", "Limit", &limit.to_string());
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn execute_list_orders(
     kind: &Option<String>,
     currency: &Option<String>,
     status: &Option<String>,
+    filter: &OrderFilter,
+    sort: OrderSort,
+    reverse: bool,
+    limit: Option<usize>,
+    window: &HistoryWindow,
+    follow: bool,
     ctx: &Context,
 ) -> Result<()> {
     // Used to get upper currency string to check against a list of tickers
@@ -52,23 +106,77 @@ pub async fn execute_list_orders(
             print_key_value("💱", "Currency Filter", currency);
         }
     }
+    print_filter_summary(filter, sort, reverse, limit);
+    print_key_value("🗓️", "Window", &window.describe());
 
     print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
     print_key_value("💡", "Action", "Fetching orders from relays...");
     println!();
 
-    // Get orders from relays
-    let table_of_orders = fetch_events_list(
-        ListKind::Orders,
-        status_checked,
-        upper_currency,
-        kind_checked,
-        ctx,
-        None,
-    )
-    .await?;
-    let table = print_orders_table(table_of_orders)?;
+    // Get orders from relays, then apply the client-side filters
+    let events = fetch_order_events(ctx, window).await?;
+    let now = chrono::Utc::now().timestamp();
+    let feed = follow.then(|| {
+        let mut feed = OrderFeed::new(
+            upper_currency.clone(),
+            status_checked,
+            kind_checked,
+            filter.clone(),
+        );
+        feed.seed(&events, now);
+        feed
+    });
+    let mut orders: Vec<OrderView> =
+        parse_order_views(events, upper_currency, status_checked, kind_checked)
+            .into_iter()
+            .filter(|v| filter.matches_view(v, now))
+            .collect();
+    sort_orders(&mut orders, sort, reverse);
+    if let Some(limit) = limit {
+        orders.truncate(limit);
+    }
+
+    // Market prices are only used for estimates: list the orders without them
+    let rates = match default_price_provider(&ctx.pool).btc_rates().await {
+        Ok(rates) => Some(rates),
+        Err(e) => {
+            print_key_value("💡", "Market Prices", &format!("unavailable ({e})"));
+            None
+        }
+    };
+    let table = print_orders_table(orders, rates.as_ref())?;
     println!("{table}");
 
-    Ok(())
+    match feed {
+        Some(feed) => follow_orders(feed, now, ctx).await,
+        None => Ok(()),
+    }
+}
+
+/// Print a line for every change to the listed orders until Ctrl-C. Order
+/// events from `since` on are merged into `feed` as relays deliver them.
+async fn follow_orders(feed: OrderFeed, since: i64, ctx: &Context) -> Result<()> {
+    let mut stream = OrderStream::start(ctx, feed, since).await?;
+    println!();
+    print_key_value(
+        "👀",
+        "Following",
+        "new, updated, taken, canceled and expired orders",
+    );
+    print_key_value("💡", "Stop", "press Ctrl-C");
+    println!();
+
+    let result = loop {
+        match stream.next().await {
+            Ok(Some((changes, now))) => {
+                for change in &changes {
+                    println!("{}", order_change_line(change, now));
+                }
+            }
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e),
+        }
+    };
+    stream.close().await;
+    result
 }
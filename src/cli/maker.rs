@@ -0,0 +1,376 @@
+use anyhow::Result;
+use mostro_core::prelude::*;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::cli::new_order::{build_new_order, send_new_order};
+use crate::cli::{Commands, Context};
+use crate::db::{MakerOffer, MakerOrder, User};
+use crate::fiat::FiatRange;
+use crate::maker::{
+    needs_reprice, new_maker_offer, offer_description, offer_order, replacement_reason,
+    MAKER_PRICE_CHECK,
+};
+use crate::parser::common::{print_key_value, print_section_header, print_success_message};
+use crate::parser::maker::{print_offer_history_table, print_offers_table};
+use crate::parser::order_feed::{clock_text, OrderChangeKind, OrderFeed};
+use crate::parser::orders::OrderFilter;
+use crate::price::{default_price_provider, PriceProvider};
+use crate::util::{fetch_order_events, run_simple_order_msg, HistoryWindow, OrderStream};
+
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_add_offer(
+    name: &str,
+    kind: &str,
+    fiat_code: &str,
+    fiat_amount: &FiatRange,
+    payment_method: &str,
+    premium: i64,
+    fixed_price: bool,
+    reprice_threshold: Option<f64>,
+    expiration_days: i64,
+    invoice: &Option<String>,
+    ctx: &Context,
+) -> Result<()> {
+    let offer = new_maker_offer(
+        name,
+        kind,
+        fiat_code,
+        fiat_amount,
+        payment_method,
+        premium,
+        fixed_price,
+        reprice_threshold,
+        expiration_days,
+        invoice.as_deref(),
+    )?
+    .insert(&ctx.pool)
+    .await?;
+
+    print_section_header("📌 New Standing Offer");
+    print_key_value("🆔", "Id", &offer.id.to_string());
+    print_key_value("🏷️", "Name", &offer.name);
+    print_key_value("📋", "Offer", &offer_description(&offer));
+    println!();
+    print_success_message("Offer saved. Run `mostro-cli maker` to keep it published.");
+    Ok(())
+}
+
+pub async fn execute_list_offers(ctx: &Context) -> Result<()> {
+    print_section_header("📌 Standing Offers");
+    let mut offers = Vec::new();
+    for offer in MakerOffer::get_all(&ctx.pool).await? {
+        let live = MakerOrder::get_live(&ctx.pool, offer.id).await?;
+        offers.push((offer, live));
+    }
+    println!("{}", print_offers_table(&offers));
+    Ok(())
+}
+
+pub async fn execute_delete_offer(id: i64, ctx: &Context) -> Result<()> {
+    if !MakerOffer::delete_by_id(&ctx.pool, id).await? {
+        return Err(anyhow::anyhow!("No offer with id {}", id));
+    }
+    print_success_message(&format!(
+        "Offer {} deleted; its live order, if any, stays published until you cancel it",
+        id
+    ));
+    Ok(())
+}
+
+pub async fn execute_offer_history(id: i64, ctx: &Context) -> Result<()> {
+    print_section_header(&format!("📜 Orders of Offer {}", id));
+    let orders = MakerOrder::get_by_offer(&ctx.pool, id).await?;
+    println!("{}", print_offer_history_table(&orders));
+    Ok(())
+}
+
+/// Live orders by offer id, and the orders they stand for.
+#[derive(Default)]
+struct LiveOrders(HashMap<i64, MakerOrder>);
+
+impl LiveOrders {
+    fn offer_of(&self, order_id: &Uuid) -> Option<i64> {
+        let id = order_id.to_string();
+        self.0
+            .iter()
+            .find(|(_, order)| order.order_id == id)
+            .map(|(offer_id, _)| *offer_id)
+    }
+}
+
+/// Price of one BTC in `fiat_code`, if the provider knows it.
+async fn reference_price(fiat_code: &str, ctx: &Context) -> Option<f64> {
+    match default_price_provider(&ctx.pool).btc_rates().await {
+        Ok(rates) => rates.price(fiat_code),
+        Err(e) => {
+            println!("⚠️  Market price unavailable: {}", e);
+            None
+        }
+    }
+}
+
+/// Publish the next order of `offer` from fresh trade keys, replacing
+/// `parent`. Failures are printed and retried at the next price check.
+async fn publish(offer: &MakerOffer, parent: Option<String>, ctx: &Context) -> Option<MakerOrder> {
+    let now = chrono::Utc::now().timestamp();
+    println!(
+        "[{}] 📤 Publishing offer '{}': {}",
+        clock_text(now),
+        offer.name,
+        offer_description(offer)
+    );
+    let result = async {
+        let price = reference_price(&offer.fiat_code, ctx).await;
+        let terms = offer_order(offer, price)?;
+        let trade_ctx = ctx.for_next_trade().await?;
+        let order = build_new_order(
+            &offer.kind,
+            &offer.fiat_code,
+            &terms.fiat_amount,
+            terms.amount,
+            &offer.payment_method,
+            terms.premium,
+            &offer.invoice,
+            offer.expiration_days,
+            &trade_ctx,
+        )
+        .await?;
+        let reply = send_new_order(order, &trade_ctx).await;
+        User::use_trade_index(&ctx.pool, trade_ctx.trade_index).await?;
+        let order_id = match reply?.payload {
+            Some(Payload::Order(order)) => order.id,
+            _ => None,
+        }
+        .ok_or_else(|| anyhow::anyhow!("Mostro did not answer with the new order"))?;
+        let order = MakerOrder {
+            order_id: order_id.to_string(),
+            offer_id: offer.id,
+            trade_index: trade_ctx.trade_index,
+            parent_order_id: parent,
+            amount: terms.amount,
+            reference_price: terms.reference_price,
+            status: Status::Pending.to_string(),
+            closed_reason: None,
+            created_at: now,
+            closed_at: None,
+        };
+        order.insert(&ctx.pool).await?;
+        anyhow::Ok(order)
+    }
+    .await;
+    match result {
+        Ok(order) => Some(order),
+        Err(e) => {
+            println!("⚠️  Could not publish offer '{}': {}", offer.name, e);
+            None
+        }
+    }
+}
+
+/// Close the live order of `offer_id` for `reason` and publish its
+/// replacement.
+async fn replace(
+    offer: &MakerOffer,
+    old: MakerOrder,
+    status: &str,
+    reason: &str,
+    live: &mut LiveOrders,
+    ctx: &Context,
+) -> Result<()> {
+    let now = chrono::Utc::now().timestamp();
+    println!(
+        "[{}] 🔁 Order {} of offer '{}' {}",
+        clock_text(now),
+        old.order_id,
+        offer.name,
+        reason
+    );
+    MakerOrder::close(&ctx.pool, &old.order_id, status, reason, now).await?;
+    live.0.remove(&offer.id);
+    if let Some(order) = publish(offer, Some(old.order_id), ctx).await {
+        live.0.insert(offer.id, order);
+    }
+    Ok(())
+}
+
+/// Publish offers that have no live order, and replace fixed-price orders
+/// whose reference price moved beyond their threshold. Offers are read again
+/// each time, so `addoffer` and `deleteoffer` apply while running.
+async fn refresh_offers(live: &mut LiveOrders, ctx: &Context) -> Result<()> {
+    let offers = MakerOffer::get_all(&ctx.pool).await?;
+    live.0
+        .retain(|offer_id, _| offers.iter().any(|o| o.id == *offer_id));
+    let mut prices = HashMap::new();
+    for offer in &offers {
+        let Some(order) = live.0.get(&offer.id).cloned() else {
+            if let Some(order) = publish(offer, None, ctx).await {
+                live.0.insert(offer.id, order);
+            }
+            continue;
+        };
+        if !offer.fixed_price || offer.reprice_threshold.is_none() {
+            continue;
+        }
+        if !prices.contains_key(&offer.fiat_code) {
+            let price = reference_price(&offer.fiat_code, ctx).await;
+            prices.insert(offer.fiat_code.clone(), price);
+        }
+        let Some(price) = prices[&offer.fiat_code] else {
+            continue;
+        };
+        if !needs_reprice(offer, &order, price) {
+            continue;
+        }
+        // Cancel first, so the stale price can't be taken meanwhile
+        let order_id = Uuid::parse_str(&order.order_id)?;
+        if let Err(e) =
+            run_simple_order_msg(Commands::Cancel { order_id }, Some(order_id), ctx).await
+        {
+            println!(
+                "⚠️  Could not cancel order {} to reprice it: {}",
+                order_id, e
+            );
+            continue;
+        }
+        replace(
+            offer,
+            order,
+            &Status::Canceled.to_string(),
+            "repriced",
+            live,
+            ctx,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// React to a change in the book: replace live orders that left it, and
+/// cancel replaced orders Mostro put back in it after a failed trade, so each
+/// offer keeps exactly one order.
+async fn handle_change(
+    kind: OrderChangeKind,
+    order_id: Uuid,
+    status: Option<Status>,
+    live: &mut LiveOrders,
+    ctx: &Context,
+) -> Result<()> {
+    let status = status.map(|s| s.to_string()).unwrap_or_default();
+    if let Some(offer_id) = live.offer_of(&order_id) {
+        let Some(reason) = replacement_reason(kind) else {
+            return Ok(());
+        };
+        let offers = MakerOffer::get_all(&ctx.pool).await?;
+        let (Some(offer), Some(old)) = (
+            offers.iter().find(|o| o.id == offer_id),
+            live.0.get(&offer_id).cloned(),
+        ) else {
+            return Ok(());
+        };
+        return replace(offer, old, &status, reason, live, ctx).await;
+    }
+    if kind == OrderChangeKind::New
+        && MakerOrder::get_by_id(&ctx.pool, &order_id.to_string())
+            .await?
+            .is_some_and(|o| o.closed_reason.is_some())
+    {
+        println!(
+            "[{}] ♻️ Replaced order {} is back in the book, canceling it",
+            clock_text(chrono::Utc::now().timestamp()),
+            order_id
+        );
+        if let Err(e) =
+            run_simple_order_msg(Commands::Cancel { order_id }, Some(order_id), ctx).await
+        {
+            println!("⚠️  Could not cancel order {}: {}", order_id, e);
+        }
+    }
+    Ok(())
+}
+
+pub async fn execute_maker(ctx: &Context) -> Result<()> {
+    let offers = MakerOffer::get_all(&ctx.pool).await?;
+    if offers.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No standing offers yet, add one with `mostro-cli addoffer`"
+        ));
+    }
+
+    print_section_header("📌 Market Maker");
+    print_key_value("📋", "Offers", &offers.len().to_string());
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("💡", "Stop", "press Ctrl-C; orders stay published");
+    println!();
+
+    let now = chrono::Utc::now().timestamp();
+    let events = fetch_order_events(ctx, &HistoryWindow::default_at(now)).await?;
+    let mut feed = OrderFeed::new(None, Some(Status::Pending), None, OrderFilter::default());
+    feed.seed(&events, now);
+
+    // Adopt the orders published by earlier runs that are still pending
+    let mut live = LiveOrders::default();
+    for offer in &offers {
+        let Some(order) = MakerOrder::get_live(&ctx.pool, offer.id).await? else {
+            continue;
+        };
+        let view = Uuid::parse_str(&order.order_id)
+            .ok()
+            .and_then(|id| feed.latest(&id));
+        let in_book = view.is_some_and(|v| {
+            v.order.status == Some(Status::Pending) && v.expires_at.is_none_or(|at| at > now)
+        });
+        if in_book {
+            live.0.insert(offer.id, order);
+            continue;
+        }
+        let status = view
+            .and_then(|v| v.order.status)
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| order.status.clone());
+        let reason = view
+            .and_then(|v| replacement_reason(OrderChangeKind::leaving(v.order.status)))
+            .unwrap_or("removed");
+        replace(offer, order, &status, reason, &mut live, ctx).await?;
+    }
+    refresh_offers(&mut live, ctx).await?;
+
+    let mut stream = OrderStream::start(ctx, feed, now).await?;
+    let mut price_check = tokio::time::interval(MAKER_PRICE_CHECK);
+    // The first tick is immediate and the offers were just refreshed
+    price_check.tick().await;
+    let result = loop {
+        tokio::select! {
+            next = stream.next() => {
+                let changes = match next {
+                    Ok(Some((changes, _))) => changes,
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e),
+                };
+                for change in changes {
+                    let Some(order_id) = change.view.order.id else {
+                        continue;
+                    };
+                    if let Err(e) = handle_change(
+                        change.kind,
+                        order_id,
+                        change.view.order.status,
+                        &mut live,
+                        ctx,
+                    )
+                    .await
+                    {
+                        println!("⚠️  {}", e);
+                    }
+                }
+            }
+            _ = price_check.tick() => {
+                if let Err(e) = refresh_offers(&mut live, ctx).await {
+                    println!("⚠️  {}", e);
+                }
+            }
+        }
+    };
+    stream.close().await;
+    result
+}
@@ -1,57 +1,212 @@
 use crate::cli::Context;
+use crate::error::NewOrderError;
+use crate::fiat::FiatRange;
 use crate::parser::common::{
     create_emoji_field_row, create_field_value_header, create_standard_table,
 };
 use crate::parser::orders::print_order_preview;
-use crate::util::{print_dm_events, send_dm, uppercase_first, wait_for_dm};
+use crate::price::{default_price_provider, OrderEstimate, PriceProvider};
+use crate::util::{print_dm_events, send_dm, uppercase_first, wait_for_dm, NodeInfo};
 use anyhow::Result;
 use mostro_core::prelude::*;
-use std::collections::HashMap;
 use std::io::{stdin, stdout, BufRead, Write};
-use std::process;
 use std::str::FromStr;
 use uuid::Uuid;
 
-pub type FiatNames = HashMap<String, String>;
+pub use crate::price::FiatNames;
 
+/// Reasons Mostro would reject `order`, checked locally so a bad order does
+/// not burn a trade index and a round trip on a `cant-do`.
+///
+/// Shape checks (positive fiat amount, valid range, premium only on market
+/// price orders, non-negative expiration) always apply. Node terms are
+/// checked when `node_info` is known and publishes them: sats amount within
+/// `min_order_amount`/`max_order_amount`, fiat code among
+/// `fiat_currencies_accepted`, premium within `max_premium` and
+/// `expiration_days` up to `max_expiration_days`. Market-price and range orders have no sats amount
+/// yet: with a `market_price` (fiat units per BTC) their estimated sats, at
+/// both ends of a range, are held to the same limits. An empty result means
+/// the order can be sent.
+pub fn new_order_problems(
+    order: &SmallOrder,
+    expiration_days: i64,
+    node_info: Option<&NodeInfo>,
+    market_price: Option<f64>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    match (order.min_amount, order.max_amount) {
+        (Some(min), Some(max)) => {
+            if min <= 0 || max <= 0 {
+                problems.push(format!(
+                    "Fiat range {}-{} must only contain positive amounts",
+                    min, max
+                ));
+            } else if min >= max {
+                problems.push(format!(
+                    "Fiat range minimum {} must be lower than its maximum {}",
+                    min, max
+                ));
+            }
+            if order.amount != 0 {
+                problems.push(
+                    "Range orders are always market priced: drop the sats amount".to_string(),
+                );
+            }
+        }
+        _ => {
+            if order.check_fiat_amount().is_err() {
+                problems.push(format!(
+                    "Fiat amount must be positive, got {}",
+                    order.fiat_amount
+                ));
+            }
+        }
+    }
+    if order.check_amount().is_err() {
+        problems.push(format!(
+            "Sats amount cannot be negative, got {}",
+            order.amount
+        ));
+    }
+    if order.check_zero_amount_with_premium().is_err() {
+        problems.push(
+            "A premium only applies to market price orders: set either the sats amount or the premium"
+                .to_string(),
+        );
+    }
+    if expiration_days < 0 {
+        problems.push(format!(
+            "Expiration days cannot be negative, got {}",
+            expiration_days
+        ));
+    }
+
+    let Some(info) = node_info else {
+        return problems;
+    };
+    if !info.accepts_currency(&order.fiat_code) {
+        problems.push(format!(
+            "This Mostro node does not accept {}; accepted currencies: {}",
+            order.fiat_code,
+            info.fiat_currencies_accepted.join(", ")
+        ));
+    }
+    if let Some(max) = info
+        .max_premium
+        .filter(|max| order.premium.unsigned_abs() > *max)
+    {
+        problems.push(format!(
+            "Premium of {}% is outside the node bound of ±{}%",
+            order.premium, max
+        ));
+    }
+    // Smallest and largest sats the order can trade, described for messages
+    let estimate = OrderEstimate::new(order, market_price);
+    let (low, high) = if order.amount > 0 {
+        let sats = (order.amount, format!("{} sats", order.amount));
+        (Some(sats.clone()), Some(sats))
+    } else {
+        let fiat_low = order.min_amount.unwrap_or(order.fiat_amount);
+        let fiat_high = order.max_amount.unwrap_or(order.fiat_amount);
+        let describe = |fiat: i64, sats: i64| {
+            format!(
+                "{} {} (about {} sats at the market price)",
+                fiat, order.fiat_code, sats
+            )
+        };
+        let high_sats = estimate.max_sats.or(estimate.min_sats);
+        (
+            estimate
+                .min_sats
+                .map(|sats| (sats, describe(fiat_low, sats))),
+            high_sats.map(|sats| (sats, describe(fiat_high, sats))),
+        )
+    };
+    if let (Some((sats, text)), Some(min)) = (&low, info.min_order_amount) {
+        if *sats < 0 || (*sats as u64) < min {
+            problems.push(format!(
+                "{} is below the node minimum of {} sats",
+                text, min
+            ));
+        }
+    }
+    if let (Some((sats, text)), Some(max)) = (&high, info.max_order_amount) {
+        if *sats > 0 && (*sats as u64) > max {
+            problems.push(format!(
+                "{} is above the node maximum of {} sats",
+                text, max
+            ));
+        }
+    }
+    if let Some(max) = info
+        .max_expiration_days
+        .filter(|max| expiration_days > 0 && expiration_days as u64 > *max)
+    {
+        problems.push(format!(
+            "Expiration of {} days exceeds the node maximum of {} days",
+            expiration_days, max
+        ));
+    }
+
+    problems
+}
+
+/// Build the order `neworder` sends and check it against the node terms,
+/// printing what is wrong before failing. Market-priced orders (`amount` 0)
+/// need a price for `fiat_code`.
 #[allow(clippy::too_many_arguments)]
-pub async fn execute_new_order(
+pub async fn build_new_order(
     kind: &str,
     fiat_code: &str,
-    fiat_amount: &(i64, Option<i64>),
-    amount: &i64,
+    fiat_amount: &FiatRange,
+    amount: i64,
     payment_method: &str,
-    premium: &i64,
+    premium: i64,
     invoice: &Option<String>,
+    expiration_days: i64,
     ctx: &Context,
-    expiration_days: &i64,
-) -> Result<()> {
+) -> Result<SmallOrder> {
     // Uppercase currency
     let fiat_code = fiat_code.to_uppercase();
-    // Check if fiat currency selected is available on Yadio and eventually force user to set amount
-    // this is in the case of crypto <--> crypto offer for example
-    if *amount == 0 {
-        // Get Fiat list
-        let api_req_string = "https://api.yadio.io/currencies".to_string();
-        let fiat_list_check = reqwest::get(api_req_string)
-            .await?
-            .json::<FiatNames>()
-            .await?
-            .contains_key(&fiat_code);
-        if !fiat_list_check {
-            println!("{} is not present in the fiat market, please specify an amount with -a flag to fix the rate", fiat_code);
-            process::exit(0);
+    // Decimals beyond the currency's minor units are a typo; cents within
+    // them are valid money the protocol still can't carry
+    fiat_amount.check_currency(&fiat_code)?;
+    let fiat_amount = fiat_amount.to_whole_units(&fiat_code)?;
+    // A market price order needs a price for the currency; otherwise (e.g. a
+    // crypto <--> crypto offer) the user has to fix the sats amount
+    let mut market_price = None;
+    if amount == 0 {
+        let provider = default_price_provider(&ctx.pool);
+        let currencies = provider
+            .currencies()
+            .await
+            .map_err(|e| anyhow::anyhow!("Could not check market currencies: {}", e))?;
+        if !currencies.contains_key(&fiat_code) {
+            return Err(NewOrderError::NoMarketPrice {
+                fiat_code,
+                provider: provider.name(),
+            }
+            .into());
         }
+        // The estimated sats are checked against the node limits below
+        market_price = match provider.btc_rates().await {
+            Ok(rates) => rates.price(&fiat_code),
+            Err(e) => {
+                log::warn!("Could not fetch market prices: {}", e);
+                None
+            }
+        };
     }
     let kind = uppercase_first(kind);
     // New check against strings
     let kind_checked = mostro_core::order::Kind::from_str(&kind)
         .map_err(|_| anyhow::anyhow!("Invalid order kind"))?;
-    let expires_at = match *expiration_days {
+    let expires_at = match expiration_days {
         0 => None,
         _ => {
             let now = chrono::Utc::now();
-            let expires_at = now + chrono::Duration::days(*expiration_days);
+            let expires_at = now + chrono::Duration::days(expiration_days);
             Some(expires_at.timestamp())
         }
     };
@@ -69,13 +224,13 @@ pub async fn execute_new_order(
         None,
         Some(kind_checked),
         Some(Status::Pending),
-        *amount,
-        fiat_code.clone(),
+        amount,
+        fiat_code,
         amt.1,
         amt.2,
         amt.0,
         payment_method.to_owned(),
-        *premium,
+        premium,
         None,
         None,
         invoice.as_ref().to_owned().cloned(),
@@ -83,31 +238,31 @@ pub async fn execute_new_order(
         expires_at,
     );
 
-    // Create new order for mostro
-    let order_content = Payload::Order(small_order.clone());
-
-    // Print order preview
-    let ord_preview = print_order_preview(order_content.clone())
-        .map_err(|e| anyhow::anyhow!("Failed to generate order preview: {}", e))?;
-    println!("{ord_preview}");
-    let mut user_input = String::new();
-    let _input = stdin();
-    stdout().flush()?;
-
-    let mut answer = stdin().lock();
-    answer.read_line(&mut user_input)?;
-
-    match user_input.to_lowercase().as_str().trim_end() {
-        "y" | "" => {}
-        "n" => {
-            println!("Ok you have cancelled the order, create another one please");
-            process::exit(0);
+    // Check the order against the node terms before spending a trade index
+    let node_info = ctx.node_info().await;
+    let problems = new_order_problems(&small_order, expiration_days, node_info, market_price);
+    if !problems.is_empty() {
+        println!("❌ Order Rejected Before Sending");
+        println!("═══════════════════════════════════════");
+        for problem in &problems {
+            println!("• {}", problem);
         }
-        &_ => {
-            println!("Can't get what you're sayin!");
-            process::exit(0);
-        }
-    };
+        return Err(NewOrderError::Rejected(problems).into());
+    }
+    if node_info.is_none() {
+        println!("💡 Node info unavailable: only the order shape was checked locally");
+    } else if small_order.amount == 0 && market_price.is_none() {
+        println!(
+            "💡 Market price unavailable: the sats amount was not checked against node limits"
+        );
+    }
+
+    Ok(small_order)
+}
+
+/// Send `order` to Mostro from the context's trade keys and handle the
+/// answer, which saves the new order locally. Returns Mostro's answer.
+pub async fn send_new_order(order: SmallOrder, ctx: &Context) -> Result<MessageKind> {
     let request_id = Uuid::new_v4().as_u128() as u64;
     // Create NewOrder message
     let message = Message::new_order(
@@ -115,7 +270,7 @@ pub async fn execute_new_order(
         Some(request_id),
         Some(ctx.trade_index),
         Action::NewOrder,
-        Some(order_content),
+        Some(Payload::Order(order.clone())),
     );
 
     // Print summary and send DM to Mostro
@@ -125,37 +280,42 @@ pub async fn execute_new_order(
     let mut table = create_standard_table();
     table.set_header(create_field_value_header());
 
-    table.add_row(create_emoji_field_row("📈 ", "Order Type", &kind));
-    table.add_row(create_emoji_field_row("💱 ", "Fiat Code", &fiat_code));
+    let kind = order.kind.map(|k| k.to_string()).unwrap_or_default();
+    table.add_row(create_emoji_field_row(
+        "📈 ",
+        "Order Type",
+        &uppercase_first(&kind),
+    ));
+    table.add_row(create_emoji_field_row("💱 ", "Fiat Code", &order.fiat_code));
     table.add_row(create_emoji_field_row(
         "💰 ",
         "Amount (sats)",
-        &amount.to_string(),
+        &order.amount.to_string(),
     ));
 
-    if let Some(max) = fiat_amount.1 {
+    if let (Some(min), Some(max)) = (order.min_amount, order.max_amount) {
         table.add_row(create_emoji_field_row(
             "📊 ",
             "Fiat Range",
-            &format!("{}-{}", fiat_amount.0, max),
+            &format!("{}-{}", min, max),
         ));
     } else {
         table.add_row(create_emoji_field_row(
             "💵 ",
             "Fiat Amount",
-            &fiat_amount.0.to_string(),
+            &order.fiat_amount.to_string(),
         ));
     }
 
     table.add_row(create_emoji_field_row(
         "💳 ",
         "Payment Method",
-        payment_method,
+        &order.payment_method,
     ));
     table.add_row(create_emoji_field_row(
         "📈 ",
         "Premium (%)",
-        &premium.to_string(),
+        &order.premium.to_string(),
     ));
     table.add_row(create_emoji_field_row(
         "🔢 ",
@@ -189,13 +349,86 @@ pub async fn execute_new_order(
         message_json,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for the DM to be sent from mostro
     let recv_event = wait_for_dm(ctx, None, sent_message).await?;
 
     // Parse the incoming DM
-    print_dm_events(recv_event, request_id, ctx, None).await?;
+    print_dm_events(recv_event, request_id, ctx, None).await
+}
+
+/// Read the answer to the order confirmation prompt: Enter or `y` go on,
+/// `n` cancels. `None` means standard input closed without an answer, as
+/// when a script runs `neworder` without `--yes`.
+pub fn check_confirmation(answer: Option<&str>) -> Result<(), NewOrderError> {
+    let Some(answer) = answer else {
+        return Err(NewOrderError::ConfirmationRequired);
+    };
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" | "" => Ok(()),
+        "n" | "no" => Err(NewOrderError::Declined),
+        other => Err(NewOrderError::UnrecognizedAnswer(other.to_string())),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_new_order(
+    kind: &str,
+    fiat_code: &str,
+    fiat_amount: &FiatRange,
+    amount: &i64,
+    payment_method: &str,
+    premium: &i64,
+    invoice: &Option<String>,
+    ctx: &Context,
+    expiration_days: &i64,
+    yes: bool,
+) -> Result<()> {
+    let small_order = build_new_order(
+        kind,
+        fiat_code,
+        fiat_amount,
+        *amount,
+        payment_method,
+        *premium,
+        invoice,
+        *expiration_days,
+        ctx,
+    )
+    .await?;
+    let fiat_code = small_order.fiat_code.clone();
+
+    // Create new order for mostro
+    let order_content = Payload::Order(small_order.clone());
+
+    // Market price for the sats / price estimates; the preview works without it
+    let market_price = match default_price_provider(&ctx.pool).btc_rates().await {
+        Ok(rates) => rates.price(&fiat_code),
+        Err(e) => {
+            println!("💡 Market price unavailable, showing the order without estimates: {e}");
+            None
+        }
+    };
+
+    // Print order preview
+    let ord_preview = print_order_preview(
+        order_content,
+        ctx.node_info().await.and_then(|i| i.fee),
+        market_price,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to generate order preview: {}", e))?;
+    println!("{ord_preview}");
+    if !yes {
+        print!("✅ All details look correct? (Y/n) ");
+        stdout().flush()?;
+        let mut user_input = String::new();
+        let read = stdin().lock().read_line(&mut user_input)?;
+        check_confirmation((read > 0).then_some(user_input.as_str()))?;
+    }
+
+    send_new_order(small_order, ctx).await?;
 
     Ok(())
 }
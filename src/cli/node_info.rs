@@ -0,0 +1,18 @@
+use crate::cli::Context;
+use crate::parser::common::{
+    create_node_info_table, print_key_value, print_no_data_message, print_section_header,
+};
+use anyhow::Result;
+
+pub async fn execute_node_info(ctx: &Context) -> Result<()> {
+    print_section_header("🧌 Mostro Node Info");
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("💡", "Action", "Fetching the node's info event...");
+    println!();
+
+    match ctx.node_info().await {
+        Some(info) => println!("{}", create_node_info_table(info)),
+        None => print_no_data_message("The node publishes no info event on these relays"),
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::cli::Context;
+use crate::db::ProtocolMessage;
+use crate::parser::common::{print_key_value, print_section_header};
+use crate::parser::timeline::print_timeline_table;
+
+pub async fn execute_order_timeline(order_id: &Uuid, ctx: &Context) -> Result<()> {
+    print_section_header("🧾 Order Timeline");
+    print_key_value("📋", "Order ID", &order_id.to_string());
+    println!();
+
+    let messages = ProtocolMessage::get_by_order(&ctx.pool, &order_id.to_string()).await?;
+    println!("{}", print_timeline_table(&messages));
+    Ok(())
+}
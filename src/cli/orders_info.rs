@@ -46,14 +46,21 @@ pub async fn execute_orders_info(order_ids: &[Uuid], ctx: &Context) -> Result<()
         message_json,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for the DM response from mostro
     let recv_event = wait_for_dm(ctx, Some(&ctx.identity_keys), sent_message).await?;
 
     // Parse the incoming DM and handle the response
-    let messages =
-        crate::parser::dms::parse_dm_events(recv_event, &ctx.identity_keys, None, true).await;
+    let messages = crate::parser::dms::parse_dm_events(
+        recv_event,
+        &ctx.identity_keys,
+        None,
+        true,
+        Some(&ctx.pool),
+    )
+    .await;
     if let Some((message, _, _)) = messages.first() {
         let message_kind = message.get_inner_message_kind();
 
@@ -0,0 +1,88 @@
+use crate::cli::Context;
+use crate::parser::common::{
+    create_bold_header, create_emoji_field_row, create_field_value_header, create_standard_table,
+    format_timestamp, print_key_value, print_section_header,
+};
+use crate::price::{default_price_provider, fiat_to_sats, sats_to_fiat, PriceProvider};
+use anyhow::Result;
+use comfy_table::{Cell, CellAlignment};
+
+pub async fn execute_price(
+    currency: &Option<String>,
+    fiat_amount: &Option<f64>,
+    sats: &Option<i64>,
+    refresh: bool,
+    ctx: &Context,
+) -> Result<()> {
+    let provider = default_price_provider(&ctx.pool).with_refresh(refresh);
+
+    print_section_header("💹 Bitcoin Price");
+    print_key_value("🏦", "Source", &provider.name());
+    println!();
+
+    let rates = provider.btc_rates().await?;
+    let age = chrono::Utc::now().timestamp() - rates.fetched_at;
+
+    let Some(code) = currency.as_ref().map(|c| c.to_uppercase()) else {
+        let names = provider.currencies().await.unwrap_or_default();
+        let mut table = create_standard_table();
+        table.set_header(create_bold_header(&[
+            "💱 Code",
+            "🏷️ Currency",
+            "₿ Price of 1 BTC",
+        ]));
+        for (code, price) in &rates.rates {
+            table.add_row(vec![
+                Cell::new(code).set_alignment(CellAlignment::Center),
+                Cell::new(names.get(code).map(String::as_str).unwrap_or("-"))
+                    .set_alignment(CellAlignment::Center),
+                Cell::new(format!("{:.2}", price)).set_alignment(CellAlignment::Center),
+            ]);
+        }
+        println!("{table}");
+        println!(
+            "📅 Rates from {} ({}s old)",
+            format_timestamp(rates.fetched_at),
+            age
+        );
+        return Ok(());
+    };
+
+    let price = rates
+        .price(&code)
+        .ok_or_else(|| anyhow::anyhow!("{} has no market price on {}", code, provider.name()))?;
+
+    let mut table = create_standard_table();
+    table.set_header(create_field_value_header());
+    table.add_row(create_emoji_field_row(
+        "₿ ",
+        "Price of 1 BTC",
+        &format!("{:.2} {}", price, code),
+    ));
+    table.add_row(create_emoji_field_row(
+        "⚡ ",
+        &format!("Sats per 1 {}", code),
+        &fiat_to_sats(1.0, price).to_string(),
+    ));
+    if let Some(fiat_amount) = fiat_amount {
+        table.add_row(create_emoji_field_row(
+            "💵 ",
+            &format!("{} {}", fiat_amount, code),
+            &format!("{} sats", fiat_to_sats(*fiat_amount, price)),
+        ));
+    }
+    if let Some(sats) = sats {
+        table.add_row(create_emoji_field_row(
+            "💰 ",
+            &format!("{} sats", sats),
+            &format!("{:.2} {}", sats_to_fiat(*sats, price), code),
+        ));
+    }
+    table.add_row(create_emoji_field_row(
+        "📅 ",
+        "Fetched",
+        &format!("{} ({}s ago)", format_timestamp(rates.fetched_at), age),
+    ));
+    println!("{table}");
+    Ok(())
+}
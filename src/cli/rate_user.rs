@@ -9,6 +9,7 @@ use crate::{
     cli::Context,
     db::Order,
     parser::common::{print_info_line, print_key_value, print_section_header},
+    trade::check_order_action,
     util::{print_dm_events, send_dm, wait_for_dm},
 };
 
@@ -37,6 +38,7 @@ pub async fn execute_rate_user(order_id: &Uuid, rating: &u8, ctx: &Context) -> R
     // Get the trade keys
     let trade_keys =
         if let Ok(order_to_vote) = Order::get_by_id(&ctx.pool, &order_id.to_string()).await {
+            check_order_action(&order_to_vote, &Action::RateUser, ctx).await?;
             match order_to_vote.trade_keys.as_ref() {
                 Some(trade_keys) => Keys::parse(trade_keys)?,
                 None => {
@@ -66,6 +68,7 @@ pub async fn execute_rate_user(order_id: &Uuid, rating: &u8, ctx: &Context) -> R
         rate_message,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for incoming DM
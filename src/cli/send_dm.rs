@@ -61,6 +61,7 @@ pub async fn execute_send_dm(
         message,
         None,
         false,
+        Some(&ctx.pool),
     )
     .await?;
 
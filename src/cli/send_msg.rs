@@ -4,6 +4,7 @@ use crate::parser::common::{
     create_emoji_field_row, create_field_value_header, create_standard_table,
 };
 use crate::parser::{dms::print_commands_results, parse_dm_events};
+use crate::trade::check_order_action;
 use crate::util::{
     create_filter, print_dm_events, send_dm, wait_for_dm, ListKind, FETCH_EVENTS_TIMEOUT,
 };
@@ -33,6 +34,13 @@ pub async fn execute_send_msg(
         }
     };
 
+    // Catch actions Mostro would refuse before anything is published
+    if let Some(order_id) = &order_id {
+        if let Ok(order) = Order::get_by_id(&ctx.pool, &order_id.to_string()).await {
+            check_order_action(&order, &requested_action, ctx).await?;
+        }
+    }
+
     // Printout command information
     println!("📤 Send Message Command");
     println!("═══════════════════════════════════════");
@@ -105,6 +113,7 @@ pub async fn execute_send_msg(
                 message_json,
                 None,
                 false,
+                Some(&ctx.pool),
             );
 
             // Wait for incoming DM
@@ -134,7 +143,9 @@ pub async fn execute_send_msg(
                         .client
                         .fetch_events(subscription, FETCH_EVENTS_TIMEOUT)
                         .await?;
-                    let messages = parse_dm_events(events, &next_trade_key, Some(&2), true).await;
+                    let messages =
+                        parse_dm_events(events, &next_trade_key, Some(&2), true, Some(&ctx.pool))
+                            .await;
                     if !messages.is_empty() {
                         for (message, _, _) in messages {
                             let message_kind = message.get_inner_message_kind();
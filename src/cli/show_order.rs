@@ -0,0 +1,36 @@
+use crate::cli::Context;
+use crate::parser::common::{print_key_value, print_section_header};
+use crate::parser::orders::print_order_detail;
+use crate::price::{default_price_provider, PriceProvider};
+use crate::util::fetch_order_view;
+use anyhow::Result;
+use uuid::Uuid;
+
+pub async fn execute_show_order(order_id: &Uuid, ctx: &Context) -> Result<()> {
+    print_section_header("🔎 Order Detail");
+    print_key_value("📋", "Order ID", &order_id.to_string());
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    print_key_value("💡", "Action", "Fetching the order event from relays...");
+    println!();
+
+    let view = fetch_order_view(ctx, order_id).await?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Order {} not found on the relays: it may have been taken, expired or never existed",
+            order_id
+        )
+    })?;
+
+    let market_price = match default_price_provider(&ctx.pool).btc_rates().await {
+        Ok(rates) => rates.price(&view.order.fiat_code),
+        Err(e) => {
+            print_key_value("💡", "Market Prices", &format!("unavailable ({e})"));
+            None
+        }
+    };
+
+    println!(
+        "{}",
+        print_order_detail(&view, market_price, chrono::Utc::now().timestamp())
+    );
+    Ok(())
+}
@@ -101,6 +101,7 @@ pub async fn execute_admin_cancel_dispute(
         admin_cancel_message,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     let recv_event = wait_for_dm(ctx, Some(admin_keys), sent_message)
@@ -112,7 +113,7 @@ pub async fn execute_admin_cancel_dispute(
             )
         })?;
 
-    let messages = parse_dm_events(recv_event, admin_keys, None, true).await;
+    let messages = parse_dm_events(recv_event, admin_keys, None, true, Some(&ctx.pool)).await;
     let (message, _, sender_pubkey) = messages
         .first()
         .ok_or_else(|| anyhow::anyhow!("No response received from Mostro"))?;
@@ -193,6 +194,7 @@ pub async fn execute_admin_settle_dispute(
         admin_settle_message,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     let recv_event = wait_for_dm(ctx, Some(admin_keys), sent_message)
@@ -204,7 +206,7 @@ pub async fn execute_admin_settle_dispute(
             )
         })?;
 
-    let messages = parse_dm_events(recv_event, admin_keys, None, true).await;
+    let messages = parse_dm_events(recv_event, admin_keys, None, true, Some(&ctx.pool)).await;
     let (message, _, sender_pubkey) = messages
         .first()
         .ok_or_else(|| anyhow::anyhow!("No response received from Mostro"))?;
@@ -271,13 +273,14 @@ pub async fn execute_take_dispute(dispute_id: &Uuid, ctx: &Context) -> Result<()
         take_dispute_message,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for incoming DM response
     let recv_event = wait_for_dm(ctx, Some(admin_keys), sent_message).await?;
 
     // Parse the incoming DM
-    let messages = parse_dm_events(recv_event, admin_keys, None, true).await;
+    let messages = parse_dm_events(recv_event, admin_keys, None, true, Some(&ctx.pool)).await;
     if let Some((message, _, sender_pubkey)) = messages.first() {
         let message_kind = message.get_inner_message_kind();
         if *sender_pubkey != ctx.mostro_pubkey {
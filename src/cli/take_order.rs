@@ -1,15 +1,117 @@
 use anyhow::Result;
-use lnurl::lightning_address::LightningAddress;
 use mostro_core::prelude::*;
-use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::cli::Context;
-use crate::lightning::is_valid_invoice;
+use crate::db::Order;
+use crate::lightning::{
+    expected_buyer_amount, resolve_payment_request, InvoiceRequirements, LnurlOptions,
+};
+use crate::nip33::MakerRating;
 use crate::parser::common::{
     create_emoji_field_row, create_field_value_header, create_standard_table,
 };
-use crate::util::{print_dm_events, send_dm, wait_for_dm};
+use crate::parser::orders::maker_rating_long_text;
+use crate::util::{fetch_order_view, print_dm_events, send_dm, wait_for_dm, NodeInfo};
+
+/// Reputation a maker needs before `takesell` / `takebuy` sends anything,
+/// read from the `rating` tag of the order event. Unset bounds are not
+/// checked; with none set, orders are taken whatever the maker's history.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MakerRequirements {
+    /// Minimum average rating, 0 to 5.
+    pub min_rating: Option<f64>,
+    /// Minimum number of reviews the maker has received.
+    pub min_reviews: Option<u64>,
+}
+
+impl MakerRequirements {
+    /// Read the defaults from `MIN_MAKER_RATING` and `MIN_MAKER_REVIEWS`. A
+    /// rating outside 0-5 (or NaN) is ignored with a warning.
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok()?.trim().parse().ok()
+        }
+        let min_rating = parse::<f64>("MIN_MAKER_RATING").filter(|rating| {
+            let valid = (0.0..=5.0).contains(rating);
+            if !valid {
+                log::warn!("Ignoring MIN_MAKER_RATING={}: not between 0 and 5", rating);
+            }
+            valid
+        });
+        Self {
+            min_rating,
+            min_reviews: parse("MIN_MAKER_REVIEWS"),
+        }
+    }
+
+    /// Replace the bounds given on the command line.
+    pub fn with_overrides(self, min_rating: Option<f64>, min_reviews: Option<u64>) -> Self {
+        Self {
+            min_rating: min_rating.or(self.min_rating),
+            min_reviews: min_reviews.or(self.min_reviews),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min_rating.is_none() && self.min_reviews.is_none()
+    }
+
+    /// Why a maker with `rating` falls short; empty when they qualify. A
+    /// maker without a published rating never qualifies for a set bound.
+    pub fn problems(&self, rating: Option<&MakerRating>) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let Some(rating) = rating else {
+            return vec!["the order event publishes no maker rating".to_string()];
+        };
+        let mut problems = Vec::new();
+        if let Some(min) = self.min_rating {
+            if rating.total_rating < min {
+                problems.push(format!(
+                    "maker rating {:.1} is below the minimum of {:.1}",
+                    rating.total_rating, min
+                ));
+            }
+        }
+        if let Some(min) = self.min_reviews {
+            if rating.total_reviews < min {
+                problems.push(format!(
+                    "maker has {} review{}, fewer than the required {}",
+                    rating.total_reviews,
+                    if rating.total_reviews == 1 { "" } else { "s" },
+                    min
+                ));
+            }
+        }
+        problems
+    }
+}
+
+/// Terms a `take-sell` payout destination must meet, so a mismatched invoice
+/// or an unreachable Lightning address fails locally instead of after a
+/// round trip.
+///
+/// The expected amount is only known for fixed-sats orders: market and range
+/// orders are priced by Mostro when taken, so only the node terms (network,
+/// minimum amount and expiry window) apply to them.
+async fn take_sell_invoice_requirements(
+    order: Option<&SmallOrder>,
+    ctx: &Context,
+) -> InvoiceRequirements {
+    let node_info = ctx.node_info().await;
+    let mut requirements = node_info
+        .map(NodeInfo::invoice_requirements)
+        .unwrap_or_default();
+    let fee = node_info.and_then(|info| info.fee);
+    if let (Some(order), Some(fee)) = (order, fee) {
+        if order.amount > 0 {
+            requirements = requirements.with_amount(Some(expected_buyer_amount(order.amount, fee)));
+        }
+    }
+    requirements
+}
 
 /// Create payload based on action type and parameters
 fn create_take_order_payload(
@@ -21,16 +123,7 @@ fn create_take_order_payload(
         Action::TakeBuy => Ok(amount.map(|amt: u32| Payload::Amount(amt as i64))),
         Action::TakeSell => Ok(Some(match invoice {
             Some(inv) => {
-                let initial_payload = match LightningAddress::from_str(inv) {
-                    Ok(_) => Payload::PaymentRequest(None, inv.to_string(), None),
-                    Err(_) => match is_valid_invoice(inv) {
-                        Ok(i) => Payload::PaymentRequest(None, i.to_string(), None),
-                        Err(e) => {
-                            println!("{}", e);
-                            Payload::PaymentRequest(None, inv.to_string(), None)
-                        }
-                    },
-                };
+                let initial_payload = Payload::PaymentRequest(None, inv.to_string(), None);
 
                 match amount {
                     Some(amt) => match initial_payload {
@@ -50,21 +143,35 @@ fn create_take_order_payload(
     }
 }
 
-/// Unified function to handle both take buy and take sell orders
+/// Unified function to handle both take buy and take sell orders. Returns
+/// Mostro's answer, e.g. the hold invoice a `take-buy` seller has to pay.
 #[allow(clippy::too_many_arguments)]
 pub async fn execute_take_order(
     order_id: &Uuid,
     action: Action,
     invoice: &Option<String>,
     amount: Option<u32>,
+    lnurl: &LnurlOptions,
+    maker: &MakerRequirements,
     ctx: &Context,
-) -> Result<()> {
+) -> Result<MessageKind> {
     let action_name = match action {
         Action::TakeBuy => "take buy",
         Action::TakeSell => "take sell",
         _ => return Err(anyhow::anyhow!("Invalid action for take order")),
     };
 
+    // The order event carries the maker's reputation. Without requirements
+    // a failed fetch only costs the reputation rows; with them it is fatal,
+    // as an unknown maker can't be vouched for.
+    let view = match fetch_order_view(ctx, order_id).await {
+        Ok(view) => view,
+        Err(e) => {
+            log::warn!("Could not fetch order {}: {}", order_id, e);
+            None
+        }
+    };
+
     println!("🛒 Take Order");
     println!("═══════════════════════════════════════");
     let mut table = create_standard_table();
@@ -85,16 +192,54 @@ pub async fn execute_take_order(
             &amt.to_string(),
         ));
     }
+    if let Some(view) = &view {
+        if let Some(name) = &view.name {
+            table.add_row(create_emoji_field_row("👤 ", "Maker", name));
+        }
+        table.add_row(create_emoji_field_row(
+            "⭐ ",
+            "Maker Rating",
+            &maker_rating_long_text(view.maker_rating.as_ref()),
+        ));
+    }
     table.add_row(create_emoji_field_row(
         "🎯 ",
         "Mostro PubKey",
         &ctx.mostro_pubkey.to_string(),
     ));
     println!("{table}");
+
+    if !maker.is_empty() {
+        let Some(view) = &view else {
+            return Err(anyhow::anyhow!(
+                "Order {} was not found on the relays, so the maker's reputation can't be checked",
+                order_id
+            ));
+        };
+        let problems = maker.problems(view.maker_rating.as_ref());
+        if !problems.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Refusing to take order {}: {}",
+                order_id,
+                problems.join("; ")
+            ));
+        }
+    }
     println!("💡 Taking order from Mostro...\n");
 
+    // Bolt11 invoices are checked and Lightning addresses resolved against
+    // the order and node terms before anything is published.
+    let invoice = match invoice {
+        Some(inv) => {
+            let order = view.as_ref().map(|view| &view.order);
+            let requirements = take_sell_invoice_requirements(order, ctx).await;
+            Some(resolve_payment_request(inv, &requirements, lnurl).await?)
+        }
+        None => None,
+    };
+
     // Create payload based on action type
-    let payload = create_take_order_payload(action.clone(), invoice, amount)?;
+    let payload = create_take_order_payload(action.clone(), &invoice, amount)?;
 
     // Create request id
     let request_id = Uuid::new_v4().as_u128() as u64;
@@ -130,13 +275,27 @@ pub async fn execute_take_order(
         message_json,
         None,
         false,
+        Some(&ctx.pool),
     );
 
     // Wait for the DM to be sent from mostro
     let recv_event = wait_for_dm(ctx, None, sent_message).await?;
 
     // Parse the incoming DM
-    print_dm_events(recv_event, request_id, ctx, None).await?;
+    let answer = print_dm_events(recv_event, request_id, ctx, None).await?;
 
-    Ok(())
+    // Mostro's answer only carries whole fiat units: keep the exact amount
+    // the order event published on the row it saved. A part of a range order
+    // is taken for a whole amount already.
+    if let Some(exact) = view
+        .and_then(|view| view.fiat_amount)
+        .filter(|_| amount.is_none())
+    {
+        if let Ok(mut order) = Order::get_by_id(&ctx.pool, &order_id.to_string()).await {
+            if order.fiat() != exact {
+                order.set_fiat_range(exact).save(&ctx.pool).await?;
+            }
+        }
+    }
+    Ok(answer)
 }
@@ -0,0 +1,219 @@
+use anyhow::{anyhow, Result};
+use mostro_core::prelude::*;
+use std::str::FromStr;
+
+use crate::cli::new_order::new_order_problems;
+use crate::cli::Context;
+use crate::db::OrderTemplate;
+use crate::fiat::FiatRange;
+use crate::lightning::lnurl_pay::LnurlDestination;
+use crate::parser::common::{print_key_value, print_section_header, print_success_message};
+use crate::parser::templates::print_templates_table;
+
+/// `neworder` terms, each given on the command line or unset.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderTerms {
+    pub kind: Option<String>,
+    pub fiat_code: Option<String>,
+    pub fiat_amount: Option<FiatRange>,
+    pub amount: Option<i64>,
+    pub payment_method: Option<String>,
+    pub premium: Option<i64>,
+    pub invoice: Option<String>,
+    pub expiration_days: Option<i64>,
+}
+
+/// Complete `neworder` terms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewOrderTerms {
+    pub kind: String,
+    pub fiat_code: String,
+    pub fiat_amount: FiatRange,
+    /// Fixed sats; 0 for a market-priced order.
+    pub amount: i64,
+    pub payment_method: String,
+    pub premium: i64,
+    pub invoice: Option<String>,
+    pub expiration_days: i64,
+}
+
+impl OrderTerms {
+    /// These terms, with the unset ones taken from `template`. Overriding
+    /// how the order is priced drops the template's other pricing: a sats
+    /// amount clears its premium, a premium or a fiat range its sats amount.
+    pub fn with_template(self, template: &OrderTemplate) -> Result<NewOrderTerms> {
+        let fixes_sats = self.amount.is_some_and(|a| a != 0);
+        let market_priced = self.premium.is_some_and(|p| p != 0)
+            || self.fiat_amount.is_some_and(|f| f.max.is_some());
+        let fiat_amount = match self.fiat_amount {
+            Some(fiat_amount) => fiat_amount,
+            None => FiatRange::parse(&template.fiat_amount)?,
+        };
+        Ok(NewOrderTerms {
+            kind: self.kind.unwrap_or_else(|| template.kind.clone()),
+            fiat_code: self.fiat_code.unwrap_or_else(|| template.fiat_code.clone()),
+            fiat_amount,
+            amount: self
+                .amount
+                .unwrap_or(if market_priced { 0 } else { template.amount }),
+            payment_method: self
+                .payment_method
+                .unwrap_or_else(|| template.payment_method.clone()),
+            premium: self
+                .premium
+                .unwrap_or(if fixes_sats { 0 } else { template.premium }),
+            invoice: self.invoice.or_else(|| template.invoice.clone()),
+            expiration_days: self.expiration_days.unwrap_or(template.expiration_days),
+        })
+    }
+
+    /// The terms without a template; kind, currency, fiat amount and payment
+    /// method are required.
+    pub fn complete(self) -> Result<NewOrderTerms> {
+        let missing = |flag: &str| anyhow!("neworder needs {} unless a --template is given", flag);
+        Ok(NewOrderTerms {
+            kind: self.kind.ok_or_else(|| missing("--kind"))?,
+            fiat_code: self.fiat_code.ok_or_else(|| missing("--fiat-code"))?,
+            fiat_amount: self.fiat_amount.ok_or_else(|| missing("--fiat-amount"))?,
+            amount: self.amount.unwrap_or(0),
+            payment_method: self
+                .payment_method
+                .ok_or_else(|| missing("--payment-method"))?,
+            premium: self.premium.unwrap_or(0),
+            invoice: self.invoice,
+            expiration_days: self.expiration_days.unwrap_or(0),
+        })
+    }
+}
+
+/// Payment methods as a clean comma-separated list, e.g. `zelle, bank
+/// transfer,` becomes `zelle,bank transfer`.
+pub fn normalize_payment_methods(methods: &str) -> String {
+    methods
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Build the template `savetemplate` stores, checking the terms make an order
+/// Mostro would accept (node terms are checked when the order is sent).
+pub fn new_order_template(name: &str, terms: NewOrderTerms) -> Result<OrderTemplate> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("A template needs a name"));
+    }
+    let kind = mostro_core::order::Kind::from_str(&terms.kind.to_lowercase())
+        .map_err(|_| anyhow!("Invalid order kind '{}'", terms.kind))?;
+    let fiat_code = terms.fiat_code.to_uppercase();
+    terms.fiat_amount.check_currency(&fiat_code)?;
+    let (fiat_min, fiat_max) = terms.fiat_amount.to_whole_units(&fiat_code)?;
+    let payment_method = normalize_payment_methods(&terms.payment_method);
+    if payment_method.is_empty() {
+        return Err(anyhow!("A template needs a payment method"));
+    }
+    if let Some(invoice) = &terms.invoice {
+        if kind != mostro_core::order::Kind::Buy {
+            return Err(anyhow!("Only buy templates take a payout --invoice"));
+        }
+        if LnurlDestination::parse(invoice).is_none() {
+            return Err(anyhow!(
+                "A template needs a Lightning address or LNURL: a bolt11 can only be paid once"
+            ));
+        }
+    }
+
+    let (fiat_amount, min_amount, max_amount) = match fiat_max {
+        Some(max) => (0, Some(fiat_min), Some(max)),
+        None => (fiat_min, None, None),
+    };
+    let order = SmallOrder {
+        kind: Some(kind),
+        amount: terms.amount,
+        fiat_code: fiat_code.clone(),
+        fiat_amount,
+        min_amount,
+        max_amount,
+        payment_method: payment_method.clone(),
+        premium: terms.premium,
+        ..Default::default()
+    };
+    let problems = new_order_problems(&order, terms.expiration_days, None, None);
+    if !problems.is_empty() {
+        return Err(anyhow!("{}", problems.join("; ")));
+    }
+
+    Ok(OrderTemplate {
+        name: name.to_string(),
+        kind: kind.to_string(),
+        fiat_code,
+        fiat_amount: terms.fiat_amount.to_string(),
+        amount: terms.amount,
+        payment_method,
+        premium: terms.premium,
+        expiration_days: terms.expiration_days,
+        invoice: terms.invoice,
+        created_at: 0,
+    })
+}
+
+/// The `neworder` terms: `terms` over the named template, if any.
+pub async fn resolve_new_order(
+    template: Option<&str>,
+    terms: OrderTerms,
+    ctx: &Context,
+) -> Result<NewOrderTerms> {
+    let Some(name) = template else {
+        return terms.complete();
+    };
+    let template = OrderTemplate::get_by_name(&ctx.pool, name)
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "No template named '{}', see `mostro-cli listtemplates`",
+                name
+            )
+        })?;
+    terms.with_template(&template)
+}
+
+pub async fn execute_save_template(name: &str, terms: NewOrderTerms, ctx: &Context) -> Result<()> {
+    let mut template = new_order_template(name, terms)?;
+    let replaced = OrderTemplate::get_by_name(&ctx.pool, &template.name)
+        .await?
+        .is_some();
+    template.save(&ctx.pool).await?;
+
+    print_section_header("📝 Order Template");
+    print_key_value("🏷️", "Name", &template.name);
+    print_key_value("📈", "Kind", &template.kind);
+    print_key_value(
+        "💰",
+        "Fiat Amount",
+        &format!("{} {}", template.fiat_amount, template.fiat_code),
+    );
+    print_key_value("💳", "Payment Method", &template.payment_method);
+    println!();
+    print_success_message(&format!(
+        "Template {}. Use it with `mostro-cli neworder --template {}`",
+        if replaced { "updated" } else { "saved" },
+        template.name
+    ));
+    Ok(())
+}
+
+pub async fn execute_list_templates(ctx: &Context) -> Result<()> {
+    print_section_header("📝 Order Templates");
+    let templates = OrderTemplate::get_all(&ctx.pool).await?;
+    println!("{}", print_templates_table(&templates));
+    Ok(())
+}
+
+pub async fn execute_delete_template(name: &str, ctx: &Context) -> Result<()> {
+    if !OrderTemplate::delete_by_name(&ctx.pool, name).await? {
+        return Err(anyhow!("No template named '{}'", name));
+    }
+    print_success_message(&format!("Template '{}' deleted", name));
+    Ok(())
+}
@@ -0,0 +1,339 @@
+use anyhow::{anyhow, Result};
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use std::io::Write;
+use std::str::FromStr;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines, Stdin};
+use uuid::Uuid;
+
+use crate::cli::add_invoice::execute_add_invoice;
+use crate::cli::rate_user::execute_rate_user;
+use crate::cli::take_order::{execute_take_order, MakerRequirements};
+use crate::cli::{Commands, Context};
+use crate::db::{Order, User};
+use crate::fiat::{FiatAmount, FiatRange};
+use crate::lightning::LnurlOptions;
+use crate::parser::common::{print_key_value, print_section_header};
+use crate::parser::dms::print_direct_messages_with_qr;
+use crate::parser::orders::print_order_detail;
+use crate::trade::{
+    order_messages, role_from_messages, step_after, step_for_status, TradeChoice, TradeRole,
+    TradeStep,
+};
+use crate::util::{fetch_order_view, record_bond_message, run_simple_order_msg, DmStream};
+
+type Input = Lines<BufReader<Stdin>>;
+
+/// Print `prompt` and read one answer; `None` once standard input closes.
+async fn ask(input: &mut Input, prompt: &str) -> Result<Option<String>> {
+    print!("{} ", prompt);
+    std::io::stdout().flush()?;
+    Ok(input.next_line().await?.map(|line| line.trim().to_string()))
+}
+
+/// Ask a yes/no question, defaulting to no.
+async fn confirm(input: &mut Input, prompt: &str) -> Result<bool> {
+    let answer = ask(input, &format!("{} (y/N)", prompt)).await?;
+    Ok(matches!(
+        answer.as_deref().map(str::to_lowercase).as_deref(),
+        Some("y" | "yes")
+    ))
+}
+
+/// Print the step and what can be done from it.
+fn print_step(step: TradeStep) {
+    println!();
+    print_key_value("🧭", "Step", step.description());
+    let choices = step.choices();
+    if choices.is_empty() {
+        println!("💡 Waiting for Mostro; q to quit");
+    } else {
+        let listed: Vec<String> = choices
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| format!("[{}] {}", i + 1, choice.name()))
+            .collect();
+        println!("💡 {}, or q to quit", listed.join("  "));
+    }
+}
+
+/// The side of a trade when nothing says it: ask.
+async fn ask_role(input: &mut Input) -> Result<TradeRole> {
+    loop {
+        let answer = ask(input, "Are you the buyer or the seller in this trade?").await?;
+        match answer.as_deref().map(str::to_lowercase).as_deref() {
+            Some("buyer" | "b") => return Ok(TradeRole::Buyer),
+            Some("seller" | "s") => return Ok(TradeRole::Seller),
+            Some(_) => println!("❌ Answer buyer or seller"),
+            None => return Err(anyhow!("No answer on standard input")),
+        }
+    }
+}
+
+/// Pick up an order we already trade, from its trade keys and the messages
+/// Mostro sent about it.
+async fn resume_trade<'a>(
+    order: &Order,
+    order_id: &Uuid,
+    input: &mut Input,
+    ctx: &'a Context,
+) -> Result<(DmStream<'a>, TradeRole, TradeStep)> {
+    let keys = Keys::parse(
+        order
+            .trade_keys
+            .as_deref()
+            .ok_or_else(|| anyhow!("Order {} has no trade keys", order_id))?,
+    )?;
+    print_key_value(
+        "💵",
+        "Fiat Amount",
+        &format!("{} {}", order.fiat(), order.fiat_code),
+    );
+    let stream = DmStream::start(ctx, &keys).await?;
+
+    let history = order_messages(order_id, &keys, None, ctx).await?;
+    if !history.is_empty() {
+        print_direct_messages_with_qr(&history, Some(ctx.mostro_pubkey), None, &ctx.qr).await?;
+    }
+    let kinds: Vec<MessageKind> = history
+        .iter()
+        .map(|(message, _, _)| message.get_inner_message_kind().clone())
+        .collect();
+    let kind = order
+        .kind
+        .as_deref()
+        .and_then(|k| mostro_core::order::Kind::from_str(k).ok());
+    let role = match role_from_messages(kind, order.is_mine, &keys.public_key(), &kinds) {
+        Some(role) => role,
+        None => ask_role(input).await?,
+    };
+    let status = order.status().unwrap_or(Status::Pending);
+    let step = kinds
+        .iter()
+        .filter_map(|message| step_after(role, &message.action))
+        .next_back()
+        .unwrap_or_else(|| step_for_status(role, status));
+    Ok((stream, role, step))
+}
+
+/// Take an order from the book, asking for what the take needs.
+async fn take_trade<'a>(
+    order_id: &Uuid,
+    input: &mut Input,
+    ctx: &'a Context,
+) -> Result<(DmStream<'a>, TradeRole, TradeStep)> {
+    let view = fetch_order_view(ctx, order_id).await?.ok_or_else(|| {
+        anyhow!(
+            "Order {} is neither a local order nor in the book",
+            order_id
+        )
+    })?;
+    println!(
+        "{}",
+        print_order_detail(&view, None, chrono::Utc::now().timestamp())
+    );
+    if view.order.status != Some(Status::Pending) {
+        return Err(anyhow!(
+            "Order {} is not pending, it can't be taken",
+            order_id
+        ));
+    }
+    let kind = view
+        .order
+        .kind
+        .ok_or_else(|| anyhow!("Order {} has no kind", order_id))?;
+    let role = TradeRole::new(kind, false);
+    if !confirm(input, &format!("Take this order as the {}?", role)).await? {
+        return Err(anyhow!("Order not taken"));
+    }
+
+    let amount = match view.fiat_range() {
+        range @ FiatRange {
+            min,
+            max: Some(max),
+        } => {
+            let prompt = format!(
+                "Fiat amount to trade ({}-{} {}):",
+                min, max, view.order.fiat_code
+            );
+            let answer = ask(input, &prompt).await?.unwrap_or_default();
+            let amount = answer
+                .parse::<u32>()
+                .map_err(|_| anyhow!("Invalid fiat amount '{}'", answer))?;
+            if !range.contains(FiatAmount::from_whole(amount.into())) {
+                return Err(anyhow!(
+                    "Fiat amount {} is outside the order range {}-{} {}",
+                    amount,
+                    min,
+                    max,
+                    view.order.fiat_code
+                ));
+            }
+            Some(amount)
+        }
+        _ => None,
+    };
+    let (action, invoice) = match kind {
+        mostro_core::order::Kind::Sell => {
+            let answer = ask(
+                input,
+                "Invoice or Lightning address to be paid at (Enter to send one later):",
+            )
+            .await?;
+            (Action::TakeSell, answer.filter(|a| !a.is_empty()))
+        }
+        mostro_core::order::Kind::Buy => (Action::TakeBuy, None),
+    };
+
+    let trade_ctx = ctx.for_next_trade().await?;
+    // Listen on the new trade key before Mostro can answer on it
+    let stream = DmStream::start(ctx, &trade_ctx.trade_keys).await?;
+    let result = execute_take_order(
+        order_id,
+        action,
+        &invoice,
+        amount,
+        &LnurlOptions::default(),
+        &MakerRequirements::from_env(),
+        &trade_ctx,
+    )
+    .await;
+    User::use_trade_index(&ctx.pool, trade_ctx.trade_index).await?;
+    let reply = match result {
+        Ok(reply) => reply,
+        Err(e) => {
+            stream.close().await;
+            return Err(e);
+        }
+    };
+    let step = step_after(role, &reply.action).unwrap_or(TradeStep::WaitingForCounterparty);
+    Ok((stream, role, step))
+}
+
+/// Carry out `choice`, asking for what it needs. Mostro's answer arrives on
+/// the trade stream.
+async fn run_choice(
+    choice: TradeChoice,
+    order_id: &Uuid,
+    input: &mut Input,
+    ctx: &Context,
+) -> Result<()> {
+    let id = *order_id;
+    match choice {
+        TradeChoice::AddInvoice => {
+            let invoice = ask(input, "Invoice or Lightning address:")
+                .await?
+                .filter(|i| !i.is_empty())
+                .ok_or_else(|| anyhow!("No invoice given"))?;
+            execute_add_invoice(order_id, &invoice, &LnurlOptions::default(), ctx).await
+        }
+        TradeChoice::FiatSent => {
+            if !confirm(input, "Have you sent the fiat?").await? {
+                return Ok(());
+            }
+            run_simple_order_msg(Commands::FiatSent { order_id: id }, Some(id), ctx).await
+        }
+        TradeChoice::Release => {
+            if !confirm(input, "Release the sats? This can't be undone").await? {
+                return Ok(());
+            }
+            run_simple_order_msg(Commands::Release { order_id: id }, Some(id), ctx).await
+        }
+        TradeChoice::Rate => {
+            let answer = ask(input, "Rating (1-5):").await?.unwrap_or_default();
+            let rating = answer
+                .parse::<u8>()
+                .ok()
+                .filter(|r| (1..=5).contains(r))
+                .ok_or_else(|| anyhow!("Invalid rating '{}', expected 1 to 5", answer))?;
+            execute_rate_user(order_id, &rating, ctx).await
+        }
+        TradeChoice::Dispute => {
+            if !confirm(input, "Open a dispute?").await? {
+                return Ok(());
+            }
+            run_simple_order_msg(Commands::Dispute { order_id: id }, Some(id), ctx).await
+        }
+        TradeChoice::Cancel => {
+            if !confirm(input, "Cancel the order?").await? {
+                return Ok(());
+            }
+            run_simple_order_msg(Commands::Cancel { order_id: id }, Some(id), ctx).await
+        }
+    }
+}
+
+pub async fn execute_trade(order_id: &Uuid, ctx: &Context) -> Result<()> {
+    print_section_header("🧭 Trade");
+    print_key_value("📋", "Order ID", &order_id.to_string());
+    print_key_value("🎯", "Mostro PubKey", &ctx.mostro_pubkey.to_string());
+    println!();
+
+    let mut input = BufReader::new(tokio::io::stdin()).lines();
+    let (mut stream, role, mut step) =
+        match Order::get_by_id(&ctx.pool, &order_id.to_string()).await {
+            Ok(order) => resume_trade(&order, order_id, &mut input, ctx).await?,
+            Err(_) => take_trade(order_id, &mut input, ctx).await?,
+        };
+    print_key_value("👤", "Role", &role.to_string());
+
+    print_step(step);
+    while step != TradeStep::Done {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            message = stream.next() => {
+                let (message, created_at) = message?;
+                let inner = message.get_inner_message_kind();
+                if inner.id.is_some_and(|id| id != *order_id) {
+                    continue;
+                }
+                // Replies to our own requests were shown by the command that
+                // sent them
+                if inner.request_id.is_none() {
+                    if let Err(e) = record_bond_message(&ctx.pool, inner, None).await {
+                        println!("⚠️  Failed to update bond records: {}", e);
+                    }
+                    let messages = [(message.clone(), created_at, ctx.mostro_pubkey)];
+                    print_direct_messages_with_qr(&messages, Some(ctx.mostro_pubkey), None, &ctx.qr)
+                        .await?;
+                }
+                match step_after(role, &inner.action) {
+                    Some(next) if next != step => {
+                        step = next;
+                        print_step(step);
+                    }
+                    _ => {}
+                }
+            }
+            line = input.next_line() => {
+                let Some(line) = line? else { break };
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                if line.eq_ignore_ascii_case("q") {
+                    break;
+                }
+                let choices = step.choices();
+                let Some(choice) = TradeChoice::pick(line, &choices) else {
+                    println!("❌ '{}' is not a choice at this step", line);
+                    print_step(step);
+                    continue;
+                };
+                if let Err(e) = run_choice(choice, order_id, &mut input, ctx).await {
+                    println!("❌ {}", e);
+                } else if choice == TradeChoice::Rate {
+                    step = TradeStep::Done;
+                }
+                if step != TradeStep::Done {
+                    print_step(step);
+                }
+            }
+        }
+    }
+    stream.close().await;
+    if step == TradeStep::Done {
+        print_key_value("🏁", "Step", step.description());
+    }
+    Ok(())
+}
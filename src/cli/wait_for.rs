@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Result};
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::cli::Context;
+use crate::db::Order;
+use crate::error::WaitForError;
+use crate::trade::order_messages;
+use crate::util::DmStream;
+
+/// A Mostro action by name, as sent on the wire (`hold-invoice-payment-accepted`)
+/// or as written in mostro-core (`HoldInvoicePaymentAccepted`).
+pub fn parse_action(name: &str) -> Result<Action> {
+    let mut kebab = String::new();
+    for c in name.trim().chars() {
+        match c {
+            'A'..='Z' => {
+                if !kebab.is_empty() && !kebab.ends_with('-') {
+                    kebab.push('-');
+                }
+                kebab.push(c.to_ascii_lowercase());
+            }
+            '_' => kebab.push('-'),
+            _ => kebab.push(c),
+        }
+    }
+    serde_json::from_value(serde_json::Value::String(kebab))
+        .map_err(|_| anyhow!("Unknown Mostro action '{}'", name))
+}
+
+/// What `message` means to a `waitfor` on `order_id`: `Some(Ok)` for one of
+/// the awaited `actions`, `Some(Err)` for a cant-do (unless awaited), `None`
+/// for anything else.
+pub fn wait_outcome(
+    message: &MessageKind,
+    order_id: &Uuid,
+    actions: &[Action],
+) -> Option<Result<(), WaitForError>> {
+    if message.id != Some(*order_id) {
+        return None;
+    }
+    if actions.contains(&message.action) {
+        return Some(Ok(()));
+    }
+    match (&message.action, &message.payload) {
+        (Action::CantDo, Some(Payload::CantDo(reason))) => {
+            Some(Err(WaitForError::CantDo(reason.clone())))
+        }
+        (Action::CantDo, _) => Some(Err(WaitForError::CantDo(None))),
+        _ => None,
+    }
+}
+
+/// The message of the `--since` lookback that ends a `waitfor` on
+/// `order_id`, from `messages` oldest first: the latest awaited action, else
+/// the latest cant-do. A cant-do the awaited action came before or after
+/// doesn't undo it.
+pub fn lookback_outcome<'a>(
+    messages: &'a [Message],
+    order_id: &Uuid,
+    actions: &[Action],
+) -> Option<(&'a Message, Result<(), WaitForError>)> {
+    let outcomes = || {
+        messages.iter().rev().filter_map(|message| {
+            wait_outcome(message.get_inner_message_kind(), order_id, actions)
+                .map(|outcome| (message, outcome))
+        })
+    };
+    outcomes()
+        .find(|(_, outcome)| outcome.is_ok())
+        .or_else(|| outcomes().next())
+}
+
+/// Print the message ending the wait as one line of JSON.
+fn report(message: &Message, outcome: Result<(), WaitForError>) -> Result<()> {
+    let json = message
+        .as_json()
+        .map_err(|e| anyhow!("Failed to serialize message: {}", e))?;
+    println!("{}", json);
+    Ok(outcome?)
+}
+
+pub async fn execute_wait_for(
+    order_id: &Uuid,
+    actions: &[Action],
+    timeout: u64,
+    since: i64,
+    ctx: &Context,
+) -> Result<()> {
+    let order = Order::get_by_id(&ctx.pool, &order_id.to_string())
+        .await
+        .map_err(|_| anyhow!("Order {} is not stored locally", order_id))?;
+    let keys = Keys::parse(
+        order
+            .trade_keys
+            .as_deref()
+            .ok_or_else(|| anyhow!("Order {} has no trade keys", order_id))?,
+    )?;
+
+    // Subscribe before looking back, so no message falls in between
+    let mut stream = DmStream::start(ctx, &keys).await?;
+    let result = async {
+        if since > 0 {
+            let messages: Vec<Message> = order_messages(order_id, &keys, Some(&since), ctx)
+                .await?
+                .into_iter()
+                .map(|(message, _, _)| message)
+                .collect();
+            if let Some((message, outcome)) = lookback_outcome(&messages, order_id, actions) {
+                return report(message, outcome);
+            }
+        }
+        let deadline = async {
+            match timeout {
+                0 => std::future::pending().await,
+                seconds => tokio::time::sleep(Duration::from_secs(seconds)).await,
+            }
+        };
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => return Err(WaitForError::Timeout(timeout).into()),
+                next = stream.next() => {
+                    let (message, _) = next?;
+                    if let Some(outcome) =
+                        wait_outcome(message.get_inner_message_kind(), order_id, actions)
+                    {
+                        return report(&message, outcome);
+                    }
+                }
+            }
+        }
+    }
+    .await;
+    stream.close().await;
+    result
+}
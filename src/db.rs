@@ -1,3 +1,4 @@
+use crate::fiat::{FiatAmount, FiatRange};
 use crate::util::get_mcli_path;
 use anyhow::Result;
 use mostro_core::prelude::*;
@@ -44,7 +45,8 @@ pub async fn connect() -> Result<Pool<Sqlite>> {
               buyer_invoice TEXT,
               request_id INTEGER,
               created_at INTEGER,
-              expires_at INTEGER
+              expires_at INTEGER,
+              fiat_range TEXT
           );
           CREATE TABLE IF NOT EXISTS users (
               i0_pubkey char(64) PRIMARY KEY,
@@ -57,6 +59,8 @@ pub async fn connect() -> Result<Pool<Sqlite>> {
         .execute(&pool)
         .await?;
 
+        migrate_add_tables(&pool).await?;
+
         let mnemonic = match Mnemonic::generate(12) {
             Ok(m) => m.to_string(),
             Err(e) => {
@@ -85,6 +89,11 @@ pub async fn connect() -> Result<Pool<Sqlite>> {
 
         // Migration: Drop buyer_token and seller_token columns if they exist
         migrate_remove_token_columns(&pool).await?;
+        // Migration: Create tables added after the initial schema
+        migrate_add_tables(&pool).await?;
+        // Migration: Add orders columns introduced after the initial schema,
+        // which reads the messages table
+        migrate_add_order_columns(&pool).await?;
     }
 
     Ok(pool)
@@ -167,6 +176,158 @@ async fn migrate_remove_token_columns(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
+/// Add the `orders` columns introduced after the original schema to an
+/// existing database. Older rows keep them `NULL`.
+///
+/// Those older rows were also saved with `is_mine` set for taken orders, so
+/// it is rewritten from the logged messages when the columns are added: a
+/// `take-sell`/`take-buy` clears it, a `new-order` with the row's request id
+/// keeps it, and without either it becomes `NULL` (unknown).
+pub(crate) async fn migrate_add_order_columns(pool: &SqlitePool) -> Result<()> {
+    let fiat_range_exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM pragma_table_info('orders') WHERE name = 'fiat_range'",
+    )
+    .fetch_one(pool)
+    .await?;
+    if fiat_range_exists == 0 {
+        println!("Adding fiat_range column to orders...");
+        sqlx::query("ALTER TABLE orders ADD COLUMN fiat_range TEXT")
+            .execute(pool)
+            .await?;
+
+        println!("Checking which legacy orders were taken...");
+        sqlx::query(
+            r#"
+          UPDATE orders SET is_mine = CASE
+              WHEN EXISTS (SELECT 1 FROM messages m
+                           WHERE m.action IN ('take-sell', 'take-buy')
+                           AND m.order_id = orders.id) THEN 0
+              WHEN EXISTS (SELECT 1 FROM messages m
+                           WHERE m.action = 'new-order'
+                           AND m.request_id = orders.request_id) THEN 1
+              ELSE NULL
+          END
+          WHERE is_mine = 1
+          "#,
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Create the tables introduced after the original `orders`/`users` schema.
+/// Every statement is idempotent, so this runs on new and existing databases.
+pub(crate) async fn migrate_add_tables(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        r#"
+          CREATE TABLE IF NOT EXISTS bonds (
+              order_id TEXT NOT NULL,
+              role TEXT NOT NULL,
+              status TEXT NOT NULL,
+              amount INTEGER NOT NULL,
+              hold_invoice TEXT,
+              payout_invoice TEXT,
+              slashed_at INTEGER,
+              claim_window_days INTEGER,
+              created_at INTEGER NOT NULL,
+              updated_at INTEGER NOT NULL,
+              PRIMARY KEY (order_id, role)
+          );
+          CREATE TABLE IF NOT EXISTS price_cache (
+              source TEXT NOT NULL,
+              kind TEXT NOT NULL,
+              data TEXT NOT NULL,
+              fetched_at INTEGER NOT NULL,
+              PRIMARY KEY (source, kind)
+          );
+          CREATE TABLE IF NOT EXISTS alert_rules (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              name TEXT NOT NULL,
+              kind TEXT,
+              fiat_code TEXT,
+              payment_methods TEXT NOT NULL DEFAULT '',
+              min_fiat TEXT,
+              max_fiat TEXT,
+              min_premium INTEGER,
+              max_premium INTEGER,
+              min_rating REAL,
+              notifier TEXT NOT NULL,
+              target TEXT,
+              allow_remote INTEGER NOT NULL DEFAULT 0,
+              created_at INTEGER NOT NULL
+          );
+          CREATE TABLE IF NOT EXISTS alert_hits (
+              rule_id INTEGER NOT NULL,
+              order_id TEXT NOT NULL,
+              notified_at INTEGER NOT NULL,
+              PRIMARY KEY (rule_id, order_id)
+          );
+          CREATE TABLE IF NOT EXISTS autotakes (
+              order_id TEXT PRIMARY KEY,
+              kind TEXT NOT NULL,
+              fiat_code TEXT NOT NULL,
+              fiat_amount TEXT NOT NULL,
+              status TEXT NOT NULL,
+              finished INTEGER NOT NULL DEFAULT 0,
+              taken_at INTEGER NOT NULL
+          );
+          CREATE TABLE IF NOT EXISTS maker_offers (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              name TEXT NOT NULL,
+              kind TEXT NOT NULL,
+              fiat_code TEXT NOT NULL,
+              fiat_amount TEXT NOT NULL,
+              payment_method TEXT NOT NULL,
+              premium INTEGER NOT NULL DEFAULT 0,
+              fixed_price INTEGER NOT NULL DEFAULT 0,
+              reprice_threshold REAL,
+              expiration_days INTEGER NOT NULL DEFAULT 0,
+              invoice TEXT,
+              created_at INTEGER NOT NULL
+          );
+          CREATE TABLE IF NOT EXISTS maker_orders (
+              order_id TEXT PRIMARY KEY,
+              offer_id INTEGER NOT NULL,
+              trade_index INTEGER NOT NULL,
+              parent_order_id TEXT,
+              amount INTEGER NOT NULL DEFAULT 0,
+              reference_price REAL,
+              status TEXT NOT NULL,
+              closed_reason TEXT,
+              created_at INTEGER NOT NULL,
+              closed_at INTEGER
+          );
+          CREATE TABLE IF NOT EXISTS order_templates (
+              name TEXT PRIMARY KEY,
+              kind TEXT NOT NULL,
+              fiat_code TEXT NOT NULL,
+              fiat_amount TEXT NOT NULL,
+              amount INTEGER NOT NULL DEFAULT 0,
+              payment_method TEXT NOT NULL,
+              premium INTEGER NOT NULL DEFAULT 0,
+              expiration_days INTEGER NOT NULL DEFAULT 0,
+              invoice TEXT,
+              created_at INTEGER NOT NULL
+          );
+          CREATE TABLE IF NOT EXISTS messages (
+              event_id TEXT PRIMARY KEY,
+              direction TEXT NOT NULL,
+              action TEXT NOT NULL,
+              order_id TEXT,
+              request_id INTEGER,
+              trade_index INTEGER,
+              created_at INTEGER NOT NULL,
+              payload TEXT
+          );
+          "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 #[derive(Debug, Default, Clone, sqlx::FromRow)]
 pub struct User {
     /// The user's ID is the identity pubkey
@@ -242,6 +403,17 @@ impl User {
         Ok(user)
     }
 
+    /// Mark `trade_index` used, whether or not Mostro accepted the message
+    /// sent from it, so its keys are never handed out again. Lower indexes
+    /// leave the user untouched.
+    pub async fn use_trade_index(pool: &SqlitePool, trade_index: i64) -> Result<()> {
+        let mut user = User::get(pool).await?;
+        if user.last_trade_index.is_none_or(|last| last < trade_index) {
+            user.set_last_trade_index(trade_index).save(pool).await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_last_trade_index(pool: SqlitePool) -> Result<i64> {
         let user = User::get(&pool).await?;
         match user.last_trade_index {
@@ -308,6 +480,11 @@ pub struct Order {
     pub request_id: Option<i64>,
     pub created_at: Option<i64>,
     pub expires_at: Option<i64>,
+    /// Exact fiat amount or `min-max` range as decimal text (see
+    /// `fiat::FiatRange`). `NULL` on rows written before it existed, whose
+    /// integer columns are then the amount.
+    #[sqlx(default)]
+    pub fiat_range: Option<String>,
 }
 
 impl Order {
@@ -316,12 +493,14 @@ impl Order {
         order: SmallOrder,
         trade_keys: &Keys,
         request_id: Option<i64>,
+        is_mine: bool,
     ) -> Result<Self> {
         let trade_keys_hex = trade_keys.secret_key().to_secret_hex();
         let id = match order.id {
             Some(id) => id.to_string(),
             None => uuid::Uuid::new_v4().to_string(),
         };
+        let fiat_range = FiatRange::from(&order).to_string();
         let order = Order {
             id: Some(id),
             kind: order.kind.as_ref().map(|k| k.to_string()),
@@ -335,11 +514,12 @@ impl Order {
             premium: order.premium,
             trade_keys: Some(trade_keys_hex),
             counterparty_pubkey: None,
-            is_mine: Some(true),
+            is_mine: Some(is_mine),
             buyer_invoice: None,
             request_id,
             created_at: Some(chrono::Utc::now().timestamp()),
             expires_at: None,
+            fiat_range: Some(fiat_range),
         };
 
         // Try insert; if id already exists, perform an update instead
@@ -371,8 +551,9 @@ impl Order {
             r#"
 			      INSERT INTO orders (id, kind, status, amount, min_amount, max_amount,
 			      fiat_code, fiat_amount, payment_method, premium, trade_keys,
-			      counterparty_pubkey, is_mine, buyer_invoice, request_id, created_at, expires_at)
-			      VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+			      counterparty_pubkey, is_mine, buyer_invoice, request_id, created_at, expires_at,
+			      fiat_range)
+			      VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
 			    "#,
         )
         .bind(&self.id)
@@ -392,6 +573,7 @@ impl Order {
         .bind(self.request_id)
         .bind(self.created_at)
         .bind(self.expires_at)
+        .bind(&self.fiat_range)
         .execute(pool)
         .await?
         .rows_affected();
@@ -404,7 +586,8 @@ impl Order {
 			  UPDATE orders 
 			  SET kind = ?, status = ?, amount = ?, min_amount = ?, max_amount = ?,
 			      fiat_code = ?, fiat_amount = ?, payment_method = ?, premium = ?, trade_keys = ?,
-			      counterparty_pubkey = ?, is_mine = ?, buyer_invoice = ?, request_id = ?, created_at = ?, expires_at = ?
+			      counterparty_pubkey = ?, is_mine = ?, buyer_invoice = ?, request_id = ?, created_at = ?, expires_at = ?,
+			      fiat_range = ?
 			  WHERE id = ?
 			"#,
 		)
@@ -424,6 +607,7 @@ impl Order {
 		.bind(self.request_id)
 		.bind(self.created_at)
 		.bind(self.expires_at)
+		.bind(&self.fiat_range)
 		.bind(&self.id)
 		.execute(pool)
 		.await?
@@ -437,6 +621,10 @@ impl Order {
         self
     }
 
+    pub fn status(&self) -> Option<Status> {
+        self.status.as_deref()?.parse().ok()
+    }
+
     pub fn set_status(&mut self, status: String) -> &mut Self {
         self.status = Some(status);
         self
@@ -467,6 +655,28 @@ impl Order {
         self
     }
 
+    /// The exact fiat amount or range, from `fiat_range` when the row has it.
+    pub fn fiat(&self) -> FiatRange {
+        self.fiat_range
+            .as_deref()
+            .and_then(|text| FiatRange::parse(text).ok())
+            .unwrap_or_else(|| match (self.min_amount, self.max_amount) {
+                (Some(min), Some(max)) => FiatRange {
+                    min: FiatAmount::from_whole(min),
+                    max: Some(FiatAmount::from_whole(max)),
+                },
+                _ => FiatRange {
+                    min: FiatAmount::from_whole(self.fiat_amount),
+                    max: None,
+                },
+            })
+    }
+
+    pub fn set_fiat_range(&mut self, fiat_range: FiatRange) -> &mut Self {
+        self.fiat_range = Some(fiat_range.to_string());
+        self
+    }
+
     pub fn set_payment_method(&mut self, payment_method: String) -> &mut Self {
         self.payment_method = payment_method;
         self
@@ -501,7 +711,7 @@ impl Order {
               UPDATE orders 
               SET kind = ?, status = ?, amount = ?, fiat_code = ?, min_amount = ?, max_amount = ?, 
                   fiat_amount = ?, payment_method = ?, premium = ?, trade_keys = ?, counterparty_pubkey = ?,
-                  is_mine = ?, buyer_invoice = ?, expires_at = ?
+                  is_mine = ?, buyer_invoice = ?, expires_at = ?, fiat_range = ?
               WHERE id = ?
               "#,
             )
@@ -519,6 +729,7 @@ impl Order {
             .bind(self.is_mine)
             .bind(&self.buyer_invoice)
             .bind(self.expires_at)
+            .bind(&self.fiat_range)
             .bind(id)
             .execute(pool)
             .await?;
@@ -619,6 +830,872 @@ impl Order {
     }
 }
 
+/// Which side of an anti-abuse bond this client is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BondRole {
+    /// A bond this user paid (`pay-bond-invoice`).
+    Posted,
+    /// A share of the counterparty's slashed bond owed to this user
+    /// (`add-bond-invoice` payout request).
+    Owed,
+}
+
+impl std::fmt::Display for BondRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BondRole::Posted => write!(f, "posted"),
+            BondRole::Owed => write!(f, "owed"),
+        }
+    }
+}
+
+impl std::str::FromStr for BondRole {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "posted" => Ok(BondRole::Posted),
+            "owed" => Ok(BondRole::Owed),
+            _ => Err(anyhow::anyhow!("Invalid bond role: {}", s)),
+        }
+    }
+}
+
+/// Lifecycle of a bond as seen from this client.
+///
+/// Posted bonds go `pending` (hold invoice received) → `locked` (the trade
+/// moved on, so the bond was paid) → `released` or `slashed`. Owed shares go
+/// `claimable` (payout requested) → `claimed` (invoice sent or accepted) →
+/// `paid`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BondStatus {
+    Pending,
+    Locked,
+    Released,
+    Slashed,
+    Claimable,
+    Claimed,
+    Paid,
+}
+
+impl BondStatus {
+    /// No further message can move the bond out of this status.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            BondStatus::Released | BondStatus::Slashed | BondStatus::Paid
+        )
+    }
+}
+
+impl std::fmt::Display for BondStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            BondStatus::Pending => "pending",
+            BondStatus::Locked => "locked",
+            BondStatus::Released => "released",
+            BondStatus::Slashed => "slashed",
+            BondStatus::Claimable => "claimable",
+            BondStatus::Claimed => "claimed",
+            BondStatus::Paid => "paid",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for BondStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(BondStatus::Pending),
+            "locked" => Ok(BondStatus::Locked),
+            "released" => Ok(BondStatus::Released),
+            "slashed" => Ok(BondStatus::Slashed),
+            "claimable" => Ok(BondStatus::Claimable),
+            "claimed" => Ok(BondStatus::Claimed),
+            "paid" => Ok(BondStatus::Paid),
+            _ => Err(anyhow::anyhow!("Invalid bond status: {}", s)),
+        }
+    }
+}
+
+/// An anti-abuse bond this user posted, or a slashed-bond share owed to them.
+#[derive(Debug, Default, Clone, sqlx::FromRow)]
+pub struct Bond {
+    pub order_id: String,
+    /// [`BondRole`] as text.
+    pub role: String,
+    /// [`BondStatus`] as text.
+    pub status: String,
+    /// Bond amount (posted) or counterparty share (owed), in sats.
+    pub amount: i64,
+    /// Hold invoice paid to lock a posted bond.
+    pub hold_invoice: Option<String>,
+    /// Invoice submitted to claim an owed share.
+    pub payout_invoice: Option<String>,
+    /// Slash anchor shipped with the payout request.
+    pub slashed_at: Option<i64>,
+    /// Node's `bond_payout_claim_window_days` when the request was recorded.
+    pub claim_window_days: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Bond {
+    pub fn new(order_id: &str, role: BondRole, status: BondStatus, amount: i64) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Bond {
+            order_id: order_id.to_string(),
+            role: role.to_string(),
+            status: status.to_string(),
+            amount,
+            created_at: now,
+            updated_at: now,
+            ..Default::default()
+        }
+    }
+
+    pub fn role(&self) -> Option<BondRole> {
+        self.role.parse().ok()
+    }
+
+    pub fn status(&self) -> Option<BondStatus> {
+        self.status.parse().ok()
+    }
+
+    /// Forfeit deadline of an owed share:
+    /// `slashed_at + claim_window_days * 86_400`.
+    pub fn claim_deadline(&self) -> Option<i64> {
+        let slashed_at = self.slashed_at?;
+        let days = self.claim_window_days?;
+        Some(slashed_at.saturating_add(days.saturating_mul(86_400)))
+    }
+
+    /// Insert the bond, or update the stored row for the same order and role.
+    /// Optional columns that are `None` here keep their stored value.
+    pub async fn upsert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+              INSERT INTO bonds (order_id, role, status, amount, hold_invoice, payout_invoice,
+                  slashed_at, claim_window_days, created_at, updated_at)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+              ON CONFLICT (order_id, role) DO UPDATE SET
+                  status = excluded.status,
+                  amount = CASE WHEN excluded.amount > 0 THEN excluded.amount ELSE bonds.amount END,
+                  hold_invoice = COALESCE(excluded.hold_invoice, bonds.hold_invoice),
+                  payout_invoice = COALESCE(excluded.payout_invoice, bonds.payout_invoice),
+                  slashed_at = COALESCE(excluded.slashed_at, bonds.slashed_at),
+                  claim_window_days = COALESCE(excluded.claim_window_days, bonds.claim_window_days),
+                  updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&self.order_id)
+        .bind(&self.role)
+        .bind(&self.status)
+        .bind(self.amount)
+        .bind(&self.hold_invoice)
+        .bind(&self.payout_invoice)
+        .bind(self.slashed_at)
+        .bind(self.claim_window_days)
+        .bind(self.created_at)
+        .bind(self.updated_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get(pool: &SqlitePool, order_id: &str, role: BondRole) -> Result<Option<Bond>> {
+        let bond = sqlx::query_as::<_, Bond>(
+            r#"
+            SELECT * FROM bonds WHERE order_id = ? AND role = ?
+            LIMIT 1
+            "#,
+        )
+        .bind(order_id)
+        .bind(role.to_string())
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(bond)
+    }
+
+    /// All bonds, most recently updated first.
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<Bond>> {
+        let bonds = sqlx::query_as::<_, Bond>(
+            r#"
+            SELECT * FROM bonds ORDER BY updated_at DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(bonds)
+    }
+}
+
+/// Last answer of a price provider, kept so prices survive restarts and
+/// outages (see `price::CachedPriceProvider`).
+#[derive(Debug, Default, Clone, sqlx::FromRow)]
+pub struct PriceCacheEntry {
+    /// Provider name, so switching `PRICE_API_URL` never mixes sources.
+    pub source: String,
+    /// `currencies` or `btc_rates`.
+    pub kind: String,
+    /// Provider answer as JSON.
+    pub data: String,
+    pub fetched_at: i64,
+}
+
+impl PriceCacheEntry {
+    pub async fn get(
+        pool: &SqlitePool,
+        source: &str,
+        kind: &str,
+    ) -> Result<Option<PriceCacheEntry>> {
+        let entry = sqlx::query_as::<_, PriceCacheEntry>(
+            r#"
+            SELECT * FROM price_cache WHERE source = ? AND kind = ?
+            LIMIT 1
+            "#,
+        )
+        .bind(source)
+        .bind(kind)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    pub async fn upsert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+              INSERT INTO price_cache (source, kind, data, fetched_at)
+              VALUES (?, ?, ?, ?)
+              ON CONFLICT (source, kind) DO UPDATE SET
+                  data = excluded.data,
+                  fetched_at = excluded.fetched_at
+            "#,
+        )
+        .bind(&self.source)
+        .bind(&self.kind)
+        .bind(&self.data)
+        .bind(self.fetched_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// A saved order alert: which public orders to look for and how to be told.
+/// Matching and notifying live in `alerts`.
+#[derive(Debug, Default, Clone, sqlx::FromRow)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    /// `buy` or `sell`; any kind when unset.
+    pub kind: Option<String>,
+    /// Any currency when unset.
+    pub fiat_code: Option<String>,
+    /// Comma-separated; any method when empty.
+    pub payment_methods: String,
+    /// Fiat bounds as decimal text (see `fiat::FiatAmount`).
+    pub min_fiat: Option<String>,
+    pub max_fiat: Option<String>,
+    pub min_premium: Option<i64>,
+    pub max_premium: Option<i64>,
+    pub min_rating: Option<f64>,
+    /// `stdout`, `exec` or `webhook`.
+    pub notifier: String,
+    /// Command for `exec`, URL for `webhook`.
+    pub target: Option<String>,
+    /// Whether a `webhook` target may be on another host.
+    pub allow_remote: bool,
+    pub created_at: i64,
+}
+
+impl AlertRule {
+    /// Save a new rule and return it with its id.
+    pub async fn insert(mut self, pool: &SqlitePool) -> Result<AlertRule> {
+        self.created_at = chrono::Utc::now().timestamp();
+        let result = sqlx::query(
+            r#"
+              INSERT INTO alert_rules (name, kind, fiat_code, payment_methods, min_fiat,
+              max_fiat, min_premium, max_premium, min_rating, notifier, target, allow_remote,
+              created_at)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.name)
+        .bind(&self.kind)
+        .bind(&self.fiat_code)
+        .bind(&self.payment_methods)
+        .bind(&self.min_fiat)
+        .bind(&self.max_fiat)
+        .bind(self.min_premium)
+        .bind(self.max_premium)
+        .bind(self.min_rating)
+        .bind(&self.notifier)
+        .bind(&self.target)
+        .bind(self.allow_remote)
+        .bind(self.created_at)
+        .execute(pool)
+        .await?;
+        self.id = result.last_insert_rowid();
+
+        Ok(self)
+    }
+
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<AlertRule>> {
+        let rules = sqlx::query_as::<_, AlertRule>(
+            r#"
+            SELECT * FROM alert_rules ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rules)
+    }
+
+    /// Delete a rule and its notification history; `false` if it didn't exist.
+    pub async fn delete_by_id(pool: &SqlitePool, id: i64) -> Result<bool> {
+        let rows = sqlx::query(r#"DELETE FROM alert_rules WHERE id = ?"#)
+            .bind(id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+        sqlx::query(r#"DELETE FROM alert_hits WHERE rule_id = ?"#)
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(rows > 0)
+    }
+}
+
+/// Record that `rule_id` fired for `order_id`, so each order is announced
+/// once per rule. `false` if it had already fired.
+pub async fn record_alert_hit(pool: &SqlitePool, rule_id: i64, order_id: &str) -> Result<bool> {
+    let rows = sqlx::query(
+        r#"
+          INSERT OR IGNORE INTO alert_hits (rule_id, order_id, notified_at)
+          VALUES (?, ?, ?)
+        "#,
+    )
+    .bind(rule_id)
+    .bind(order_id)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(rows > 0)
+}
+
+/// Whether `rule_id` already fired for `order_id`.
+pub async fn alert_hit_exists(pool: &SqlitePool, rule_id: i64, order_id: &str) -> Result<bool> {
+    let hit: Option<(i64,)> =
+        sqlx::query_as(r#"SELECT rule_id FROM alert_hits WHERE rule_id = ? AND order_id = ?"#)
+            .bind(rule_id)
+            .bind(order_id)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(hit.is_some())
+}
+
+/// An order taken by `autotake`, kept to enforce its open-trade and daily
+/// volume limits across restarts.
+#[derive(Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct AutoTake {
+    pub order_id: String,
+    /// Kind of the order taken: `buy` or `sell`.
+    pub kind: String,
+    pub fiat_code: String,
+    /// Fiat traded, as decimal text (see `fiat::FiatAmount`).
+    pub fiat_amount: String,
+    /// Newest order status seen since the take.
+    pub status: String,
+    /// Whether the trade is over, successfully or not.
+    pub finished: bool,
+    pub taken_at: i64,
+}
+
+impl AutoTake {
+    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+              INSERT OR REPLACE INTO autotakes (order_id, kind, fiat_code, fiat_amount,
+              status, finished, taken_at)
+              VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.order_id)
+        .bind(&self.kind)
+        .bind(&self.fiat_code)
+        .bind(&self.fiat_amount)
+        .bind(&self.status)
+        .bind(self.finished)
+        .bind(self.taken_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Takes of `fiat_code` orders made from `since` on, and every
+    /// unfinished one. Limits are counted in one currency, so takes in
+    /// others are left out.
+    pub async fn get_recent(
+        pool: &SqlitePool,
+        fiat_code: &str,
+        since: i64,
+    ) -> Result<Vec<AutoTake>> {
+        let takes = sqlx::query_as::<_, AutoTake>(
+            r#"
+            SELECT * FROM autotakes
+            WHERE fiat_code = ? COLLATE NOCASE AND (taken_at >= ? OR finished = 0)
+            ORDER BY taken_at
+            "#,
+        )
+        .bind(fiat_code)
+        .bind(since)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(takes)
+    }
+
+    pub async fn delete(pool: &SqlitePool, order_id: &str) -> Result<()> {
+        sqlx::query(r#"DELETE FROM autotakes WHERE order_id = ?"#)
+            .bind(order_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_status(
+        pool: &SqlitePool,
+        order_id: &str,
+        status: &str,
+        finished: bool,
+    ) -> Result<()> {
+        sqlx::query(r#"UPDATE autotakes SET status = ?, finished = ? WHERE order_id = ?"#)
+            .bind(status)
+            .bind(finished)
+            .bind(order_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// A standing order the `maker` daemon keeps published.
+#[derive(Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct MakerOffer {
+    pub id: i64,
+    pub name: String,
+    /// `buy` or `sell`.
+    pub kind: String,
+    pub fiat_code: String,
+    /// Amount or `min-max` range, as for `neworder -f`.
+    pub fiat_amount: String,
+    pub payment_method: String,
+    pub premium: i64,
+    /// Publish a fixed sats amount from the reference price and `premium`
+    /// instead of a market-priced order.
+    pub fixed_price: bool,
+    /// Percent the reference price may move before a fixed-price order is
+    /// replaced; never repriced when unset.
+    pub reprice_threshold: Option<f64>,
+    pub expiration_days: i64,
+    /// Payout Lightning address of buy offers.
+    pub invoice: Option<String>,
+    pub created_at: i64,
+}
+
+impl MakerOffer {
+    /// Save a new offer and return it with its id.
+    pub async fn insert(mut self, pool: &SqlitePool) -> Result<MakerOffer> {
+        self.created_at = chrono::Utc::now().timestamp();
+        let result = sqlx::query(
+            r#"
+              INSERT INTO maker_offers (name, kind, fiat_code, fiat_amount, payment_method,
+              premium, fixed_price, reprice_threshold, expiration_days, invoice, created_at)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.name)
+        .bind(&self.kind)
+        .bind(&self.fiat_code)
+        .bind(&self.fiat_amount)
+        .bind(&self.payment_method)
+        .bind(self.premium)
+        .bind(self.fixed_price)
+        .bind(self.reprice_threshold)
+        .bind(self.expiration_days)
+        .bind(&self.invoice)
+        .bind(self.created_at)
+        .execute(pool)
+        .await?;
+        self.id = result.last_insert_rowid();
+
+        Ok(self)
+    }
+
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<MakerOffer>> {
+        let offers = sqlx::query_as::<_, MakerOffer>(
+            r#"
+            SELECT * FROM maker_offers ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(offers)
+    }
+
+    /// Stop maintaining an offer; `false` if it didn't exist. Its orders
+    /// stay in `maker_orders` as history.
+    pub async fn delete_by_id(pool: &SqlitePool, id: i64) -> Result<bool> {
+        let rows = sqlx::query(r#"DELETE FROM maker_offers WHERE id = ?"#)
+            .bind(id)
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows > 0)
+    }
+}
+
+/// One order published for a [`MakerOffer`]. Each replacement points to the
+/// order it replaced, so an offer's orders form a chain.
+#[derive(Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct MakerOrder {
+    pub order_id: String,
+    pub offer_id: i64,
+    /// Trade index of the keys the order was published from.
+    pub trade_index: i64,
+    /// Order this one replaced; `None` for the first of its offer.
+    pub parent_order_id: Option<String>,
+    /// Sats of a fixed-price order; 0 when market priced.
+    pub amount: i64,
+    /// BTC price the fixed amount was computed from.
+    pub reference_price: Option<f64>,
+    /// Newest order status seen.
+    pub status: String,
+    /// Why the order was replaced: `taken`, `canceled`, `expired`,
+    /// `removed` or `repriced`. `None` while it is the offer's live order.
+    pub closed_reason: Option<String>,
+    pub created_at: i64,
+    pub closed_at: Option<i64>,
+}
+
+impl MakerOrder {
+    pub async fn insert(&self, pool: &SqlitePool) -> Result<()> {
+        sqlx::query(
+            r#"
+              INSERT OR REPLACE INTO maker_orders (order_id, offer_id, trade_index,
+              parent_order_id, amount, reference_price, status, closed_reason, created_at,
+              closed_at)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.order_id)
+        .bind(self.offer_id)
+        .bind(self.trade_index)
+        .bind(&self.parent_order_id)
+        .bind(self.amount)
+        .bind(self.reference_price)
+        .bind(&self.status)
+        .bind(&self.closed_reason)
+        .bind(self.created_at)
+        .bind(self.closed_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The live order of `offer_id`, if it has one.
+    pub async fn get_live(pool: &SqlitePool, offer_id: i64) -> Result<Option<MakerOrder>> {
+        let order = sqlx::query_as::<_, MakerOrder>(
+            r#"
+            SELECT * FROM maker_orders
+            WHERE offer_id = ? AND closed_reason IS NULL
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(offer_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(order)
+    }
+
+    pub async fn get_by_id(pool: &SqlitePool, order_id: &str) -> Result<Option<MakerOrder>> {
+        let order = sqlx::query_as::<_, MakerOrder>(
+            r#"
+            SELECT * FROM maker_orders WHERE order_id = ?
+            "#,
+        )
+        .bind(order_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(order)
+    }
+
+    /// Every order published for `offer_id`, oldest first.
+    pub async fn get_by_offer(pool: &SqlitePool, offer_id: i64) -> Result<Vec<MakerOrder>> {
+        let orders = sqlx::query_as::<_, MakerOrder>(
+            r#"
+            SELECT * FROM maker_orders WHERE offer_id = ? ORDER BY created_at, rowid
+            "#,
+        )
+        .bind(offer_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(orders)
+    }
+
+    /// Mark the order replaced for `reason` at `closed_at`.
+    pub async fn close(
+        pool: &SqlitePool,
+        order_id: &str,
+        status: &str,
+        reason: &str,
+        closed_at: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+              UPDATE maker_orders SET status = ?, closed_reason = ?, closed_at = ?
+              WHERE order_id = ?
+            "#,
+        )
+        .bind(status)
+        .bind(reason)
+        .bind(closed_at)
+        .bind(order_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Saved `neworder` flags, reused with `neworder --template`.
+#[derive(Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct OrderTemplate {
+    pub name: String,
+    /// `buy` or `sell`.
+    pub kind: String,
+    pub fiat_code: String,
+    /// Amount or `min-max` range, as for `neworder -f`.
+    pub fiat_amount: String,
+    /// Fixed sats; 0 for a market-priced order.
+    pub amount: i64,
+    pub payment_method: String,
+    pub premium: i64,
+    pub expiration_days: i64,
+    /// Payout Lightning address of buy orders.
+    pub invoice: Option<String>,
+    pub created_at: i64,
+}
+
+impl OrderTemplate {
+    /// Save the template, replacing any template with the same name.
+    pub async fn save(&mut self, pool: &SqlitePool) -> Result<()> {
+        self.created_at = chrono::Utc::now().timestamp();
+        sqlx::query(
+            r#"
+              INSERT OR REPLACE INTO order_templates (name, kind, fiat_code, fiat_amount,
+              amount, payment_method, premium, expiration_days, invoice, created_at)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.name)
+        .bind(&self.kind)
+        .bind(&self.fiat_code)
+        .bind(&self.fiat_amount)
+        .bind(self.amount)
+        .bind(&self.payment_method)
+        .bind(self.premium)
+        .bind(self.expiration_days)
+        .bind(&self.invoice)
+        .bind(self.created_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_by_name(pool: &SqlitePool, name: &str) -> Result<Option<OrderTemplate>> {
+        let template = sqlx::query_as::<_, OrderTemplate>(
+            r#"
+            SELECT * FROM order_templates WHERE name = ?
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(template)
+    }
+
+    pub async fn get_all(pool: &SqlitePool) -> Result<Vec<OrderTemplate>> {
+        let templates = sqlx::query_as::<_, OrderTemplate>(
+            r#"
+            SELECT * FROM order_templates ORDER BY name
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(templates)
+    }
+
+    /// Delete a template; `false` if it didn't exist.
+    pub async fn delete_by_name(pool: &SqlitePool, name: &str) -> Result<bool> {
+        let rows = sqlx::query(r#"DELETE FROM order_templates WHERE name = ?"#)
+            .bind(name)
+            .execute(pool)
+            .await?
+            .rows_affected();
+
+        Ok(rows > 0)
+    }
+}
+
+/// Whether a protocol message went to Mostro or came from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageDirection {
+    Sent,
+    Received,
+}
+
+impl std::fmt::Display for MessageDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageDirection::Sent => write!(f, "sent"),
+            MessageDirection::Received => write!(f, "received"),
+        }
+    }
+}
+
+impl std::str::FromStr for MessageDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sent" => Ok(MessageDirection::Sent),
+            "received" => Ok(MessageDirection::Received),
+            _ => Err(anyhow::anyhow!("Invalid message direction: {}", s)),
+        }
+    }
+}
+
+/// A Mostro protocol message this client sent or received, keyed by the
+/// Nostr event that carried it.
+#[derive(Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct ProtocolMessage {
+    pub event_id: String,
+    /// `sent` or `received`, see [`MessageDirection`].
+    pub direction: String,
+    /// Wire name of the action (`fiat-sent`).
+    pub action: String,
+    pub order_id: Option<String>,
+    pub request_id: Option<i64>,
+    pub trade_index: Option<i64>,
+    pub created_at: i64,
+    /// The decoded payload as JSON; `None` for messages without one.
+    pub payload: Option<String>,
+}
+
+impl ProtocolMessage {
+    pub fn new(
+        event_id: &EventId,
+        direction: MessageDirection,
+        message: &Message,
+        created_at: i64,
+    ) -> Result<Self> {
+        let inner = message.get_inner_message_kind();
+        let action = match serde_json::to_value(&inner.action)? {
+            serde_json::Value::String(action) => action,
+            other => other.to_string(),
+        };
+        let payload = inner
+            .payload
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        Ok(Self {
+            event_id: event_id.to_hex(),
+            direction: direction.to_string(),
+            action,
+            order_id: inner.id.map(|id| id.to_string()),
+            request_id: inner.request_id.map(|id| id as i64),
+            trade_index: inner.trade_index,
+            created_at,
+            payload,
+        })
+    }
+
+    /// Store the message; a message already stored (the same event fetched
+    /// again) is left as is. `false` if it was already there.
+    pub async fn insert(&self, pool: &SqlitePool) -> Result<bool> {
+        let rows = sqlx::query(
+            r#"
+              INSERT OR IGNORE INTO messages (event_id, direction, action, order_id,
+              request_id, trade_index, created_at, payload)
+              VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&self.event_id)
+        .bind(&self.direction)
+        .bind(&self.action)
+        .bind(&self.order_id)
+        .bind(self.request_id)
+        .bind(self.trade_index)
+        .bind(self.created_at)
+        .bind(&self.payload)
+        .execute(pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows > 0)
+    }
+
+    /// Every message about an order, oldest first. A request sent before the
+    /// order had an id (our `new-order`) is matched through the request id
+    /// Mostro's reply carries along with the order id.
+    pub async fn get_by_order(pool: &SqlitePool, order_id: &str) -> Result<Vec<ProtocolMessage>> {
+        let messages = sqlx::query_as::<_, ProtocolMessage>(
+            r#"
+            SELECT * FROM messages
+            WHERE order_id = ?
+               OR (order_id IS NULL AND request_id IN (
+                   SELECT request_id FROM messages
+                   WHERE order_id = ? AND request_id IS NOT NULL))
+            ORDER BY created_at, rowid
+            "#,
+        )
+        .bind(order_id)
+        .bind(order_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(messages)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -672,4 +1749,453 @@ mod tests {
 
         std::fs::remove_file(&path).ok();
     }
+
+    /// The `orders` table as databases created before any column was added
+    /// have it.
+    async fn legacy_orders_table(pool: &SqlitePool) {
+        sqlx::query(
+            r#"
+          CREATE TABLE orders (
+              id TEXT PRIMARY KEY, kind TEXT NOT NULL, status TEXT NOT NULL,
+              amount INTEGER NOT NULL, min_amount INTEGER, max_amount INTEGER,
+              fiat_code TEXT NOT NULL, fiat_amount INTEGER NOT NULL,
+              payment_method TEXT NOT NULL, premium INTEGER NOT NULL, trade_keys TEXT,
+              counterparty_pubkey TEXT, is_mine BOOLEAN, buyer_invoice TEXT,
+              request_id INTEGER, created_at INTEGER, expires_at INTEGER
+          );
+          "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fiat_range_migration_keeps_legacy_amounts() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        legacy_orders_table(&pool).await;
+        sqlx::query(
+            r#"
+          INSERT INTO orders (id, kind, status, amount, min_amount, max_amount, fiat_code,
+                              fiat_amount, payment_method, premium, is_mine)
+          VALUES ('legacy', 'sell', 'active', 0, 10, 100, 'EUR', 0, 'sepa', 0, 1)
+          "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+        migrate_add_order_columns(&pool).await.unwrap();
+        // Idempotent on an already migrated database
+        migrate_add_order_columns(&pool).await.unwrap();
+
+        // Rows written before the column read their integer columns
+        let mut order = Order::get_by_id(&pool, "legacy").await.unwrap();
+        assert_eq!(order.fiat_range, None);
+        assert_eq!(order.fiat(), FiatRange::parse("10-100").unwrap());
+
+        order
+            .set_fiat_range(FiatRange::parse("10.50-99.99").unwrap())
+            .save(&pool)
+            .await
+            .unwrap();
+        let order = Order::get_by_id(&pool, "legacy").await.unwrap();
+        assert_eq!(order.fiat_range.as_deref(), Some("10.50-99.99"));
+        assert_eq!(order.fiat(), FiatRange::parse("10.50-99.99").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_legacy_taker_rows_lose_is_mine() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        legacy_orders_table(&pool).await;
+        migrate_add_tables(&pool).await.unwrap();
+        // Earlier versions saved every order with is_mine = 1, taken or made
+        for (id, request_id) in [("taken", 1), ("made", 2), ("unknown", 3)] {
+            sqlx::query(
+                r#"
+              INSERT INTO orders (id, kind, status, amount, fiat_code, fiat_amount,
+                                  payment_method, premium, is_mine, request_id)
+              VALUES (?, 'sell', 'active', 0, 'EUR', 100, 'sepa', 0, 1, ?)
+              "#,
+            )
+            .bind(id)
+            .bind(request_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let logged = |event_id: &str, action: &str, order_id: Option<&str>, request_id: i64| {
+            ProtocolMessage {
+                event_id: event_id.to_string(),
+                direction: MessageDirection::Sent.to_string(),
+                action: action.to_string(),
+                order_id: order_id.map(str::to_string),
+                request_id: Some(request_id),
+                trade_index: Some(1),
+                created_at: 1_700_000_000,
+                payload: None,
+            }
+        };
+        logged("e1", "take-sell", Some("taken"), 1)
+            .insert(&pool)
+            .await
+            .unwrap();
+        logged("e2", "new-order", None, 2)
+            .insert(&pool)
+            .await
+            .unwrap();
+
+        migrate_add_order_columns(&pool).await.unwrap();
+        let is_mine = |id: &'static str| {
+            let pool = pool.clone();
+            async move { Order::get_by_id(&pool, id).await.unwrap().is_mine }
+        };
+        assert_eq!(is_mine("taken").await, Some(false));
+        assert_eq!(is_mine("made").await, Some(true));
+        assert_eq!(is_mine("unknown").await, None);
+
+        // Rows saved afterwards are left alone
+        let mut order = Order::get_by_id(&pool, "unknown").await.unwrap();
+        order.set_is_mine(true).save(&pool).await.unwrap();
+        migrate_add_order_columns(&pool).await.unwrap();
+        assert_eq!(is_mine("unknown").await, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_bond_upsert_keeps_known_fields() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+        // Idempotent on an existing database
+        migrate_add_tables(&pool).await.unwrap();
+
+        let mut bond = Bond::new("order-1", BondRole::Owed, BondStatus::Claimable, 2_500);
+        bond.slashed_at = Some(1_700_000_000);
+        bond.claim_window_days = Some(3);
+        bond.upsert(&pool).await.unwrap();
+
+        // A later update without amount or claim data must not erase them
+        let mut update = Bond::new("order-1", BondRole::Owed, BondStatus::Claimed, 0);
+        update.payout_invoice = Some("lnbc1".to_string());
+        update.upsert(&pool).await.unwrap();
+
+        let stored = Bond::get(&pool, "order-1", BondRole::Owed)
+            .await
+            .unwrap()
+            .expect("bond should be stored");
+        assert_eq!(stored.status(), Some(BondStatus::Claimed));
+        assert_eq!(stored.amount, 2_500);
+        assert_eq!(stored.claim_deadline(), Some(1_700_000_000 + 3 * 86_400));
+        assert_eq!(stored.payout_invoice.as_deref(), Some("lnbc1"));
+        assert!(Bond::get(&pool, "order-1", BondRole::Posted)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(Bond::get_all(&pool).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_alert_rules_and_hits() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let rule = AlertRule {
+            name: "cheap zelle".to_string(),
+            kind: Some("sell".to_string()),
+            payment_methods: "zelle".to_string(),
+            max_fiat: Some("500".to_string()),
+            notifier: "stdout".to_string(),
+            ..Default::default()
+        }
+        .insert(&pool)
+        .await
+        .unwrap();
+        assert!(rule.id > 0);
+        let rules = AlertRule::get_all(&pool).await.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].max_fiat.as_deref(), Some("500"));
+
+        assert!(!alert_hit_exists(&pool, rule.id, "order-1").await.unwrap());
+        assert!(record_alert_hit(&pool, rule.id, "order-1").await.unwrap());
+        assert!(!record_alert_hit(&pool, rule.id, "order-1").await.unwrap());
+        assert!(alert_hit_exists(&pool, rule.id, "order-1").await.unwrap());
+
+        assert!(AlertRule::delete_by_id(&pool, rule.id).await.unwrap());
+        assert!(!AlertRule::delete_by_id(&pool, rule.id).await.unwrap());
+        assert!(!alert_hit_exists(&pool, rule.id, "order-1").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_autotakes() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let take = |order_id: &str, taken_at: i64| AutoTake {
+            order_id: order_id.to_string(),
+            kind: "sell".to_string(),
+            fiat_code: "USD".to_string(),
+            fiat_amount: "100".to_string(),
+            status: "pending".to_string(),
+            finished: false,
+            taken_at,
+        };
+        take("old", 100).insert(&pool).await.unwrap();
+        take("new", 1_000).insert(&pool).await.unwrap();
+        assert_eq!(
+            AutoTake::get_recent(&pool, "USD", 500).await.unwrap().len(),
+            2
+        );
+
+        // Finished takes drop out once older than `since`
+        AutoTake::set_status(&pool, "old", "success", true)
+            .await
+            .unwrap();
+        let recent = AutoTake::get_recent(&pool, "USD", 500).await.unwrap();
+        assert_eq!(recent, vec![take("new", 1_000)]);
+        let all = AutoTake::get_recent(&pool, "USD", 0).await.unwrap();
+        assert_eq!(all[0].status, "success");
+        assert!(all[0].finished);
+    }
+
+    #[tokio::test]
+    async fn test_autotakes_are_counted_per_currency() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let take = |order_id: &str, fiat_code: &str, fiat_amount: &str| AutoTake {
+            order_id: order_id.to_string(),
+            kind: "sell".to_string(),
+            fiat_code: fiat_code.to_string(),
+            fiat_amount: fiat_amount.to_string(),
+            status: "pending".to_string(),
+            finished: false,
+            taken_at: 1_000,
+        };
+        take("usd", "USD", "100").insert(&pool).await.unwrap();
+        take("eur", "EUR", "400").insert(&pool).await.unwrap();
+
+        let usd = AutoTake::get_recent(&pool, "usd", 0).await.unwrap();
+        assert_eq!(usd, vec![take("usd", "USD", "100")]);
+        // A run limited in USD neither counts the EUR trade as open nor its volume
+        let ledger = crate::autotake::AutoTakeLedger::new(usd);
+        assert_eq!(ledger.open_count(), 1);
+        assert_eq!(ledger.volume_since(0).to_string(), "100");
+        let eur = AutoTake::get_recent(&pool, "EUR", 0).await.unwrap();
+        assert_eq!(eur, vec![take("eur", "EUR", "400")]);
+    }
+
+    #[tokio::test]
+    async fn test_maker_offers_and_lineage() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let offer = MakerOffer {
+            name: "usd sell".to_string(),
+            kind: "sell".to_string(),
+            fiat_code: "USD".to_string(),
+            fiat_amount: "50-500".to_string(),
+            payment_method: "zelle".to_string(),
+            premium: 2,
+            ..Default::default()
+        }
+        .insert(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            MakerOffer::get_all(&pool).await.unwrap(),
+            vec![offer.clone()]
+        );
+        assert_eq!(MakerOrder::get_live(&pool, offer.id).await.unwrap(), None);
+
+        let first = MakerOrder {
+            order_id: "order-1".to_string(),
+            offer_id: offer.id,
+            trade_index: 4,
+            status: "pending".to_string(),
+            created_at: 100,
+            ..Default::default()
+        };
+        first.insert(&pool).await.unwrap();
+        MakerOrder::close(&pool, "order-1", "in-progress", "taken", 200)
+            .await
+            .unwrap();
+        let second = MakerOrder {
+            order_id: "order-2".to_string(),
+            parent_order_id: Some("order-1".to_string()),
+            trade_index: 5,
+            created_at: 200,
+            ..first.clone()
+        };
+        second.insert(&pool).await.unwrap();
+
+        assert_eq!(
+            MakerOrder::get_live(&pool, offer.id).await.unwrap(),
+            Some(second)
+        );
+        let chain = MakerOrder::get_by_offer(&pool, offer.id).await.unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].closed_reason.as_deref(), Some("taken"));
+        assert_eq!(chain[0].closed_at, Some(200));
+
+        assert!(MakerOffer::delete_by_id(&pool, offer.id).await.unwrap());
+        assert!(!MakerOffer::delete_by_id(&pool, offer.id).await.unwrap());
+        assert_eq!(
+            MakerOrder::get_by_offer(&pool, offer.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_order_templates() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let mut template = OrderTemplate {
+            name: "usd-zelle".to_string(),
+            kind: "sell".to_string(),
+            fiat_code: "USD".to_string(),
+            fiat_amount: "50-500".to_string(),
+            payment_method: "zelle".to_string(),
+            premium: 2,
+            ..Default::default()
+        };
+        template.save(&pool).await.unwrap();
+        assert_eq!(
+            OrderTemplate::get_by_name(&pool, "usd-zelle")
+                .await
+                .unwrap(),
+            Some(template.clone())
+        );
+
+        // Saving under the same name replaces the template
+        template.premium = 3;
+        template.save(&pool).await.unwrap();
+        let templates = OrderTemplate::get_all(&pool).await.unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].premium, 3);
+
+        assert!(OrderTemplate::delete_by_name(&pool, "usd-zelle")
+            .await
+            .unwrap());
+        assert!(!OrderTemplate::delete_by_name(&pool, "usd-zelle")
+            .await
+            .unwrap());
+        assert_eq!(
+            OrderTemplate::get_by_name(&pool, "usd-zelle")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protocol_messages() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let order_id = uuid::Uuid::new_v4();
+        let keys = Keys::generate();
+        let sent = Message::new_order(Some(order_id), Some(7), Some(3), Action::FiatSent, None);
+        let received = Message::new_order(
+            Some(order_id),
+            None,
+            None,
+            Action::AddInvoice,
+            Some(Payload::PaymentRequest(None, "lnbc1".to_string(), None)),
+        );
+        let sent_id = EventId::all_zeros();
+        let received_id = nostr_sdk::EventBuilder::text_note("x")
+            .sign_with_keys(&keys)
+            .unwrap()
+            .id;
+
+        let message = ProtocolMessage::new(&sent_id, MessageDirection::Sent, &sent, 200).unwrap();
+        assert_eq!(message.action, "fiat-sent");
+        assert_eq!(message.request_id, Some(7));
+        assert_eq!(message.trade_index, Some(3));
+        assert_eq!(message.payload, None);
+        assert!(message.insert(&pool).await.unwrap());
+        // The same event fetched again is stored once
+        assert!(!message.insert(&pool).await.unwrap());
+        ProtocolMessage::new(&received_id, MessageDirection::Received, &received, 100)
+            .unwrap()
+            .insert(&pool)
+            .await
+            .unwrap();
+
+        let timeline = ProtocolMessage::get_by_order(&pool, &order_id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].action, "add-invoice");
+        assert_eq!(
+            timeline[0].direction.parse::<MessageDirection>().unwrap(),
+            MessageDirection::Received
+        );
+        assert!(timeline[0].payload.as_deref().unwrap().contains("lnbc1"));
+        assert_eq!(timeline[1].event_id, sent_id.to_hex());
+        assert!(
+            ProtocolMessage::get_by_order(&pool, &uuid::Uuid::new_v4().to_string())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_protocol_messages_match_new_order_by_request_id() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        migrate_add_tables(&pool).await.unwrap();
+
+        let order_id = uuid::Uuid::new_v4();
+        let keys = Keys::generate();
+        let store = |direction, message: Message, created_at: i64| {
+            let pool = pool.clone();
+            let id = nostr_sdk::EventBuilder::text_note(created_at.to_string())
+                .sign_with_keys(&keys)
+                .unwrap()
+                .id;
+            async move {
+                ProtocolMessage::new(&id, direction, &message, created_at)
+                    .unwrap()
+                    .insert(&pool)
+                    .await
+                    .unwrap();
+            }
+        };
+        // Our new-order has no order id yet; Mostro's reply brings it
+        store(
+            MessageDirection::Sent,
+            Message::new_order(None, Some(9), Some(1), Action::NewOrder, None),
+            100,
+        )
+        .await;
+        store(
+            MessageDirection::Received,
+            Message::new_order(Some(order_id), Some(9), None, Action::NewOrder, None),
+            110,
+        )
+        .await;
+        // Another request without an order id stays out
+        store(
+            MessageDirection::Sent,
+            Message::new_order(None, Some(11), Some(2), Action::NewOrder, None),
+            120,
+        )
+        .await;
+
+        let timeline = ProtocolMessage::get_by_order(&pool, &order_id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].order_id, None);
+        assert_eq!(timeline[0].request_id, Some(9));
+        assert_eq!(
+            timeline[0].direction.parse::<MessageDirection>().unwrap(),
+            MessageDirection::Sent
+        );
+        assert_eq!(timeline[1].order_id, Some(order_id.to_string()));
+    }
 }
@@ -1,3 +1,4 @@
+use mostro_core::prelude::CantDoReason;
 use std::fmt;
 
 #[derive(Debug)]
@@ -7,6 +8,8 @@ pub enum MostroError {
     InvoiceExpiredError,
     MinExpirationTimeError,
     MinAmountError,
+    WrongAmountError,
+    WrongNetworkError,
 }
 
 impl std::error::Error for MostroError {}
@@ -19,6 +22,12 @@ impl fmt::Display for MostroError {
             MostroError::InvoiceExpiredError => write!(f, "Invoice has expired"),
             MostroError::MinExpirationTimeError => write!(f, "Minimal expiration time on invoice"),
             MostroError::MinAmountError => write!(f, "Minimal payment amount"),
+            MostroError::WrongAmountError => {
+                write!(f, "Invoice amount does not match the order")
+            }
+            MostroError::WrongNetworkError => {
+                write!(f, "Invoice network does not match the Mostro node")
+            }
         }
     }
 }
@@ -40,3 +49,106 @@ impl From<std::num::ParseIntError> for MostroError {
         MostroError::ParsingNumberError
     }
 }
+
+/// Why `neworder` stopped before sending the order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewOrderError {
+    /// The user answered no to the confirmation prompt.
+    Declined,
+    /// The confirmation answer was neither yes nor no.
+    UnrecognizedAnswer(String),
+    /// Standard input closed before a confirmation; pass `--yes` instead.
+    ConfirmationRequired,
+    /// A market price order for a currency the price provider can't quote.
+    NoMarketPrice { fiat_code: String, provider: String },
+    /// The order breaks the order shape or the node terms.
+    Rejected(Vec<String>),
+}
+
+impl std::error::Error for NewOrderError {}
+
+impl fmt::Display for NewOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewOrderError::Declined => write!(f, "Order cancelled, nothing was sent"),
+            NewOrderError::UnrecognizedAnswer(answer) => write!(
+                f,
+                "Unrecognized answer '{}', expected y or n; nothing was sent",
+                answer
+            ),
+            NewOrderError::ConfirmationRequired => write!(
+                f,
+                "No confirmation on standard input; pass --yes to create the order without one"
+            ),
+            NewOrderError::NoMarketPrice {
+                fiat_code,
+                provider,
+            } => write!(
+                f,
+                "{} has no market price on {}, please specify an amount with -a flag to fix the rate",
+                fiat_code, provider
+            ),
+            NewOrderError::Rejected(problems) => write!(
+                f,
+                "Order does not meet the Mostro node terms: {}",
+                problems.join("; ")
+            ),
+        }
+    }
+}
+
+/// Mostro received a request and refused it with `cant-do`, as opposed to a
+/// reply that never came or could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CantDoError {
+    pub reason: Option<CantDoReason>,
+    /// What the refusal means, as shown to the user.
+    pub message: String,
+}
+
+impl std::error::Error for CantDoError {}
+
+impl fmt::Display for CantDoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Why `waitfor` gave up before the awaited message arrived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitForError {
+    /// Nothing came within the timeout, in seconds.
+    Timeout(u64),
+    /// Mostro refused the trade's last request.
+    CantDo(Option<CantDoReason>),
+}
+
+impl WaitForError {
+    /// Process exit code, so scripts can tell a timeout from a refusal.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WaitForError::Timeout(_) => 2,
+            WaitForError::CantDo(_) => 3,
+        }
+    }
+}
+
+impl std::error::Error for WaitForError {}
+
+impl fmt::Display for WaitForError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitForError::Timeout(seconds) => {
+                write!(
+                    f,
+                    "Timed out after {}s without the awaited message",
+                    seconds
+                )
+            }
+            WaitForError::CantDo(Some(reason)) => {
+                write!(f, "Mostro answered cant-do: {:?}", reason)
+            }
+            WaitForError::CantDo(None) => write!(f, "Mostro answered cant-do"),
+        }
+    }
+}
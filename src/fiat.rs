@@ -0,0 +1,287 @@
+//! Decimal fiat amounts.
+//!
+//! Order events may carry amounts such as `12.50` in their `fa` tag, while
+//! the Mostro protocol's `SmallOrder` stores whole fiat units. [`FiatAmount`]
+//! keeps the exact value for parsing, display, price estimates and the
+//! local `orders` table, and [`currency_decimals`] says how many decimal
+//! places a currency allows.
+
+use anyhow::{anyhow, Result};
+use mostro_core::prelude::SmallOrder;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Most decimal places accepted in an amount, whatever the currency.
+pub const MAX_FIAT_DECIMALS: u32 = 8;
+
+/// ISO 4217 minor units of `fiat_code`: 0 for e.g. JPY, 3 for e.g. KWD,
+/// 2 for everything else.
+pub fn currency_decimals(fiat_code: &str) -> u32 {
+    match fiat_code.to_uppercase().as_str() {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+/// Non-negative decimal fiat amount: `units / 10^decimals`. Amounts compare
+/// by value, so `12.5` equals `12.50`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FiatAmount {
+    units: i64,
+    decimals: u32,
+}
+
+impl FiatAmount {
+    pub fn from_whole(amount: i64) -> Self {
+        Self {
+            units: amount,
+            decimals: 0,
+        }
+    }
+
+    /// Parse `12`, `12.5` or `12.50`, keeping the written precision.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        if !digits(whole) || !(fraction.is_empty() || digits(fraction)) || text.ends_with('.') {
+            return Err(anyhow!("Invalid fiat amount '{}'", text));
+        }
+        let decimals = fraction.len() as u32;
+        if decimals > MAX_FIAT_DECIMALS {
+            return Err(anyhow!(
+                "Invalid fiat amount '{}': more than {} decimal places",
+                text,
+                MAX_FIAT_DECIMALS
+            ));
+        }
+        let units = format!("{}{}", whole, fraction)
+            .parse::<i64>()
+            .map_err(|_| anyhow!("Fiat amount '{}' is too large", text))?;
+        Ok(Self { units, decimals })
+    }
+
+    /// Decimal places as written, trailing zeros included.
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+
+    /// Decimal places actually needed: `12.50` needs 1, `12.00` needs 0.
+    pub fn significant_decimals(&self) -> u32 {
+        let mut units = self.units;
+        let mut decimals = self.decimals;
+        while decimals > 0 && units % 10 == 0 {
+            units /= 10;
+            decimals -= 1;
+        }
+        decimals
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.units as f64 / 10f64.powi(self.decimals as i32)
+    }
+
+    /// The amount in whole units, if it has no fractional part.
+    pub fn whole(&self) -> Option<i64> {
+        let scale = 10i64.pow(self.decimals);
+        (self.units % scale == 0).then(|| self.units / scale)
+    }
+
+    /// Nearest whole amount, halves rounded up.
+    pub fn round(&self) -> i64 {
+        let scale = 10i64.pow(self.decimals);
+        (self.units + scale / 2) / scale
+    }
+
+    /// Largest whole amount not above this one.
+    pub fn floor(&self) -> i64 {
+        self.units / 10i64.pow(self.decimals)
+    }
+
+    /// Smallest whole amount not below this one.
+    pub fn ceil(&self) -> i64 {
+        let scale = 10i64.pow(self.decimals);
+        (self.units + scale - 1) / scale
+    }
+
+    /// Sum of both amounts, at the larger precision.
+    pub fn saturating_add(&self, other: FiatAmount) -> Self {
+        self.combine(other, |a, b| a + b)
+    }
+
+    /// What is left of this amount after taking `other`, never below zero.
+    pub fn saturating_sub(&self, other: FiatAmount) -> Self {
+        self.combine(other, |a, b| (a - b).max(0))
+    }
+
+    fn combine(&self, other: FiatAmount, op: impl Fn(i128, i128) -> i128) -> Self {
+        let decimals = self.decimals.max(other.decimals);
+        let scaled = |a: &FiatAmount| a.units as i128 * 10i128.pow(decimals - a.decimals);
+        Self {
+            units: op(scaled(self), scaled(&other)).clamp(0, i64::MAX as i128) as i64,
+            decimals,
+        }
+    }
+
+    /// Check the amount against the minor units of `fiat_code`.
+    pub fn check_currency(&self, fiat_code: &str) -> Result<()> {
+        let allowed = currency_decimals(fiat_code);
+        if self.significant_decimals() > allowed {
+            return Err(anyhow!(
+                "{} {} has more decimal places than {} allows ({})",
+                self,
+                fiat_code,
+                fiat_code,
+                allowed
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for FiatAmount {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for FiatAmount {}
+
+impl PartialOrd for FiatAmount {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FiatAmount {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let decimals = self.decimals.max(other.decimals);
+        let scaled = |a: &FiatAmount| a.units as i128 * 10i128.pow(decimals - a.decimals);
+        scaled(self).cmp(&scaled(other))
+    }
+}
+
+impl FromStr for FiatAmount {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for FiatAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.decimals == 0 {
+            return write!(f, "{}", self.units);
+        }
+        let scale = 10i64.pow(self.decimals);
+        write!(
+            f,
+            "{}.{:0width$}",
+            self.units / scale,
+            self.units % scale,
+            width = self.decimals as usize
+        )
+    }
+}
+
+/// A fiat amount or a `min-max` range, as written in `--fiat-amount` or an
+/// order's `fa` tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FiatRange {
+    /// The amount, or the lower bound of a range.
+    pub min: FiatAmount,
+    /// Upper bound of a range order.
+    pub max: Option<FiatAmount>,
+}
+
+impl FiatRange {
+    /// Parse `12.50` or `10-20.5`; a range must be ascending.
+    pub fn parse(text: &str) -> Result<Self> {
+        if text.matches('-').count() > 1 {
+            return Err(anyhow!("Wrong amount syntax"));
+        }
+        match text.split_once('-') {
+            Some((min, max)) => {
+                let min =
+                    FiatAmount::parse(min).map_err(|e| anyhow!("Invalid min value: {}", e))?;
+                let max =
+                    FiatAmount::parse(max).map_err(|e| anyhow!("Invalid max value: {}", e))?;
+                if min >= max {
+                    return Err(anyhow!("Range of values must be 100-200 for example..."));
+                }
+                Ok(Self {
+                    min,
+                    max: Some(max),
+                })
+            }
+            None => Ok(Self {
+                min: FiatAmount::parse(text)?,
+                max: None,
+            }),
+        }
+    }
+
+    /// Whether the order can be taken for exactly `amount`.
+    pub fn contains(&self, amount: FiatAmount) -> bool {
+        match self.max {
+            Some(max) => self.min <= amount && amount <= max,
+            None => self.min == amount,
+        }
+    }
+
+    /// Check both bounds against the minor units of `fiat_code`.
+    pub fn check_currency(&self, fiat_code: &str) -> Result<()> {
+        self.min.check_currency(fiat_code)?;
+        if let Some(max) = &self.max {
+            max.check_currency(fiat_code)?;
+        }
+        Ok(())
+    }
+
+    /// The whole-unit `(amount or min, max)` the Mostro protocol carries, or
+    /// an error naming the nearest whole amounts when a bound has cents.
+    pub fn to_whole_units(&self, fiat_code: &str) -> Result<(i64, Option<i64>)> {
+        let whole = |amount: &FiatAmount| {
+            amount.whole().ok_or_else(|| {
+                anyhow!(
+                    "Mostro orders carry whole fiat units, so {} {} can't be sent; use {} or {}",
+                    amount,
+                    fiat_code,
+                    amount.floor(),
+                    amount.ceil()
+                )
+            })
+        };
+        Ok((whole(&self.min)?, self.max.as_ref().map(whole).transpose()?))
+    }
+}
+
+impl From<&SmallOrder> for FiatRange {
+    /// The whole-unit amount or range a protocol order carries.
+    fn from(order: &SmallOrder) -> Self {
+        match (order.min_amount, order.max_amount) {
+            (Some(min), Some(max)) => Self {
+                min: FiatAmount::from_whole(min),
+                max: Some(FiatAmount::from_whole(max)),
+            },
+            _ => Self {
+                min: FiatAmount::from_whole(order.fiat_amount),
+                max: None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for FiatRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.max {
+            Some(max) => write!(f, "{}-{}", self.min, max),
+            None => write!(f, "{}", self.min),
+        }
+    }
+}
@@ -1,7 +1,13 @@
+pub mod alerts;
+pub mod autotake;
 pub mod cli;
 pub mod db;
 pub mod error;
+pub mod fiat;
 pub mod lightning;
+pub mod maker;
 pub mod nip33;
 pub mod parser;
+pub mod price;
+pub mod trade;
 pub mod util;
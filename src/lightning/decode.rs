@@ -0,0 +1,112 @@
+use crate::error::MostroError;
+use lightning_invoice::{Bolt11Invoice as Invoice, Bolt11InvoiceDescriptionRef, Currency};
+use std::str::FromStr;
+
+/// A hop of a private route hint carried in the invoice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedRouteHop {
+    pub node_id: String,
+    /// `block x tx x output` notation.
+    pub short_channel_id: String,
+    pub fee_base_msat: u32,
+    pub fee_ppm: u32,
+    pub cltv_expiry_delta: u16,
+}
+
+/// Human-readable breakdown of a bolt11 invoice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedInvoice {
+    pub network: Currency,
+    pub amount_msat: Option<u64>,
+    pub payee: String,
+    pub payment_hash: String,
+    /// Plain description, or `None` when the invoice commits to a hash.
+    pub description: Option<String>,
+    pub description_hash: Option<String>,
+    pub created_at: u64,
+    pub expiry_secs: u64,
+    /// Seconds left before expiry; `0` once expired.
+    pub remaining_secs: u64,
+    pub min_final_cltv_expiry_delta: u64,
+    pub route_hints: Vec<Vec<DecodedRouteHop>>,
+}
+
+impl DecodedInvoice {
+    pub fn amount_sats(&self) -> Option<u64> {
+        self.amount_msat.map(|msat| msat / 1000)
+    }
+
+    pub fn expires_at(&self) -> u64 {
+        self.created_at.saturating_add(self.expiry_secs)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining_secs == 0
+    }
+}
+
+/// Name the node would publish for the network an invoice `currency` is for.
+pub fn network_name(currency: &Currency) -> &'static str {
+    match currency {
+        Currency::Bitcoin => "mainnet",
+        Currency::BitcoinTestnet => "testnet",
+        Currency::Regtest => "regtest",
+        Currency::Simnet => "simnet",
+        Currency::Signet => "signet",
+    }
+}
+
+fn format_short_channel_id(scid: u64) -> String {
+    format!(
+        "{}x{}x{}",
+        scid >> 40,
+        (scid >> 16) & 0xff_ffff,
+        scid & 0xffff
+    )
+}
+
+impl From<&Invoice> for DecodedInvoice {
+    fn from(invoice: &Invoice) -> Self {
+        let (description, description_hash) = match invoice.description() {
+            Bolt11InvoiceDescriptionRef::Direct(d) => (Some(d.to_string()), None),
+            Bolt11InvoiceDescriptionRef::Hash(h) => (None, Some(h.0.to_string())),
+        };
+        let route_hints = invoice
+            .route_hints()
+            .into_iter()
+            .map(|hint| {
+                hint.0
+                    .iter()
+                    .map(|hop| DecodedRouteHop {
+                        node_id: hop.src_node_id.to_string(),
+                        short_channel_id: format_short_channel_id(hop.short_channel_id),
+                        fee_base_msat: hop.fees.base_msat,
+                        fee_ppm: hop.fees.proportional_millionths,
+                        cltv_expiry_delta: hop.cltv_expiry_delta,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        DecodedInvoice {
+            network: invoice.currency(),
+            amount_msat: invoice.amount_milli_satoshis(),
+            payee: invoice.get_payee_pub_key().to_string(),
+            payment_hash: invoice.payment_hash().to_string(),
+            description,
+            description_hash,
+            created_at: invoice.duration_since_epoch().as_secs(),
+            expiry_secs: invoice.expiry_time().as_secs(),
+            remaining_secs: invoice.duration_until_expiry().as_secs(),
+            min_final_cltv_expiry_delta: invoice.min_final_cltv_expiry_delta(),
+            route_hints,
+        }
+    }
+}
+
+/// Parse a bolt11 and break it down for display. Unlike
+/// [`super::is_valid_invoice`] expired invoices are decoded too.
+pub fn decode_invoice(payment_request: &str) -> Result<DecodedInvoice, MostroError> {
+    let invoice = Invoice::from_str(payment_request.trim())?;
+    Ok(DecodedInvoice::from(&invoice))
+}
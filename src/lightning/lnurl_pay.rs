@@ -0,0 +1,272 @@
+//! LNURL-pay (LUD-06) and Lightning address (LUD-16) resolution.
+//!
+//! Mostro pays buyers through whatever they hand it, so a typo in a
+//! Lightning address only surfaces once the payout fails. Resolving the
+//! destination here first confirms the endpoint exists and will accept the
+//! trade amount.
+
+use anyhow::{anyhow, Result};
+use bitcoin::hashes::Hash;
+use lightning_invoice::{Bolt11Invoice, Bolt11InvoiceDescriptionRef};
+use lnurl::api::{decode_ln_url_response_from_json, LnUrlResponse};
+use lnurl::lightning_address::LightningAddress;
+use lnurl::lnurl::LnUrl;
+use lnurl::pay::{LnURLPayInvoice, PayResponse};
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::{validate_invoice, InvoiceRequirements};
+
+/// Upper bound for each request made to an LNURL service.
+pub const LNURL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How a Lightning address or LNURL payout destination is handed to Mostro.
+#[derive(Debug, Default, Clone)]
+pub struct LnurlOptions {
+    /// Send a bolt11 fetched from the LNURL service instead of the address.
+    pub fetch_invoice: bool,
+    /// Comment passed to the LNURL service along with the bolt11 request.
+    pub comment: Option<String>,
+}
+
+/// A payout destination resolved through LNURL-pay instead of a bolt11.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LnurlDestination {
+    /// `user@domain` Lightning address.
+    Address(LightningAddress),
+    /// bech32 `lnurl1...` code.
+    Lnurl(LnUrl),
+}
+
+impl LnurlDestination {
+    /// Parse a Lightning address or LNURL, with or without a `lightning:`
+    /// URI prefix. Returns `None` for anything else (e.g. a bolt11).
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let input = match input.get(..10) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("lightning:") => &input[10..],
+            _ => input,
+        };
+        if let Ok(address) = LightningAddress::from_str(input) {
+            return Some(Self::Address(address));
+        }
+        LnUrl::from_str(input).ok().map(Self::Lnurl)
+    }
+
+    /// URL of the LNURL-pay endpoint.
+    pub fn url(&self) -> String {
+        match self {
+            Self::Address(address) => address.lnurlp_url(),
+            Self::Lnurl(lnurl) => lnurl.url.clone(),
+        }
+    }
+
+    /// Lightning address Mostro can resolve on its own, if there is one.
+    /// Plain LNURL codes have to be turned into a bolt11 before sending.
+    pub fn lightning_address(&self) -> Option<String> {
+        match self {
+            Self::Address(address) => Some(address.to_string()),
+            Self::Lnurl(lnurl) => lnurl.lightning_address().map(|a| a.to_string()),
+        }
+    }
+}
+
+/// LNURL services report failures as `{"status": "ERROR", "reason": ...}`.
+fn service_error(json: &serde_json::Value) -> Option<String> {
+    let status = json.get("status")?.as_str()?;
+    if !status.eq_ignore_ascii_case("error") {
+        return None;
+    }
+    Some(
+        json.get("reason")
+            .and_then(|r| r.as_str())
+            .unwrap_or("unknown error")
+            .to_string(),
+    )
+}
+
+async fn get_json(client: &reqwest::Client, url: &str) -> Result<serde_json::Value> {
+    let response = client.get(url).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("{} returned HTTP {}", url, status));
+    }
+    let json: serde_json::Value = response.json().await?;
+    if let Some(reason) = service_error(&json) {
+        return Err(anyhow!("{} returned an error: {}", url, reason));
+    }
+    Ok(json)
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder().timeout(LNURL_TIMEOUT).build()?)
+}
+
+/// Parse the body of an LNURL endpoint, which must be a pay request.
+pub fn parse_pay_response(json: serde_json::Value) -> Result<PayResponse> {
+    if let Some(reason) = service_error(&json) {
+        return Err(anyhow!("LNURL service returned an error: {}", reason));
+    }
+    match decode_ln_url_response_from_json(json) {
+        Ok(LnUrlResponse::LnUrlPayResponse(pay)) => Ok(pay),
+        Ok(_) => Err(anyhow!("LNURL endpoint is not a pay request")),
+        Err(e) => Err(anyhow!("Invalid LNURL-pay response: {}", e)),
+    }
+}
+
+/// Fetch the LNURL-pay parameters of `destination`.
+pub async fn fetch_pay_response(destination: &LnurlDestination) -> Result<PayResponse> {
+    let json = get_json(&http_client()?, &destination.url()).await?;
+    parse_pay_response(json)
+}
+
+/// Check the LUD-06 `metadata` field: a JSON array of `[mime, content]`
+/// pairs holding exactly one `text/plain` entry.
+fn check_metadata(metadata: &str) -> Result<()> {
+    let entries: Vec<Vec<serde_json::Value>> = serde_json::from_str(metadata)
+        .map_err(|_| anyhow!("LNURL metadata is not a list of [type, content] pairs"))?;
+    let text_entries = entries
+        .iter()
+        .filter(|entry| entry.first().and_then(|t| t.as_str()) == Some("text/plain"))
+        .count();
+    if text_entries != 1 {
+        return Err(anyhow!(
+            "LNURL metadata must contain exactly one text/plain entry"
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a pay endpoint accepts `amount_sats` (when known) and
+/// `comment`, and that its metadata is well formed.
+pub fn check_pay_response(
+    pay: &PayResponse,
+    amount_sats: Option<u64>,
+    comment: Option<&str>,
+) -> Result<()> {
+    if pay.min_sendable == 0 || pay.min_sendable > pay.max_sendable {
+        return Err(anyhow!(
+            "LNURL endpoint advertises an invalid range ({}-{} msats)",
+            pay.min_sendable,
+            pay.max_sendable
+        ));
+    }
+    if let Some(sats) = amount_sats {
+        let msats = sats.saturating_mul(1000);
+        if msats < pay.min_sendable || msats > pay.max_sendable {
+            return Err(anyhow!(
+                "{} sats is outside the {}-{} sats the recipient accepts",
+                sats,
+                pay.min_sendable.div_ceil(1000),
+                pay.max_sendable / 1000
+            ));
+        }
+    }
+    if let Some(comment) = comment.filter(|c| !c.is_empty()) {
+        match pay.comment_allowed {
+            None | Some(0) => return Err(anyhow!("The recipient does not accept comments")),
+            Some(max) if comment.chars().count() > max as usize => {
+                return Err(anyhow!(
+                    "Comment is longer than the {} characters the recipient accepts",
+                    max
+                ))
+            }
+            _ => {}
+        }
+    }
+    check_metadata(&pay.metadata)
+}
+
+/// Request a bolt11 for `amount_sats` from the pay endpoint and check it
+/// is for that amount and commits to the endpoint's metadata.
+pub async fn fetch_invoice(
+    pay: &PayResponse,
+    amount_sats: u64,
+    comment: Option<&str>,
+) -> Result<String> {
+    let msats = amount_sats.saturating_mul(1000);
+    let mut url = reqwest::Url::parse(&pay.callback)
+        .map_err(|e| anyhow!("Invalid LNURL callback {}: {}", pay.callback, e))?;
+    url.query_pairs_mut()
+        .append_pair("amount", &msats.to_string());
+    if let Some(comment) = comment.filter(|c| !c.is_empty()) {
+        url.query_pairs_mut().append_pair("comment", comment);
+    }
+
+    let json = get_json(&http_client()?, url.as_str()).await?;
+    let response: LnURLPayInvoice = serde_json::from_value(json)
+        .map_err(|e| anyhow!("Invalid LNURL-pay invoice response: {}", e))?;
+    let invoice = Bolt11Invoice::from_str(response.invoice())
+        .map_err(|e| anyhow!("LNURL service returned an invalid invoice: {}", e))?;
+
+    if invoice.amount_milli_satoshis() != Some(msats) {
+        return Err(anyhow!(
+            "LNURL service returned an invoice for a different amount"
+        ));
+    }
+    // LUD-06 has the invoice commit to the metadata through its description
+    // hash; a mismatch means the callback is not answering for this endpoint.
+    if let Bolt11InvoiceDescriptionRef::Hash(hash) = invoice.description() {
+        if hash.0.to_byte_array() != pay.metadata_hash() {
+            return Err(anyhow!(
+                "LNURL invoice description hash does not match the metadata"
+            ));
+        }
+    }
+
+    Ok(response.pr)
+}
+
+/// Turn the `--invoice` argument of a payout into the payment request sent
+/// to Mostro.
+///
+/// Bolt11 invoices are checked with [`validate_invoice`]. Lightning addresses
+/// and LNURLs are resolved first so an unknown endpoint, or one that cannot
+/// receive the payout amount, fails here. The address itself is sent unless
+/// `options.fetch_invoice` is set or the destination is a bare LNURL Mostro cannot
+/// resolve; in that case a bolt11 for `requirements.amount_sats` is fetched
+/// and validated like any other invoice.
+pub async fn resolve_payment_request(
+    input: &str,
+    requirements: &InvoiceRequirements,
+    options: &LnurlOptions,
+) -> Result<String> {
+    let comment = options.comment.as_deref();
+    let Some(destination) = LnurlDestination::parse(input) else {
+        return validate_invoice(input, requirements)
+            .map(|i| i.to_string())
+            .map_err(|e| anyhow!("Invalid invoice: {}", e));
+    };
+
+    let pay = fetch_pay_response(&destination)
+        .await
+        .map_err(|e| anyhow!("Could not resolve {}: {}", input, e))?;
+    check_pay_response(&pay, requirements.amount_sats, comment)
+        .map_err(|e| anyhow!("{} cannot receive this payout: {}", input, e))?;
+    if let Some(min) = requirements.min_amount_sats {
+        if pay.max_sendable / 1000 < min {
+            return Err(anyhow!(
+                "{} accepts at most {} sats, below the node minimum of {} sats",
+                input,
+                pay.max_sendable / 1000,
+                min
+            ));
+        }
+    }
+
+    match destination.lightning_address() {
+        Some(address) if !options.fetch_invoice => Ok(address),
+        _ => {
+            let amount = requirements.amount_sats.ok_or_else(|| {
+                anyhow!(
+                    "The payout amount is not known yet, so no invoice can be fetched from {}",
+                    input
+                )
+            })?;
+            let invoice = fetch_invoice(&pay, amount, comment).await?;
+            validate_invoice(&invoice, requirements)
+                .map(|i| i.to_string())
+                .map_err(|e| anyhow!("Invalid invoice from {}: {}", input, e))
+        }
+    }
+}
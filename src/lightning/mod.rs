@@ -1,7 +1,14 @@
 use crate::error::MostroError;
 use lightning_invoice::Bolt11Invoice as Invoice;
+use lightning_invoice::Currency;
 use std::str::FromStr;
 
+pub mod decode;
+pub mod lnurl_pay;
+
+pub use decode::{decode_invoice, network_name, DecodedInvoice};
+pub use lnurl_pay::{resolve_payment_request, LnurlOptions};
+
 /// Verify if an invoice is valid
 pub fn is_valid_invoice(payment_request: &str) -> Result<Invoice, MostroError> {
     let invoice = Invoice::from_str(payment_request)?;
@@ -11,3 +18,93 @@ pub fn is_valid_invoice(payment_request: &str) -> Result<Invoice, MostroError> {
 
     Ok(invoice)
 }
+
+/// Order and node terms an invoice handed to Mostro has to satisfy.
+///
+/// Every field is optional: `None` (or an empty `networks` list) disables the
+/// matching check, so callers only enforce what they actually know about the
+/// order and the node.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InvoiceRequirements {
+    /// Exact sats the invoice must request. Amountless invoices always pass.
+    pub amount_sats: Option<u64>,
+    /// Smallest payment the node is willing to make (`min_order_amount`).
+    pub min_amount_sats: Option<u64>,
+    /// Minimum number of seconds the invoice must remain payable
+    /// (`invoice_expiration_window`).
+    pub min_expiry_secs: Option<u64>,
+    /// Networks the node's Lightning backend runs on (`lnd_networks`).
+    pub networks: Vec<Currency>,
+}
+
+impl InvoiceRequirements {
+    pub fn with_amount(mut self, amount_sats: Option<u64>) -> Self {
+        self.amount_sats = amount_sats;
+        self
+    }
+}
+
+/// Map a network name as published by the node (`mainnet`, `testnet`,
+/// `signet`, `regtest`, ...) to the bolt11 currency it produces.
+pub fn parse_network(name: &str) -> Option<Currency> {
+    match name.trim().to_lowercase().as_str() {
+        "mainnet" | "bitcoin" => Some(Currency::Bitcoin),
+        "testnet" | "testnet3" | "testnet4" => Some(Currency::BitcoinTestnet),
+        "signet" => Some(Currency::Signet),
+        "regtest" => Some(Currency::Regtest),
+        "simnet" => Some(Currency::Simnet),
+        _ => None,
+    }
+}
+
+/// Mostro fee charged to each side of a trade of `amount` sats.
+///
+/// The node's `fee` is split evenly between buyer and seller, so the buyer
+/// receives `amount - mostro_fee_share(amount, fee)`.
+pub fn mostro_fee_share(amount: i64, fee: f64) -> i64 {
+    ((fee * amount as f64) / 2.0).round() as i64
+}
+
+/// Sats a buyer invoice must request for an order of `amount` sats once the
+/// buyer's half of the Mostro fee is deducted.
+pub fn expected_buyer_amount(amount: i64, fee: f64) -> u64 {
+    amount.saturating_sub(mostro_fee_share(amount, fee)).max(0) as u64
+}
+
+/// Verify an invoice against the order and node terms in `requirements`.
+///
+/// On top of [`is_valid_invoice`] this rejects invoices for the wrong
+/// network, with an amount other than the expected one or below the node
+/// minimum, and invoices that expire before the node's minimum window.
+pub fn validate_invoice(
+    payment_request: &str,
+    requirements: &InvoiceRequirements,
+) -> Result<Invoice, MostroError> {
+    let invoice = is_valid_invoice(payment_request)?;
+
+    if !requirements.networks.is_empty() && !requirements.networks.contains(&invoice.currency()) {
+        return Err(MostroError::WrongNetworkError);
+    }
+
+    if let Some(amount_msat) = invoice.amount_milli_satoshis() {
+        let amount_sats = amount_msat / 1000;
+        if let Some(min) = requirements.min_amount_sats {
+            if amount_sats < min {
+                return Err(MostroError::MinAmountError);
+            }
+        }
+        if let Some(expected) = requirements.amount_sats {
+            if amount_sats != expected {
+                return Err(MostroError::WrongAmountError);
+            }
+        }
+    }
+
+    if let Some(min_expiry) = requirements.min_expiry_secs {
+        if invoice.duration_until_expiry().as_secs() < min_expiry {
+            return Err(MostroError::MinExpirationTimeError);
+        }
+    }
+
+    Ok(invoice)
+}
@@ -1,12 +1,16 @@
 use anyhow::Result;
 use mostro_client::cli::run;
+use mostro_client::error::WaitForError;
 use std::process;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     if let Err(e) = run().await {
         eprintln!("{e}");
-        process::exit(1);
+        let code = e
+            .downcast_ref::<WaitForError>()
+            .map_or(1, WaitForError::exit_code);
+        process::exit(code);
     }
 
     process::exit(0);
@@ -0,0 +1,159 @@
+//! Standing orders for the `maker` daemon.
+//!
+//! A [`MakerOffer`] (stored in `maker_offers`) describes an order to keep
+//! published. Every order published for it is a [`MakerOrder`] row pointing
+//! to the order it replaced; the daemon replaces the live order when it is
+//! taken, canceled or expires, and when a fixed-price order's reference
+//! price moves too far.
+
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::db::{MakerOffer, MakerOrder};
+use crate::fiat::FiatRange;
+use crate::lightning::lnurl_pay::LnurlDestination;
+use crate::parser::order_feed::OrderChangeKind;
+use crate::price::market_sats;
+
+/// How often the reference prices are checked, and offers without a live
+/// order retried.
+pub const MAKER_PRICE_CHECK: Duration = Duration::from_secs(300);
+
+/// Why a live order that went through `change` has to be replaced; `None`
+/// while it is still in the book.
+pub fn replacement_reason(change: OrderChangeKind) -> Option<&'static str> {
+    match change {
+        OrderChangeKind::New | OrderChangeKind::Updated => None,
+        OrderChangeKind::Taken => Some("taken"),
+        OrderChangeKind::Canceled => Some("canceled"),
+        OrderChangeKind::Expired => Some("expired"),
+        OrderChangeKind::Removed => Some("removed"),
+    }
+}
+
+/// Terms of the next order published for an offer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfferOrder {
+    pub fiat_amount: FiatRange,
+    /// Fixed sats; 0 for a market-priced order.
+    pub amount: i64,
+    pub premium: i64,
+    /// BTC price a fixed amount was computed from.
+    pub reference_price: Option<f64>,
+}
+
+/// The order to publish for `offer` when one BTC is worth `price` in its
+/// currency. Fixed-price offers carry the premium in their sats amount and
+/// need a price.
+pub fn offer_order(offer: &MakerOffer, price: Option<f64>) -> Result<OfferOrder> {
+    let fiat_amount = FiatRange::parse(&offer.fiat_amount)?;
+    if !offer.fixed_price {
+        return Ok(OfferOrder {
+            fiat_amount,
+            amount: 0,
+            premium: offer.premium,
+            reference_price: None,
+        });
+    }
+    let price =
+        price.ok_or_else(|| anyhow!("No {} price to fix the sats amount", offer.fiat_code))?;
+    Ok(OfferOrder {
+        fiat_amount,
+        amount: market_sats(fiat_amount.min.to_f64(), offer.premium, price),
+        premium: 0,
+        reference_price: Some(price),
+    })
+}
+
+/// Whether `order`, a fixed-price order of `offer`, is stale now that one
+/// BTC is worth `price`.
+pub fn needs_reprice(offer: &MakerOffer, order: &MakerOrder, price: f64) -> bool {
+    let (Some(threshold), Some(reference)) = (offer.reprice_threshold, order.reference_price)
+    else {
+        return false;
+    };
+    offer.fixed_price
+        && reference > 0.0
+        && (price - reference).abs() / reference * 100.0 >= threshold
+}
+
+/// Build the offer `addoffer` saves, checking its parts make sense.
+#[allow(clippy::too_many_arguments)]
+pub fn new_maker_offer(
+    name: &str,
+    kind: &str,
+    fiat_code: &str,
+    fiat_amount: &FiatRange,
+    payment_method: &str,
+    premium: i64,
+    fixed_price: bool,
+    reprice_threshold: Option<f64>,
+    expiration_days: i64,
+    invoice: Option<&str>,
+) -> Result<MakerOffer> {
+    let kind = mostro_core::order::Kind::from_str(&kind.to_lowercase())
+        .map_err(|_| anyhow!("Invalid order kind '{}'", kind))?;
+    let fiat_code = fiat_code.to_uppercase();
+    fiat_amount.check_currency(&fiat_code)?;
+    fiat_amount.to_whole_units(&fiat_code)?;
+    if payment_method.trim().is_empty() {
+        return Err(anyhow!("An offer needs a payment method"));
+    }
+    if fixed_price && fiat_amount.max.is_some() {
+        return Err(anyhow!(
+            "Range orders are always market priced: drop --fixed-price or use a single amount"
+        ));
+    }
+    match reprice_threshold {
+        Some(_) if !fixed_price => {
+            return Err(anyhow!(
+                "--reprice-threshold only applies to --fixed-price offers; market-priced orders follow the price by themselves"
+            ));
+        }
+        Some(threshold) if threshold <= 0.0 => {
+            return Err(anyhow!("--reprice-threshold must be a positive percentage"));
+        }
+        _ => {}
+    }
+    if let Some(invoice) = invoice {
+        if kind != mostro_core::order::Kind::Buy {
+            return Err(anyhow!("Only buy offers take a payout --invoice"));
+        }
+        if LnurlDestination::parse(invoice).is_none() {
+            return Err(anyhow!(
+                "A standing offer needs a Lightning address or LNURL: a bolt11 can only be paid once"
+            ));
+        }
+    }
+    Ok(MakerOffer {
+        name: name.to_string(),
+        kind: kind.to_string(),
+        fiat_code,
+        fiat_amount: fiat_amount.to_string(),
+        payment_method: payment_method.trim().to_string(),
+        premium,
+        fixed_price,
+        reprice_threshold,
+        expiration_days,
+        invoice: invoice.map(str::to_string),
+        ..Default::default()
+    })
+}
+
+/// Short text of what `offer` publishes, e.g. `sell 50-500 USD @ +2% via zelle`.
+pub fn offer_description(offer: &MakerOffer) -> String {
+    let price = if offer.fixed_price {
+        let reprice = offer
+            .reprice_threshold
+            .map(|t| format!(", repriced at ±{}%", t))
+            .unwrap_or_default();
+        format!("fixed at {:+}%{}", offer.premium, reprice)
+    } else {
+        format!("@ {:+}%", offer.premium)
+    };
+    format!(
+        "{} {} {} {} via {}",
+        offer.kind, offer.fiat_amount, offer.fiat_code, price, offer.payment_method
+    )
+}
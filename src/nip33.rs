@@ -1,11 +1,106 @@
+use crate::fiat::{FiatAmount, FiatRange};
 use anyhow::{Ok, Result};
 use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use uuid::Uuid;
 
+/// Everything a kind-38383 order event says about an order: the
+/// [`SmallOrder`] fields plus the tags `SmallOrder` has no room for.
+#[derive(Debug, Clone, Default)]
+pub struct OrderView {
+    pub order: SmallOrder,
+    /// `rating`: the maker's reputation on this node.
+    pub maker_rating: Option<MakerRating>,
+    /// `fa`: the fiat amount or range exactly as published. The protocol has
+    /// no room for cents, so `order` carries each bound rounded to the
+    /// nearest whole unit (halves up), with a warning when that changes it.
+    pub fiat_amount: Option<FiatRange>,
+    /// `name`: the maker's chosen name.
+    pub name: Option<String>,
+    /// `expires_at`: when Mostro expires the pending order. Also copied to
+    /// `order.expires_at`.
+    pub expires_at: Option<i64>,
+    /// `expiration` (NIP-40): when relays may drop the event.
+    pub expiration: Option<i64>,
+    /// `network`: bitcoin network, e.g. `mainnet`.
+    pub network: Option<String>,
+    /// `layer`: settlement layer, e.g. `lightning`.
+    pub layer: Option<String>,
+    /// `source`: link to the order on a client.
+    pub source: Option<String>,
+    /// `g` / `geohash`: location of in-person trades.
+    pub geohash: Option<String>,
+    /// `y`: platform and instance name, e.g. `mostro`.
+    pub platform: Option<String>,
+    /// `z`: document type, `order` for order events.
+    pub document: Option<String>,
+    /// Every `bond*` tag, raw.
+    pub bond: BTreeMap<String, String>,
+    /// Tags this client does not know about yet, raw.
+    pub other: BTreeMap<String, Vec<String>>,
+}
+
+impl From<SmallOrder> for OrderView {
+    fn from(order: SmallOrder) -> Self {
+        OrderView {
+            expires_at: order.expires_at,
+            order,
+            ..Default::default()
+        }
+    }
+}
+
+impl OrderView {
+    /// The exact fiat amount or range of the order, falling back to the
+    /// whole units of `order` when the event had no readable `fa` tag.
+    pub fn fiat_range(&self) -> FiatRange {
+        self.fiat_amount
+            .unwrap_or_else(|| FiatRange::from(&self.order))
+    }
+
+    /// Fiat amount as published, e.g. `12.50` or `10-20.5`; falls back to
+    /// the whole units of `order` when the event had no readable `fa` tag.
+    pub fn fiat_amount_text(&self) -> String {
+        if let Some(fiat) = &self.fiat_amount {
+            return fiat.to_string();
+        }
+        let order = &self.order;
+        match (order.min_amount, order.max_amount) {
+            (None, None) => order.fiat_amount.to_string(),
+            (Some(min), Some(max)) => format!("{}-{}", min, max),
+            (Some(min), None) => format!("{}-?", min),
+            (None, Some(max)) => format!("?-{}", max),
+        }
+    }
+}
+
 pub fn order_from_tags(tags: Tags) -> Result<SmallOrder> {
-    let mut order = SmallOrder::default();
+    Ok(order_view_from_tags(tags)?.order)
+}
+
+/// Parse every tag of an order event. Unknown tags land in
+/// [`OrderView::other`].
+pub fn order_view_from_tags(tags: Tags) -> Result<OrderView> {
+    let mut view = OrderView {
+        maker_rating: maker_rating_from_tags(&tags),
+        ..Default::default()
+    };
+    let order = &mut view.order;
+    let order_id = tags.identifier().unwrap_or("?").to_string();
+    // Whole units for `order`, warning when cents are rounded away
+    let whole = |amount: FiatAmount| {
+        if amount.whole().is_none() {
+            log::warn!(
+                "Order {}: fiat amount {} has cents, rounded to {} in its SmallOrder",
+                order_id,
+                amount,
+                amount.round()
+            );
+        }
+        amount.round()
+    };
 
     for tag in tags {
         let t = tag.to_vec(); // Vec<String>
@@ -17,6 +112,7 @@ pub fn order_from_tags(tags: Tags) -> Result<SmallOrder> {
         let values = &t[1..];
 
         let v = values.first().map(|s| s.as_str()).unwrap_or_default();
+        let text = || (!v.trim().is_empty()).then(|| v.trim().to_string());
 
         match key {
             "d" => {
@@ -35,14 +131,18 @@ pub fn order_from_tags(tags: Tags) -> Result<SmallOrder> {
                 order.amount = v.parse::<i64>().unwrap_or(0);
             }
             "fa" => {
-                if v.contains('.') {
-                    continue;
-                }
+                let min = FiatAmount::parse(v).ok();
                 if let Some(max_str) = values.get(1) {
-                    order.min_amount = v.parse::<i64>().ok();
-                    order.max_amount = max_str.parse::<i64>().ok();
+                    let max = FiatAmount::parse(max_str).ok();
+                    order.min_amount = min.map(whole);
+                    order.max_amount = max.map(whole);
+                    view.fiat_amount = min.zip(max).map(|(min, max)| FiatRange {
+                        min,
+                        max: Some(max),
+                    });
                 } else {
-                    order.fiat_amount = v.parse::<i64>().unwrap_or(0);
+                    order.fiat_amount = min.map(whole).unwrap_or(0);
+                    view.fiat_amount = min.map(|min| FiatRange { min, max: None });
                 }
             }
             "pm" => {
@@ -51,11 +151,66 @@ pub fn order_from_tags(tags: Tags) -> Result<SmallOrder> {
             "premium" => {
                 order.premium = v.parse::<i64>().unwrap_or(0);
             }
-            _ => {}
+            // Parsed above, before the loop
+            "rating" => {}
+            "name" => view.name = text(),
+            "expires_at" => {
+                view.expires_at = v.parse::<i64>().ok();
+                order.expires_at = view.expires_at;
+            }
+            "expiration" => view.expiration = v.parse::<i64>().ok(),
+            "network" => view.network = text(),
+            "layer" => view.layer = text(),
+            "source" => view.source = text(),
+            "g" | "geohash" => view.geohash = text(),
+            "y" => {
+                view.platform = Some(values.join(" ")).filter(|p| !p.trim().is_empty());
+            }
+            "z" => view.document = text(),
+            key if key.starts_with("bond") => {
+                view.bond.insert(key.to_string(), values.join(","));
+            }
+            key => {
+                view.other.insert(key.to_string(), values.to_vec());
+            }
         }
     }
 
-    Ok(order)
+    Ok(view)
+}
+
+/// Maker reputation carried by the `rating` tag of an order event.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MakerRating {
+    pub total_reviews: u64,
+    /// Average rating, 0 to 5.
+    pub total_rating: f64,
+    /// Days since the maker's first trade on this node.
+    pub days: u64,
+}
+
+/// Read the `rating` tag of an order event. mostrod publishes it as a JSON
+/// object, or wrapped as `["rating", {...}]` by older daemons; `None` when
+/// the tag is missing or unreadable.
+pub fn maker_rating_from_tags(tags: &Tags) -> Option<MakerRating> {
+    let value = tags.iter().find_map(|tag| {
+        let t = tag.as_slice();
+        (t.first().map(String::as_str) == Some("rating"))
+            .then(|| t.get(1).cloned())
+            .flatten()
+    })?;
+    let json: serde_json::Value = serde_json::from_str(&value).ok()?;
+    let object = match &json {
+        serde_json::Value::Array(items) => items.iter().find(|item| item.is_object())?,
+        object @ serde_json::Value::Object(_) => object,
+        _ => return None,
+    };
+    let number = |key: &str| object.get(key).and_then(|v| v.as_f64());
+    Some(MakerRating {
+        total_reviews: number("total_reviews").unwrap_or(0.0) as u64,
+        total_rating: number("total_rating").unwrap_or(0.0),
+        days: number("days").unwrap_or(0.0) as u64,
+    })
 }
 
 pub fn dispute_from_tags(tags: Tags) -> Result<Dispute> {
@@ -0,0 +1,40 @@
+use comfy_table::*;
+
+use crate::alerts::rule_description;
+use crate::db::AlertRule;
+use crate::parser::common::{
+    create_bold_header, create_empty_table, create_standard_table, format_timestamp,
+};
+
+/// Table of the saved alert rules.
+pub fn print_alerts_table(rules: &[AlertRule]) -> String {
+    if rules.is_empty() {
+        return create_empty_table("📭 No Alerts", "No alert rules yet, add one with addalert…")
+            .to_string();
+    }
+
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        "🆔 Id",
+        "🏷️ Name",
+        "🔎 Looking For",
+        "🔔 Notify",
+        "📅 Created",
+    ]));
+
+    for rule in rules {
+        let notify = match &rule.target {
+            Some(target) => format!("{} {}", rule.notifier, target),
+            None => rule.notifier.clone(),
+        };
+        table.add_row(Row::from(vec![
+            Cell::new(rule.id).set_alignment(CellAlignment::Center),
+            Cell::new(&rule.name).set_alignment(CellAlignment::Center),
+            Cell::new(rule_description(rule)).set_alignment(CellAlignment::Center),
+            Cell::new(notify).set_alignment(CellAlignment::Center),
+            Cell::new(format_timestamp(rule.created_at)).set_alignment(CellAlignment::Center),
+        ]));
+    }
+
+    table.to_string()
+}
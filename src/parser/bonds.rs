@@ -0,0 +1,106 @@
+use comfy_table::*;
+
+use crate::db::{Bond, BondRole, BondStatus};
+use crate::parser::common::{
+    create_bold_header, create_empty_table, create_standard_table, format_duration_secs,
+};
+
+/// Color a bond status: green when the money is back or paid out, red when
+/// it is lost, yellow while something is still expected of the user.
+fn bond_status_cell(bond: &Bond) -> Cell {
+    let cell = Cell::new(&bond.status).set_alignment(CellAlignment::Center);
+    match bond.status() {
+        Some(BondStatus::Released | BondStatus::Paid) => cell.fg(Color::Green),
+        Some(BondStatus::Slashed) => cell.fg(Color::Red),
+        Some(BondStatus::Claimable | BondStatus::Pending) => cell.fg(Color::Yellow),
+        Some(BondStatus::Locked | BondStatus::Claimed) => cell.fg(Color::Cyan),
+        None => cell,
+    }
+}
+
+/// Forfeit deadline of a bond share with the time left at `now`, or `-`
+/// when it does not apply (posted bonds, claims already settled).
+pub fn bond_deadline_text(bond: &Bond, now: i64) -> String {
+    if bond.role() != Some(BondRole::Owed) || bond.status() == Some(BondStatus::Paid) {
+        return "-".to_string();
+    }
+    let Some(deadline) = bond.claim_deadline() else {
+        return if bond.slashed_at.is_some() {
+            "unknown (node publishes no claim window)".to_string()
+        } else {
+            "-".to_string()
+        };
+    };
+    let date = chrono::DateTime::from_timestamp(deadline, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "Invalid date".to_string());
+    if deadline <= now {
+        format!("{} (expired)", date)
+    } else {
+        format!(
+            "{} ({} left)",
+            date,
+            format_duration_secs((deadline - now) as u64)
+        )
+    }
+}
+
+fn shorten_invoice(invoice: &str) -> String {
+    if invoice.chars().count() <= 24 {
+        return invoice.to_string();
+    }
+    let head: String = invoice.chars().take(14).collect();
+    let tail: String = invoice.chars().skip(invoice.chars().count() - 8).collect();
+    format!("{}…{}", head, tail)
+}
+
+/// Table of the bonds stored locally, with forfeit deadlines relative to `now`.
+pub fn print_bonds_table(bonds: &[Bond], now: i64) -> String {
+    if bonds.is_empty() {
+        return create_empty_table("📭 No Bonds", "No posted or owed bonds recorded yet…")
+            .to_string();
+    }
+
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        "🆔 Order Id",
+        "🧭 Role",
+        "📊 Status",
+        "💰 Amount",
+        "⏳ Forfeit Deadline",
+        "🧾 Payout Invoice",
+    ]));
+
+    for bond in bonds {
+        let amount = if bond.amount > 0 {
+            format!("{} sats", bond.amount)
+        } else {
+            "-".to_string()
+        };
+        let deadline = bond_deadline_text(bond, now);
+        let mut deadline_cell = Cell::new(&deadline).set_alignment(CellAlignment::Center);
+        if bond.status() == Some(BondStatus::Claimable) {
+            deadline_cell = if deadline.ends_with("(expired)") {
+                deadline_cell.fg(Color::Red)
+            } else {
+                deadline_cell.fg(Color::Yellow)
+            };
+        }
+        let invoice = bond
+            .payout_invoice
+            .as_deref()
+            .map(shorten_invoice)
+            .unwrap_or_else(|| "-".to_string());
+
+        table.add_row(Row::from(vec![
+            Cell::new(&bond.order_id).set_alignment(CellAlignment::Center),
+            Cell::new(&bond.role).set_alignment(CellAlignment::Center),
+            bond_status_cell(bond),
+            Cell::new(amount).set_alignment(CellAlignment::Center),
+            deadline_cell,
+            Cell::new(invoice).set_alignment(CellAlignment::Center),
+        ]));
+    }
+
+    table.to_string()
+}
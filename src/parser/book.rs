@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use comfy_table::*;
+use mostro_core::prelude::*;
+
+use crate::fiat::FiatAmount;
+use crate::nip33::OrderView;
+use crate::parser::common::{create_bold_header, create_empty_table, create_standard_table};
+use crate::parser::orders::OrderFilter;
+use crate::price::{BtcRates, OrderEstimate};
+
+/// One order on a side of the book.
+#[derive(Debug, Clone)]
+pub struct BookEntry {
+    pub view: OrderView,
+    pub estimate: OrderEstimate,
+    /// Percent above (+) or below (-) the market the order trades at, when
+    /// it can be told: the order's estimate, or the premium of a market-price
+    /// order while no market price is known.
+    pub vs_market: Option<f64>,
+    /// Fiat available from the best order down to this one.
+    pub depth: FiatAmount,
+}
+
+/// Pending orders of one currency. Asks are sell orders, cheapest first;
+/// bids are buy orders, highest paying first.
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyBook {
+    pub fiat_code: String,
+    /// Market price of one BTC, if known.
+    pub market_price: Option<f64>,
+    pub asks: Vec<BookEntry>,
+    pub bids: Vec<BookEntry>,
+}
+
+impl CurrencyBook {
+    pub fn best_ask(&self) -> Option<&BookEntry> {
+        self.asks.first()
+    }
+
+    pub fn best_bid(&self) -> Option<&BookEntry> {
+        self.bids.first()
+    }
+}
+
+/// Largest fiat amount an order can be taken for.
+fn max_fiat(view: &OrderView) -> FiatAmount {
+    let fiat = view.fiat_range();
+    fiat.max.unwrap_or(fiat.min)
+}
+
+/// Distance from the market of a market-price order with `premium`, using
+/// mostrod's quote (the sats, not the price, are reduced by the premium).
+fn premium_vs_market(premium: i64) -> Option<f64> {
+    let factor = 1.0 - premium as f64 / 100.0;
+    (factor > 0.0).then(|| (1.0 / factor - 1.0) * 100.0)
+}
+
+/// Order by price, unknown prices last; `ascending` for asks.
+fn compare_entries(a: &BookEntry, b: &BookEntry, ascending: bool) -> Ordering {
+    match (a.vs_market, b.vs_market) {
+        (Some(x), Some(y)) => {
+            let by_price = if ascending {
+                x.total_cmp(&y)
+            } else {
+                y.total_cmp(&x)
+            };
+            by_price.then_with(|| a.view.order.created_at.cmp(&b.view.order.created_at))
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.view.order.created_at.cmp(&b.view.order.created_at),
+    }
+}
+
+fn build_side(views: Vec<OrderView>, market_price: Option<f64>, ascending: bool) -> Vec<BookEntry> {
+    let mut entries: Vec<BookEntry> = views
+        .into_iter()
+        .map(|view| {
+            let estimate = OrderEstimate::for_view(&view, market_price);
+            let vs_market = estimate.vs_market.or_else(|| {
+                (view.order.amount == 0)
+                    .then(|| premium_vs_market(view.order.premium))
+                    .flatten()
+            });
+            BookEntry {
+                view,
+                estimate,
+                vs_market,
+                depth: FiatAmount::default(),
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| compare_entries(a, b, ascending));
+    let mut depth = FiatAmount::default();
+    for entry in entries.iter_mut() {
+        depth = depth.saturating_add(max_fiat(&entry.view));
+        entry.depth = depth;
+    }
+    entries
+}
+
+/// Group `views` by currency into asks and bids, keeping those `filter`
+/// matches. `rates` prices fixed-sats orders against market-price ones.
+pub fn build_order_book(
+    views: Vec<OrderView>,
+    rates: Option<&BtcRates>,
+    filter: &OrderFilter,
+) -> Vec<CurrencyBook> {
+    let mut by_currency: BTreeMap<String, (Vec<OrderView>, Vec<OrderView>)> = BTreeMap::new();
+    let now = chrono::Utc::now().timestamp();
+    for view in views.into_iter().filter(|v| filter.matches_view(v, now)) {
+        let sides = by_currency.entry(view.order.fiat_code.clone()).or_default();
+        match view.order.kind {
+            Some(Kind::Sell) => sides.0.push(view),
+            Some(Kind::Buy) => sides.1.push(view),
+            None => {}
+        }
+    }
+    by_currency
+        .into_iter()
+        .map(|(fiat_code, (sells, buys))| {
+            let market_price = rates.and_then(|r| r.price(&fiat_code));
+            CurrencyBook {
+                asks: build_side(sells, market_price, true),
+                bids: build_side(buys, market_price, false),
+                market_price,
+                fiat_code,
+            }
+        })
+        .collect()
+}
+
+/// `52631.58 USD (+5.3%)`, or only the distance when no price is known.
+pub fn book_price_text(entry: &BookEntry) -> String {
+    match (entry.estimate.btc_price, entry.vs_market) {
+        (Some(_), _) => entry.estimate.price_text(&entry.view.order.fiat_code),
+        (None, Some(diff)) => format!("market {:+.1}%", diff),
+        (None, None) => "-".to_string(),
+    }
+}
+
+fn side_table(title: &str, entries: &[BookEntry]) -> Table {
+    if entries.is_empty() {
+        return create_empty_table(title, "No orders on this side…");
+    }
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        title,
+        "💹 Price vs Market",
+        "₿ Sats",
+        "💵 Fiat Amt",
+        "📚 Depth",
+        "💳 Payment Method",
+        "🆔 Order Id",
+    ]));
+    for (rank, entry) in entries.iter().enumerate() {
+        table.add_row(Row::from(vec![
+            Cell::new(rank + 1).set_alignment(CellAlignment::Center),
+            Cell::new(book_price_text(entry)).set_alignment(CellAlignment::Center),
+            Cell::new(entry.estimate.sats_text(&entry.view.order))
+                .set_alignment(CellAlignment::Center),
+            Cell::new(entry.view.fiat_amount_text()).set_alignment(CellAlignment::Center),
+            Cell::new(entry.depth).set_alignment(CellAlignment::Center),
+            Cell::new(&entry.view.order.payment_method).set_alignment(CellAlignment::Center),
+            Cell::new(
+                entry
+                    .view
+                    .order
+                    .id
+                    .map(|id| id.to_string())
+                    .unwrap_or_else(|| "N/A".to_string()),
+            )
+            .set_alignment(CellAlignment::Center),
+        ]));
+    }
+    table
+}
+
+/// Best bid, best ask and, when both have a price, the spread.
+pub fn book_summary(book: &CurrencyBook) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(price) = book.market_price {
+        lines.push(format!("📈 Market: {:.2} {}", price, book.fiat_code));
+    }
+    let best = |entry: Option<&BookEntry>| {
+        entry
+            .map(book_price_text)
+            .unwrap_or_else(|| "none".to_string())
+    };
+    lines.push(format!("🟢 Best bid: {}", best(book.best_bid())));
+    lines.push(format!("🔴 Best ask: {}", best(book.best_ask())));
+    let (bid, ask) = (
+        book.best_bid().and_then(|e| e.vs_market),
+        book.best_ask().and_then(|e| e.vs_market),
+    );
+    if let (Some(bid), Some(ask)) = (bid, ask) {
+        lines.push(format!("↔️ Spread: {:.1} points", ask - bid));
+    }
+    lines
+}
+
+/// Render every currency book: summary, then asks and bids.
+pub fn print_order_book(books: &[CurrencyBook]) -> String {
+    if books.is_empty() {
+        return create_empty_table(
+            "📭 Empty Book",
+            "No pending orders match the requested parameters…",
+        )
+        .to_string();
+    }
+    let mut out = String::new();
+    for book in books {
+        out.push_str(&format!("💱 {}\n", book.fiat_code));
+        out.push_str("═══════════════════════════════════════\n");
+        for line in book_summary(book) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out.push_str(&format!("{}\n", side_table("🔴 Asks (sell)", &book.asks)));
+        out.push_str(&format!("{}\n\n", side_table("🟢 Bids (buy)", &book.bids)));
+    }
+    out
+}
@@ -66,6 +66,23 @@ pub fn create_field_value_header() -> Vec<Cell> {
     ]
 }
 
+/// Create a bold, centered table header from column titles
+pub fn create_bold_header(titles: &[&str]) -> Vec<Cell> {
+    titles
+        .iter()
+        .map(|t| create_centered_cell(t, true))
+        .collect()
+}
+
+/// Create the table shown when there is nothing to list: a bold `title`
+/// over a red `message`
+pub fn create_empty_table(title: &str, message: &str) -> Table {
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[title]));
+    table.add_row(Row::from(vec![create_error_cell(message)]));
+    table
+}
+
 /// Create a centered cell with optional bold formatting
 pub fn create_centered_cell(content: &str, bold: bool) -> Cell {
     let mut cell = Cell::new(content).set_alignment(CellAlignment::Center);
@@ -183,3 +200,211 @@ pub fn print_trade_index(trade_index: u64) {
 pub fn print_order_count(count: usize) {
     println!("📊 Found {} order(s):", count);
 }
+
+/// Format a number of seconds as a compact duration, e.g. `1h 05m 30s`
+pub fn format_duration_secs(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m {:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
+
+/// Create a field/value table with the breakdown of a decoded bolt11 invoice
+pub fn create_invoice_table(decoded: &crate::lightning::DecodedInvoice) -> Table {
+    let mut table = create_standard_table();
+    table.set_header(create_field_value_header());
+    table.add_row(create_emoji_field_row(
+        "🌐 ",
+        "Network",
+        crate::lightning::network_name(&decoded.network),
+    ));
+    let amount = decoded
+        .amount_msat
+        .map(|msat| {
+            if msat % 1000 == 0 {
+                format!("{} sats", msat / 1000)
+            } else {
+                format!("{} sats ({} msats)", msat / 1000, msat)
+            }
+        })
+        .unwrap_or_else(|| "Any (amountless)".to_string());
+    table.add_row(create_emoji_field_row("💰 ", "Amount", &amount));
+    table.add_row(create_emoji_field_row("🔑 ", "Payee", &decoded.payee));
+    table.add_row(create_emoji_field_row(
+        "#️⃣ ",
+        "Payment Hash",
+        &decoded.payment_hash,
+    ));
+    match (&decoded.description, &decoded.description_hash) {
+        (Some(description), _) => {
+            table.add_row(create_emoji_field_row("📝 ", "Description", description));
+        }
+        (None, Some(hash)) => {
+            table.add_row(create_emoji_field_row("📝 ", "Description Hash", hash));
+        }
+        (None, None) => {}
+    }
+    table.add_row(create_emoji_field_row(
+        "📅 ",
+        "Created",
+        &format_timestamp(decoded.created_at as i64),
+    ));
+    table.add_row(create_emoji_field_row(
+        "⏳ ",
+        "Expires",
+        &format!(
+            "{} (after {})",
+            format_timestamp(decoded.expires_at() as i64),
+            format_duration_secs(decoded.expiry_secs)
+        ),
+    ));
+    let remaining = if decoded.is_expired() {
+        Cell::new("Expired").fg(Color::Red)
+    } else {
+        Cell::new(format_duration_secs(decoded.remaining_secs)).fg(Color::Green)
+    };
+    table.add_row(Row::from(vec![
+        Cell::new("⏰ Remaining").set_alignment(CellAlignment::Center),
+        remaining.set_alignment(CellAlignment::Center),
+    ]));
+    table.add_row(create_emoji_field_row(
+        "🧱 ",
+        "Min Final CLTV",
+        &decoded.min_final_cltv_expiry_delta.to_string(),
+    ));
+    for (i, hint) in decoded.route_hints.iter().enumerate() {
+        let hops = hint
+            .iter()
+            .map(|hop| {
+                format!(
+                    "{} via {} (base {} msat, {} ppm, cltv {})",
+                    hop.node_id,
+                    hop.short_channel_id,
+                    hop.fee_base_msat,
+                    hop.fee_ppm,
+                    hop.cltv_expiry_delta
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        table.add_row(create_emoji_field_row(
+            "🛣️ ",
+            &format!("Route Hint {}", i + 1),
+            &hops,
+        ));
+    }
+    table
+}
+
+/// Create a field/value table with every setting a Mostro node publishes
+pub fn create_node_info_table(info: &crate::util::NodeInfo) -> Table {
+    let mut table = create_standard_table();
+    table.set_header(create_field_value_header());
+    let mut row = |emoji: &str, field: &str, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            table.add_row(create_emoji_field_row(emoji, field, &value));
+        }
+    };
+    let list = |items: &[String]| Some(items.join(", "));
+
+    row("🧌 ", "Mostro Version", info.mostro_version.clone());
+    row("🔖 ", "Mostro Commit", info.mostro_commit_hash.clone());
+    row(
+        "📡 ",
+        "Protocol Version",
+        info.protocol_version.map(|v| v.to_string()),
+    );
+    row(
+        "💸 ",
+        "Fee",
+        info.fee.map(|fee| {
+            // Round away float noise such as 0.6000000000000001
+            let pct = |f: f64| (f * 1_000_000.0).round() / 10_000.0;
+            format!("{}% ({}% per side)", pct(fee), pct(fee / 2.0))
+        }),
+    );
+    row("⛏️ ", "Proof of Work", info.pow.map(|p| p.to_string()));
+    row(
+        "⬇️ ",
+        "Min Order Amount",
+        info.min_order_amount.map(|a| format!("{} sats", a)),
+    );
+    row(
+        "⬆️ ",
+        "Max Order Amount",
+        info.max_order_amount.map(|a| format!("{} sats", a)),
+    );
+    row(
+        "⏳ ",
+        "Order Expiration",
+        info.expiration_hours.map(|h| format!("{} hours", h)),
+    );
+    row(
+        "⏱️ ",
+        "Step Timeout",
+        info.expiration_seconds.map(format_duration_secs),
+    );
+    row(
+        "📆 ",
+        "Max Expiration",
+        info.max_expiration_days.map(|d| format!("{} days", d)),
+    );
+    row(
+        "📈 ",
+        "Max Premium",
+        info.max_premium.map(|p| format!("±{}%", p)),
+    );
+    row(
+        "📄 ",
+        "Max Orders per Response",
+        info.max_orders_per_response.map(|n| n.to_string()),
+    );
+    row(
+        "🔒 ",
+        "Hold Invoice Window",
+        info.hold_invoice_expiration_window
+            .map(format_duration_secs),
+    );
+    row(
+        "🧱 ",
+        "Hold Invoice CLTV Delta",
+        info.hold_invoice_cltv_delta.map(|d| d.to_string()),
+    );
+    row(
+        "🧾 ",
+        "Invoice Expiration Window",
+        info.invoice_expiration_window.map(format_duration_secs),
+    );
+    row(
+        "💱 ",
+        "Currencies",
+        Some(if info.fiat_currencies_accepted.is_empty() {
+            "Any".to_string()
+        } else {
+            info.fiat_currencies_accepted.join(", ")
+        }),
+    );
+    row("⚡ ", "LND Version", info.lnd_version.clone());
+    row("🔑 ", "LND Node Pubkey", info.lnd_node_pubkey.clone());
+    row("🔖 ", "LND Commit", info.lnd_commit_hash.clone());
+    row("🏷️ ", "LND Alias", info.lnd_node_alias.clone());
+    row("⛓️ ", "LND Chains", list(&info.lnd_chains));
+    row("🌐 ", "LND Networks", list(&info.lnd_networks));
+    row("🔗 ", "LND URIs", list(&info.lnd_uris));
+    for (name, value) in &info.bond_settings {
+        row("🛡️ ", name, Some(value.clone()));
+    }
+    for (name, value) in &info.other {
+        row("➕ ", name, Some(value.clone()));
+    }
+    row(
+        "📅 ",
+        "Published",
+        Some(format_timestamp(info.published_at as i64)),
+    );
+    table
+}
@@ -9,16 +9,21 @@ use comfy_table::*;
 use mostro_core::prelude::*;
 use nip44::v2::{decrypt_to_bytes, ConversationKey};
 use nostr_sdk::prelude::*;
+use sqlx::SqlitePool;
 
 use crate::{
     cli::Context,
-    db::{Order, User},
+    db::{MessageDirection, Order, User},
+    lightning::decode_invoice,
     parser::common::{
-        format_timestamp, print_amount_info, print_fiat_code, print_order_count,
-        print_payment_method, print_premium, print_required_amount, print_section_header,
-        print_success_message, print_trade_index,
+        create_invoice_table, format_timestamp, print_amount_info, print_fiat_code,
+        print_order_count, print_payment_method, print_premium, print_required_amount,
+        print_section_header, print_success_message, print_trade_index,
+    },
+    util::{
+        fetch_bond_claim_window_days, log_message, print_invoice_qr, record_bond_message,
+        save_order, QrOptions,
     },
-    util::{fetch_bond_claim_window_days, save_order},
 };
 use serde_json;
 
@@ -47,6 +52,20 @@ fn handle_new_order_display(order: &mostro_core::order::SmallOrder) {
     print_success_message("Order saved successfully!");
 }
 
+/// Print the decoded amount, expiry and payee of a hold invoice Mostro asks
+/// us to pay, so the exact terms are visible before paying it.
+fn print_hold_invoice_details(invoice: &str) {
+    match decode_invoice(invoice) {
+        Ok(decoded) => {
+            println!("{}", create_invoice_table(&decoded));
+            if decoded.is_expired() {
+                println!("⚠️  This invoice has already expired");
+            }
+        }
+        Err(e) => println!("⚠️  Could not decode invoice: {}", e),
+    }
+}
+
 /// Handle add invoice display
 fn handle_add_invoice_display(order: &mostro_core::order::SmallOrder) {
     print_section_header("⚡ Add Lightning Invoice");
@@ -59,7 +78,11 @@ fn handle_add_invoice_display(order: &mostro_core::order::SmallOrder) {
 }
 
 /// Handle pay invoice display
-fn handle_pay_invoice_display(order: &Option<mostro_core::order::SmallOrder>, invoice: &str) {
+fn handle_pay_invoice_display(
+    order: &Option<mostro_core::order::SmallOrder>,
+    invoice: &str,
+    qr: &QrOptions,
+) {
     print_section_header("💳 Payment Invoice Received");
     if let Some(order) = order {
         if let Some(order_id) = order.id {
@@ -70,15 +93,21 @@ fn handle_pay_invoice_display(order: &Option<mostro_core::order::SmallOrder>, in
         println!("💵 Fiat Amount: {}", order.fiat_amount);
     }
     println!();
+    print_hold_invoice_details(invoice);
     println!("⚡ LIGHTNING INVOICE TO PAY:");
     println!("─────────────────────────────────────");
     println!("{}", invoice);
     println!("─────────────────────────────────────");
+    print_invoice_qr(invoice, qr);
     println!("💡 Pay this invoice to continue the trade");
     println!();
 }
 
-fn handle_pay_bond_invoice_display(order: &Option<mostro_core::order::SmallOrder>, invoice: &str) {
+fn handle_pay_bond_invoice_display(
+    order: &Option<mostro_core::order::SmallOrder>,
+    invoice: &str,
+    qr: &QrOptions,
+) {
     print_section_header("🪙 Anti-Abuse Bond Invoice");
     if let Some(order) = order {
         if let Some(order_id) = order.id {
@@ -89,10 +118,12 @@ fn handle_pay_bond_invoice_display(order: &Option<mostro_core::order::SmallOrder
         println!("💵 Fiat Amount: {}", order.fiat_amount);
     }
     println!();
+    print_hold_invoice_details(invoice);
     println!("⚡ LIGHTNING BOND INVOICE TO PAY:");
     println!("─────────────────────────────────────");
     println!("{}", invoice);
     println!("─────────────────────────────────────");
+    print_invoice_qr(invoice, qr);
     println!("💡 Pay this hold invoice to lock your taker bond.");
     println!("💡 The trade hold invoice will arrive next.");
     println!();
@@ -466,6 +497,16 @@ fn display_solver_dispute_info(dispute_info: &mostro_core::dispute::SolverDisput
 
 /// Execute logic of command answer
 pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Result<()> {
+    // Keep the local bond ledger in step with whatever Mostro reports. A
+    // payout request also records its claim deadline, from the node's window.
+    let claim_window_days = match message.action {
+        Action::AddBondInvoice => fetch_bond_claim_window_days(ctx).await,
+        _ => None,
+    };
+    if let Err(e) = record_bond_message(&ctx.pool, message, claim_window_days).await {
+        println!("⚠️  Failed to update bond records: {}", e);
+    }
+
     // Do the logic for the message response
     match message.action {
         Action::NewOrder => {
@@ -476,6 +517,7 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                         &ctx.trade_keys,
                         req_id,
                         ctx.trade_index,
+                        true,
                         &ctx.pool,
                     )
                     .await
@@ -528,6 +570,7 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                         &ctx.trade_keys,
                         req_id,
                         ctx.trade_index,
+                        false,
                         &ctx.pool,
                     )
                     .await
@@ -546,7 +589,7 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
         // this is the case where the buyer pays the invoice coming from a takebuy
         Action::PayInvoice => {
             if let Some(Payload::PaymentRequest(order, invoice, _)) = &message.payload {
-                handle_pay_invoice_display(order, invoice);
+                handle_pay_invoice_display(order, invoice, &ctx.qr);
 
                 if let Some(order) = order {
                     if let Some(req_id) = message.request_id {
@@ -557,6 +600,7 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                             &ctx.trade_keys,
                             req_id,
                             ctx.trade_index,
+                            false,
                             &ctx.pool,
                         )
                         .await
@@ -585,7 +629,7 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                     ));
                 }
             };
-            handle_pay_bond_invoice_display(order, invoice);
+            handle_pay_bond_invoice_display(order, invoice, &ctx.qr);
             let order = order.as_ref().ok_or_else(|| {
                 anyhow::anyhow!("PayBondInvoice payload is missing the SmallOrder")
             })?;
@@ -597,6 +641,7 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                 &ctx.trade_keys,
                 req_id,
                 ctx.trade_index,
+                false,
                 &ctx.pool,
             )
             .await
@@ -609,7 +654,6 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
         // `add-bond-invoice` carrying a bolt11 for your share.
         Action::AddBondInvoice => match &message.payload {
             Some(Payload::BondPayoutRequest(req)) => {
-                let claim_window_days = fetch_bond_claim_window_days(ctx).await;
                 handle_add_bond_invoice_request_display(req, claim_window_days);
                 Ok(())
             }
@@ -628,8 +672,43 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                     println!("💰 Amount Error");
                     println!("💡 The amount is outside the allowed range");
                     println!("📊 Please check the order's min/max limits");
+                    let limits = ctx.node_info().await.and_then(|info| {
+                        match (info.min_order_amount, info.max_order_amount) {
+                            (None, None) => None,
+                            (min, max) => Some(format!(
+                                "{}-{} sats",
+                                min.map_or("?".to_string(), |m| m.to_string()),
+                                max.map_or("?".to_string(), |m| m.to_string())
+                            )),
+                        }
+                    });
+                    if let Some(limits) = &limits {
+                        println!("📏 This node accepts orders of {}", limits);
+                    }
+                    Err(anyhow::anyhow!(
+                        "Amount is outside the allowed range{}. Please check the order's min/max limits.",
+                        limits
+                            .map(|l| format!(" (node accepts {})", l))
+                            .unwrap_or_default()
+                    ))
+                }
+                Some(Payload::CantDo(Some(CantDoReason::InvalidAmount))) => {
+                    println!("💰 Invalid Amount");
+                    println!(
+                        "💡 Amounts must be positive and fiat ranges must go from low to high"
+                    );
+                    Err(anyhow::anyhow!(
+                        "Invalid amount. Amounts must be positive and a fiat range minimum lower than its maximum."
+                    ))
+                }
+                Some(Payload::CantDo(Some(CantDoReason::InvalidParameters))) => {
+                    println!("⚙️  Invalid Parameters");
+                    println!("💡 Mostro rejected the combination of order fields");
+                    println!(
+                        "📊 A premium only applies when the sats amount is left at market price"
+                    );
                     Err(anyhow::anyhow!(
-                        "Amount is outside the allowed range. Please check the order's min/max limits."
+                        "Invalid parameters. A premium only applies when the sats amount is left at market price."
                     ))
                 }
                 Some(Payload::CantDo(Some(CantDoReason::PendingOrderExists))) => {
@@ -652,7 +731,21 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
                     println!("💱 Invalid Currency");
                     println!("💡 The fiat currency is not supported");
                     println!("📊 Please use a valid currency");
-                    Err(anyhow::anyhow!("Invalid currency"))
+                    let accepted = ctx
+                        .node_info()
+                        .await
+                        .map(|info| info.fiat_currencies_accepted.join(", "))
+                        .filter(|list| !list.is_empty());
+                    match accepted {
+                        Some(list) => {
+                            println!("💱 Accepted currencies: {}", list);
+                            Err(anyhow::anyhow!(
+                                "Invalid currency. Accepted currencies: {}",
+                                list
+                            ))
+                        }
+                        None => Err(anyhow::anyhow!("Invalid currency")),
+                    }
                 }
                 Some(Payload::CantDo(Some(CantDoReason::NotFound))) => Err(anyhow::anyhow!(
                     "Resource not found. Verify the order or dispute id exists."
@@ -890,11 +983,14 @@ pub async fn print_commands_results(message: &MessageKind, ctx: &Context) -> Res
 ///
 /// Keeping the two explicit avoids misparsing peer chat as a Mostro message on
 /// the v2 transport, where both share kind 14 (see docs/TRANSPORT_V2_SPEC.md).
+///
+/// Mostro-protocol messages are recorded in `pool` when one is given.
 pub async fn parse_dm_events(
     events: Events,
     pubkey: &Keys,
     since: Option<&i64>,
     mostro_protocol: bool,
+    pool: Option<&SqlitePool>,
 ) -> Vec<(Message, u64, PublicKey)> {
     let mut id_set = HashSet::<EventId>::new();
     let mut direct_messages: Vec<(Message, u64, PublicKey)> = Vec::new();
@@ -907,7 +1003,19 @@ pub async fn parse_dm_events(
 
         let (created_at, message, sender) = if mostro_protocol {
             match unwrap_incoming(dm, pubkey).await {
-                Ok(Some(u)) => (u.created_at, u.message, u.sender),
+                Ok(Some(u)) => {
+                    if let Some(pool) = pool {
+                        log_message(
+                            pool,
+                            &dm.id,
+                            MessageDirection::Received,
+                            &u.message,
+                            u.created_at.as_secs(),
+                        )
+                        .await;
+                    }
+                    (u.created_at, u.message, u.sender)
+                }
                 Ok(None) => continue, // decrypt failed → not addressed to us
                 Err(e) => {
                     eprintln!("Warning: could not unwrap message (event {}): {e}", dm.id);
@@ -983,6 +1091,18 @@ pub async fn print_direct_messages(
     dm: &[(Message, u64, PublicKey)],
     mostro_pubkey: Option<PublicKey>,
     claim_window_days: Option<i64>,
+) -> Result<()> {
+    print_direct_messages_with_qr(dm, mostro_pubkey, claim_window_days, &QrOptions::from_env())
+        .await
+}
+
+/// Like [`print_direct_messages`], rendering the bolt11 of `pay-invoice` and
+/// `pay-bond-invoice` messages as QR codes according to `qr`.
+pub async fn print_direct_messages_with_qr(
+    dm: &[(Message, u64, PublicKey)],
+    mostro_pubkey: Option<PublicKey>,
+    claim_window_days: Option<i64>,
+    qr: &QrOptions,
 ) -> Result<()> {
     if dm.is_empty() {
         println!();
@@ -1049,6 +1169,12 @@ pub async fn print_direct_messages(
             println!("📝 Details: -");
         }
 
+        if qr.is_enabled() && matches!(inner.action, Action::PayInvoice | Action::PayBondInvoice) {
+            if let Some(Payload::PaymentRequest(_, invoice, _)) = &inner.payload {
+                print_invoice_qr(invoice, qr);
+            }
+        }
+
         println!();
     }
 
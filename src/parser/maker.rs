@@ -0,0 +1,89 @@
+use comfy_table::*;
+
+use crate::db::{MakerOffer, MakerOrder};
+use crate::maker::offer_description;
+use crate::parser::common::{
+    apply_status_color, create_bold_header, create_empty_table, create_standard_table,
+    format_timestamp,
+};
+
+/// Table of the standing offers with their live order, if any.
+pub fn print_offers_table(offers: &[(MakerOffer, Option<MakerOrder>)]) -> String {
+    if offers.is_empty() {
+        return create_empty_table(
+            "📭 No Offers",
+            "No standing offers yet, add one with addoffer…",
+        )
+        .to_string();
+    }
+
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        "🆔 Id",
+        "🏷️ Name",
+        "📋 Offer",
+        "🟢 Live Order",
+        "🔢 Trade Index",
+    ]));
+    for (offer, live) in offers {
+        let (order_id, trade_index) = match live {
+            Some(order) => (order.order_id.clone(), order.trade_index.to_string()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        table.add_row(Row::from(vec![
+            Cell::new(offer.id).set_alignment(CellAlignment::Center),
+            Cell::new(&offer.name).set_alignment(CellAlignment::Center),
+            Cell::new(offer_description(offer)).set_alignment(CellAlignment::Center),
+            Cell::new(order_id).set_alignment(CellAlignment::Center),
+            Cell::new(trade_index).set_alignment(CellAlignment::Center),
+        ]));
+    }
+
+    table.to_string()
+}
+
+/// Table of every order published for an offer, oldest first, each with
+/// the order it replaced.
+pub fn print_offer_history_table(orders: &[MakerOrder]) -> String {
+    if orders.is_empty() {
+        return create_empty_table("📭 No Orders", "No orders published for this offer yet…")
+            .to_string();
+    }
+
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        "📋 Order",
+        "🔢 Trade Index",
+        "↩️ Replaces",
+        "💰 Sats",
+        "📊 Status",
+        "🏁 Closed",
+        "📅 Published",
+    ]));
+    for order in orders {
+        let sats = match order.amount {
+            0 => "market".to_string(),
+            amount => amount.to_string(),
+        };
+        let closed = match (&order.closed_reason, order.closed_at) {
+            (Some(reason), Some(at)) => format!("{} ({})", reason, format_timestamp(at)),
+            (Some(reason), None) => reason.clone(),
+            _ => "live".to_string(),
+        };
+        table.add_row(Row::from(vec![
+            Cell::new(&order.order_id).set_alignment(CellAlignment::Center),
+            Cell::new(order.trade_index).set_alignment(CellAlignment::Center),
+            Cell::new(order.parent_order_id.as_deref().unwrap_or("-"))
+                .set_alignment(CellAlignment::Center),
+            Cell::new(sats).set_alignment(CellAlignment::Center),
+            apply_status_color(
+                Cell::new(&order.status).set_alignment(CellAlignment::Center),
+                &order.status,
+            ),
+            Cell::new(closed).set_alignment(CellAlignment::Center),
+            Cell::new(format_timestamp(order.created_at)).set_alignment(CellAlignment::Center),
+        ]));
+    }
+
+    table.to_string()
+}
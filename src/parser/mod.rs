@@ -1,7 +1,14 @@
+pub mod alerts;
+pub mod bonds;
+pub mod book;
 pub mod common;
 pub mod disputes;
 pub mod dms;
+pub mod maker;
+pub mod order_feed;
 pub mod orders;
+pub mod templates;
+pub mod timeline;
 
 pub use common::{
     apply_kind_color, apply_status_color, create_centered_cell, create_emoji_field_row,
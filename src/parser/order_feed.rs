@@ -0,0 +1,232 @@
+//! Live order book for `listorders --follow`.
+//!
+//! [`OrderFeed`] keeps the newest revision of every order it has seen, merged
+//! by `d` tag exactly like
+//! [`parse_order_views`](crate::parser::orders::parse_order_views), and
+//! reports how each new event changes the set of orders the user asked for.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::DateTime;
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use uuid::Uuid;
+
+use crate::nip33::OrderView;
+use crate::parser::orders::{merge_latest, order_view_from_event, order_view_matches, OrderFilter};
+
+/// What happened to an order in the followed book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderChangeKind {
+    /// Published, or now matching the filters.
+    New,
+    /// Still listed, with new terms.
+    Updated,
+    /// Left the book because someone took it.
+    Taken,
+    Canceled,
+    /// Expired on Mostro, or passed its `expires_at` while watched.
+    Expired,
+    /// No longer matches the filters for another reason.
+    Removed,
+}
+
+impl OrderChangeKind {
+    /// Why an order whose newest revision is in `status` left the book.
+    pub fn leaving(status: Option<Status>) -> Self {
+        match status {
+            Some(Status::Canceled | Status::CanceledByAdmin | Status::CooperativelyCanceled) => {
+                Self::Canceled
+            }
+            Some(Status::Expired) => Self::Expired,
+            Some(
+                Status::Active
+                | Status::InProgress
+                | Status::WaitingBuyerInvoice
+                | Status::WaitingPayment
+                | Status::WaitingTakerBond
+                | Status::FiatSent
+                | Status::SettledHoldInvoice
+                | Status::Success
+                | Status::Dispute
+                | Status::SettledByAdmin
+                | Status::CompletedByAdmin,
+            ) => Self::Taken,
+            _ => Self::Removed,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::New => "🆕 NEW",
+            Self::Updated => "✏️ UPDATED",
+            Self::Taken => "🤝 TAKEN",
+            Self::Canceled => "❌ CANCELED",
+            Self::Expired => "⌛ EXPIRED",
+            Self::Removed => "🚫 REMOVED",
+        }
+    }
+}
+
+/// One change to the followed book, with the order as it is now.
+#[derive(Debug, Clone)]
+pub struct OrderChange {
+    pub kind: OrderChangeKind,
+    pub view: OrderView,
+}
+
+/// Newest revision of every order seen, and which of them are listed.
+#[derive(Debug, Default)]
+pub struct OrderFeed {
+    currency: Option<String>,
+    status: Option<Status>,
+    kind: Option<mostro_core::order::Kind>,
+    filter: OrderFilter,
+    latest: HashMap<Uuid, OrderView>,
+    listed: HashSet<Uuid>,
+}
+
+impl OrderFeed {
+    /// Follow orders with `currency`, `status` and `kind` (as for
+    /// [`parse_order_views`](crate::parser::orders::parse_order_views)) that
+    /// also pass `filter`.
+    pub fn new(
+        currency: Option<String>,
+        status: Option<Status>,
+        kind: Option<mostro_core::order::Kind>,
+        filter: OrderFilter,
+    ) -> Self {
+        Self {
+            currency,
+            status,
+            kind,
+            filter,
+            ..Default::default()
+        }
+    }
+
+    fn is_listed(&self, view: &OrderView, now: i64) -> bool {
+        order_view_matches(view, self.currency.as_deref(), self.status, self.kind)
+            && self.filter.matches_view(view, now)
+            && view.expires_at.is_none_or(|at| at > now)
+    }
+
+    /// Load the initial snapshot without reporting changes.
+    pub fn seed(&mut self, events: &Events, now: i64) {
+        for event in events.iter() {
+            self.apply(event, now);
+        }
+    }
+
+    /// Merge one order event; the change it makes to the listed orders, if
+    /// any. Revisions older than the one already seen are ignored.
+    pub fn apply(&mut self, event: &Event, now: i64) -> Option<OrderChange> {
+        let view = order_view_from_event(event)?;
+        let id = view.order.id?;
+        if !merge_latest(&mut self.latest, view) {
+            return None;
+        }
+        let view = self.latest.get(&id)?.clone();
+        let was_listed = self.listed.contains(&id);
+        let kind = match (was_listed, self.is_listed(&view, now)) {
+            (false, false) => return None,
+            (false, true) => {
+                self.listed.insert(id);
+                OrderChangeKind::New
+            }
+            (true, true) => OrderChangeKind::Updated,
+            (true, false) => {
+                self.listed.remove(&id);
+                OrderChangeKind::leaving(view.order.status)
+            }
+        };
+        Some(OrderChange { kind, view })
+    }
+
+    /// Drop listed orders whose `expires_at` has passed by `now`; Mostro may
+    /// publish their `expired` revision late or not at all.
+    pub fn expire(&mut self, now: i64) -> Vec<OrderChange> {
+        let expired: Vec<Uuid> = self
+            .listed
+            .iter()
+            .filter(|id| {
+                self.latest
+                    .get(id)
+                    .and_then(|v| v.expires_at)
+                    .is_some_and(|at| at <= now)
+            })
+            .copied()
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| {
+                self.listed.remove(&id);
+                Some(OrderChange {
+                    kind: OrderChangeKind::Expired,
+                    view: self.latest.get(&id)?.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Newest revision seen of order `id`, listed or not.
+    pub fn latest(&self, id: &Uuid) -> Option<&OrderView> {
+        self.latest.get(id)
+    }
+
+    /// The listed orders, newest first.
+    pub fn listed(&self) -> Vec<OrderView> {
+        let mut views: Vec<OrderView> = self
+            .listed
+            .iter()
+            .filter_map(|id| self.latest.get(id).cloned())
+            .collect();
+        views.sort_by(|a, b| b.order.created_at.cmp(&a.order.created_at));
+        views
+    }
+}
+
+/// One line per change: `[14:03:09] 🆕 NEW sell 50 EUR @ +1% via SEPA <id>`.
+pub fn order_change_line(change: &OrderChange, now: i64) -> String {
+    format!(
+        "[{}] {} {}",
+        clock_text(now),
+        change.kind.label(),
+        order_summary(&change.view)
+    )
+}
+
+/// `HH:MM:SS` of a unix time, in UTC.
+pub fn clock_text(now: i64) -> String {
+    DateTime::from_timestamp(now, 0)
+        .map(|d| d.format("%H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+
+/// One-line order terms: `sell 50 EUR @ +1% via SEPA <id>`, with the sats
+/// of fixed-price orders.
+pub fn order_summary(view: &OrderView) -> String {
+    let order = &view.order;
+    let kind = order
+        .kind
+        .map(|k| k.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let sats = if order.amount > 0 {
+        format!(" for {} sats", order.amount)
+    } else {
+        String::new()
+    };
+    format!(
+        "{} {} {}{} @ {:+}% via {} {}",
+        kind,
+        view.fiat_amount_text(),
+        order.fiat_code,
+        sats,
+        order.premium,
+        order.payment_method,
+        order
+            .id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "N/A".to_string()),
+    )
+}
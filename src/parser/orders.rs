@@ -1,7 +1,12 @@
 use std::collections::HashMap;
 
-use crate::parser::common::{apply_kind_color, apply_status_color, create_error_cell};
-use crate::util::Event;
+use crate::fiat::{FiatAmount, FiatRange};
+use crate::lightning::mostro_fee_share;
+use crate::parser::common::{
+    apply_kind_color, apply_status_color, create_emoji_field_row, create_error_cell,
+    create_field_value_header, create_standard_table, format_duration_secs, format_timestamp,
+};
+use crate::price::{BtcRates, OrderEstimate};
 use anyhow::Result;
 use chrono::DateTime;
 use comfy_table::presets::UTF8_FULL;
@@ -11,7 +16,7 @@ use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
 use uuid::Uuid;
 
-use crate::nip33::order_from_tags;
+use crate::nip33::{order_view_from_tags, MakerRating, OrderView};
 
 pub fn parse_orders_events(
     events: Events,
@@ -19,66 +24,202 @@ pub fn parse_orders_events(
     status: Option<Status>,
     kind: Option<mostro_core::order::Kind>,
 ) -> Vec<SmallOrder> {
+    parse_order_views(events, currency, status, kind)
+        .into_iter()
+        .map(|view| view.order)
+        .collect()
+}
+
+/// Like [`parse_orders_events`], keeping every tag of the newest event of
+/// each order in an [`OrderView`].
+pub fn parse_order_views(
+    events: Events,
+    currency: Option<String>,
+    status: Option<Status>,
+    kind: Option<mostro_core::order::Kind>,
+) -> Vec<OrderView> {
     // HashMap to store the latest order by id
-    let mut latest_by_id: HashMap<Uuid, SmallOrder> = HashMap::new();
+    let mut latest_by_id: HashMap<Uuid, OrderView> = HashMap::new();
 
     for event in events.iter() {
-        // Get order from tags
-        let mut order = match order_from_tags(event.tags.clone()) {
-            Ok(o) => o,
-            Err(e) => {
-                error!("{e:?}");
-                continue;
-            }
-        };
-        // Get order id
-        let order_id = match order.id {
-            Some(id) => id,
-            None => {
-                info!("Order ID is none");
-                continue;
-            }
-        };
-        // Check if order kind is none
-        if order.kind.is_none() {
-            info!("Order kind is none");
+        let Some(view) = order_view_from_event(event) else {
             continue;
-        }
-        // Set created at
-        order.created_at = Some(event.created_at.as_secs() as i64);
-        // Update latest order by id
-        latest_by_id
-            .entry(order_id)
-            .and_modify(|existing| {
-                let new_ts = order.created_at.unwrap_or(0);
-                let old_ts = existing.created_at.unwrap_or(0);
-                if new_ts > old_ts {
-                    *existing = order.clone();
-                }
-            })
-            .or_insert(order);
+        };
+        merge_latest(&mut latest_by_id, view);
     }
 
-    let mut requested: Vec<SmallOrder> = latest_by_id
+    let mut requested: Vec<OrderView> = latest_by_id
         .into_values()
-        .filter(|o| status.map(|s| o.status == Some(s)).unwrap_or(true))
-        .filter(|o| currency.as_ref().map(|c| o.fiat_code == *c).unwrap_or(true))
-        .filter(|o| {
-            kind.as_ref()
-                .map(|k| o.kind.as_ref() == Some(k))
-                .unwrap_or(true)
-        })
+        .filter(|v| order_view_matches(v, currency.as_deref(), status, kind))
         .collect();
 
-    requested.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    requested.sort_by(|a, b| b.order.created_at.cmp(&a.order.created_at));
     requested
 }
 
-pub fn print_order_preview(ord: Payload) -> Result<String, String> {
+/// Parse one order event: every tag plus the event's `created_at`. `None`
+/// when the event has no order id or kind.
+pub fn order_view_from_event(event: &Event) -> Option<OrderView> {
+    // Get order from tags
+    let mut view = match order_view_from_tags(event.tags.clone()) {
+        Ok(v) => v,
+        Err(e) => {
+            error!("{e:?}");
+            return None;
+        }
+    };
+    // Check order id and kind
+    if view.order.id.is_none() {
+        info!("Order ID is none");
+        return None;
+    }
+    if view.order.kind.is_none() {
+        info!("Order kind is none");
+        return None;
+    }
+    // Set created at
+    view.order.created_at = Some(event.created_at.as_secs() as i64);
+    Some(view)
+}
+
+/// Keep `view` in `latest_by_id` if it is newer than the stored revision of
+/// the same order. Returns whether it was kept.
+pub fn merge_latest(latest_by_id: &mut HashMap<Uuid, OrderView>, view: OrderView) -> bool {
+    let Some(order_id) = view.order.id else {
+        return false;
+    };
+    match latest_by_id.get_mut(&order_id) {
+        Some(existing) => {
+            let new_ts = view.order.created_at.unwrap_or(0);
+            let old_ts = existing.order.created_at.unwrap_or(0);
+            if new_ts > old_ts {
+                *existing = view;
+                true
+            } else {
+                false
+            }
+        }
+        None => {
+            latest_by_id.insert(order_id, view);
+            true
+        }
+    }
+}
+
+/// Whether `view` has the requested fiat `currency`, `status` and `kind`;
+/// `None` matches anything.
+pub fn order_view_matches(
+    view: &OrderView,
+    currency: Option<&str>,
+    status: Option<Status>,
+    kind: Option<mostro_core::order::Kind>,
+) -> bool {
+    status.is_none_or(|s| view.order.status == Some(s))
+        && currency.is_none_or(|c| view.order.fiat_code == c)
+        && kind.is_none_or(|k| view.order.kind == Some(k))
+}
+
+/// Client-side order filters, applied on top of what [`parse_orders_events`]
+/// returns. Empty fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct OrderFilter {
+    /// Keep orders whose payment methods contain any of these, ignoring case.
+    pub payment_methods: Vec<String>,
+    /// Keep orders that can be taken for exactly this fiat amount.
+    pub fiat_amount: Option<FiatAmount>,
+    pub min_premium: Option<i64>,
+    pub max_premium: Option<i64>,
+    /// Keep orders whose maker has at least this average rating; orders
+    /// without a rating are dropped.
+    pub min_rating: Option<f64>,
+    /// Keep orders published at most this many seconds ago.
+    pub max_age_secs: Option<i64>,
+}
+
+impl OrderFilter {
+    /// Whether `order`, whose maker has `rating`, passes every filter at
+    /// unix time `now`.
+    pub fn matches(&self, order: &SmallOrder, rating: Option<&MakerRating>, now: i64) -> bool {
+        self.matches_fiat(order, FiatRange::from(order), rating, now)
+    }
+
+    /// [`OrderFilter::matches`] for an order event, checking the fiat amount
+    /// against the exact `fa` tag rather than the whole units of `order`.
+    pub fn matches_view(&self, view: &OrderView, now: i64) -> bool {
+        self.matches_fiat(
+            &view.order,
+            view.fiat_range(),
+            view.maker_rating.as_ref(),
+            now,
+        )
+    }
+
+    fn matches_fiat(
+        &self,
+        order: &SmallOrder,
+        fiat: FiatRange,
+        rating: Option<&MakerRating>,
+        now: i64,
+    ) -> bool {
+        let methods = order.payment_method.to_lowercase();
+        let method_ok = self.payment_methods.is_empty()
+            || self
+                .payment_methods
+                .iter()
+                .any(|m| methods.contains(&m.trim().to_lowercase()));
+        let amount_ok = self.fiat_amount.is_none_or(|amount| fiat.contains(amount));
+        let premium_ok = self.min_premium.is_none_or(|min| order.premium >= min)
+            && self.max_premium.is_none_or(|max| order.premium <= max);
+        let rating_ok = self
+            .min_rating
+            .is_none_or(|min| rating.is_some_and(|r| r.total_rating >= min));
+        let age_ok = self
+            .max_age_secs
+            .is_none_or(|max| now - order.created_at.unwrap_or(0) <= max);
+        method_ok && amount_ok && premium_ok && rating_ok && age_ok
+    }
+}
+
+/// Order of the `listorders` results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OrderSort {
+    /// Newest first
+    #[default]
+    Age,
+    /// Lowest premium first
+    Premium,
+    /// Smallest fiat amount first (the maximum of range orders)
+    Amount,
+}
+
+/// Sort `orders` by `sort`, reversed when `reverse` is set.
+pub fn sort_orders(orders: &mut [OrderView], sort: OrderSort, reverse: bool) {
+    match sort {
+        OrderSort::Age => orders.sort_by(|a, b| b.order.created_at.cmp(&a.order.created_at)),
+        OrderSort::Premium => orders.sort_by_key(|v| v.order.premium),
+        OrderSort::Amount => orders.sort_by_key(|v| {
+            let fiat = v.fiat_range();
+            fiat.max.unwrap_or(fiat.min)
+        }),
+    }
+    if reverse {
+        orders.reverse();
+    }
+}
+
+/// Preview of a new order. `fee` is the node's Mostro fee, shown as the
+/// maker's share when known. `market_price` (fiat per BTC) adds the estimated
+/// sats of market-price orders and the price the order trades at.
+pub fn print_order_preview(
+    ord: Payload,
+    fee: Option<f64>,
+    market_price: Option<f64>,
+) -> Result<String, String> {
     let single_order = match ord {
         Payload::Order(o) => o,
         _ => return Err("Error".to_string()),
     };
+    let estimate = OrderEstimate::new(&single_order, market_price);
 
     let mut table = Table::new();
 
@@ -105,8 +246,33 @@ pub fn print_order_preview(ord: Payload) -> Result<String, String> {
             Cell::new("📊 Premium %")
                 .add_attribute(Attribute::Bold)
                 .set_alignment(CellAlignment::Center),
+            Cell::new("💹 Price vs Market")
+                .add_attribute(Attribute::Bold)
+                .set_alignment(CellAlignment::Center),
+            Cell::new("💸 Mostro Fee")
+                .add_attribute(Attribute::Bold)
+                .set_alignment(CellAlignment::Center),
         ]);
 
+    // Each side pays half the node fee
+    let fee_text = match fee {
+        None => "unknown".to_string(),
+        Some(fee) if single_order.amount > 0 => format!(
+            "{} sats ({}%)",
+            mostro_fee_share(single_order.amount, fee),
+            (fee * 500_000.0).round() / 10_000.0
+        ),
+        Some(fee) if estimate.min_sats.is_some() => format!(
+            "~{} sats ({}%)",
+            mostro_fee_share(estimate.min_sats.unwrap_or(0), fee),
+            (fee * 500_000.0).round() / 10_000.0
+        ),
+        Some(fee) => format!(
+            "{}% of the sats amount",
+            (fee * 500_000.0).round() / 10_000.0
+        ),
+    };
+
     //Table rows
     let r = Row::from(vec![
         if let Some(k) = single_order.kind {
@@ -117,11 +283,7 @@ pub fn print_order_preview(ord: Payload) -> Result<String, String> {
         } else {
             Cell::new("BUY/SELL").set_alignment(CellAlignment::Center)
         },
-        if single_order.amount == 0 {
-            Cell::new("market").set_alignment(CellAlignment::Center)
-        } else {
-            Cell::new(single_order.amount).set_alignment(CellAlignment::Center)
-        },
+        Cell::new(estimate.sats_text(&single_order)).set_alignment(CellAlignment::Center),
         Cell::new(single_order.fiat_code.to_string()).set_alignment(CellAlignment::Center),
         // No range order print row
         if single_order.min_amount.is_none() && single_order.max_amount.is_none() {
@@ -137,6 +299,9 @@ pub fn print_order_preview(ord: Payload) -> Result<String, String> {
         },
         Cell::new(single_order.payment_method.to_string()).set_alignment(CellAlignment::Center),
         Cell::new(single_order.premium.to_string()).set_alignment(CellAlignment::Center),
+        Cell::new(estimate.price_text(&single_order.fiat_code))
+            .set_alignment(CellAlignment::Center),
+        Cell::new(fee_text).set_alignment(CellAlignment::Center),
     ]);
 
     table.add_row(r);
@@ -146,25 +311,59 @@ pub fn print_order_preview(ord: Payload) -> Result<String, String> {
     result.push_str("═══════════════════════════════════════\n");
     result.push_str("📋 Order Preview - Please review carefully\n");
     result.push_str("💡 This order will be submitted to Mostro\n");
-    result.push_str("✅ All details look correct? (Y/n)\n");
     result.push_str("═══════════════════════════════════════\n");
 
     Ok(result)
 }
 
-pub fn print_orders_table(orders_table: Vec<Event>) -> Result<String> {
+/// Compact maker reputation for tables: `4.5★ (12, 90d)` (rating, reviews,
+/// days active), `new (3d)` before the first review, `-` without a
+/// `rating` tag.
+pub fn maker_rating_text(rating: Option<&MakerRating>) -> String {
+    match rating {
+        None => "-".to_string(),
+        Some(r) if r.total_reviews == 0 => format!("new ({}d)", r.days),
+        Some(r) => format!("{:.1}★ ({}, {}d)", r.total_rating, r.total_reviews, r.days),
+    }
+}
+
+/// Spelled-out maker reputation for the order detail view.
+pub fn maker_rating_long_text(rating: Option<&MakerRating>) -> String {
+    match rating {
+        None => "not published".to_string(),
+        Some(r) => format!(
+            "{:.1}/5 from {} review{}, active for {} day{}",
+            r.total_rating,
+            r.total_reviews,
+            if r.total_reviews == 1 { "" } else { "s" },
+            r.days,
+            if r.days == 1 { "" } else { "s" }
+        ),
+    }
+}
+
+/// Time left before `expires_at` at `now`: `in 5h 03m 00s`, `in 2d 04h`,
+/// `expired`, or `-`.
+pub fn expires_text(expires_at: Option<i64>, now: i64) -> String {
+    match expires_at {
+        None => "-".to_string(),
+        Some(at) if at <= now => "expired".to_string(),
+        Some(at) if at - now >= 86_400 => {
+            let left = at - now;
+            format!("in {}d {:02}h", left / 86_400, (left % 86_400) / 3_600)
+        }
+        Some(at) => format!("in {}", format_duration_secs((at - now) as u64)),
+    }
+}
+
+/// Table of public orders. With `rates`, market-price orders show their
+/// estimated sats and every order shows its BTC price against the market.
+pub fn print_orders_table(
+    orders_table: Vec<OrderView>,
+    rates: Option<&BtcRates>,
+) -> Result<String> {
     let mut table = Table::new();
-    // Convert Event to SmallOrder
-    let orders_table: Vec<SmallOrder> = orders_table
-        .into_iter()
-        .filter_map(|event| {
-            if let Event::SmallOrder(order) = event {
-                Some(order)
-            } else {
-                None
-            }
-        })
-        .collect();
+    let now = chrono::Utc::now().timestamp();
 
     //Table rows
     let mut rows: Vec<Row> = Vec::new();
@@ -217,14 +416,28 @@ pub fn print_orders_table(orders_table: Vec<Event>) -> Result<String> {
                 Cell::new("📊 Premium %")
                     .add_attribute(Attribute::Bold)
                     .set_alignment(CellAlignment::Center),
+                Cell::new("💹 Price vs Market")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Center),
+                Cell::new("⭐ Maker")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Center),
+                Cell::new("⏳ Expires")
+                    .add_attribute(Attribute::Bold)
+                    .set_alignment(CellAlignment::Center),
                 Cell::new("📅 Created")
                     .add_attribute(Attribute::Bold)
                     .set_alignment(CellAlignment::Center),
             ]);
 
         //Iterate to create table of orders
-        for single_order in orders_table.into_iter() {
+        for view in orders_table.into_iter() {
+            let single_order = &view.order;
             let date = DateTime::from_timestamp(single_order.created_at.unwrap_or(0), 0);
+            let estimate = OrderEstimate::for_view(
+                &view,
+                rates.and_then(|r| r.price(&single_order.fiat_code)),
+            );
 
             let r = Row::from(vec![
                 if let Some(k) = single_order.kind {
@@ -252,28 +465,17 @@ pub fn print_orders_table(orders_table: Vec<Event>) -> Result<String> {
                         &status,
                     )
                 },
-                if single_order.amount == 0 {
-                    Cell::new("market").set_alignment(CellAlignment::Center)
-                } else {
-                    Cell::new(single_order.amount.to_string()).set_alignment(CellAlignment::Center)
-                },
+                Cell::new(estimate.sats_text(single_order)).set_alignment(CellAlignment::Center),
                 Cell::new(single_order.fiat_code.to_string()).set_alignment(CellAlignment::Center),
-                // No range order print row
-                if single_order.min_amount.is_none() && single_order.max_amount.is_none() {
-                    Cell::new(single_order.fiat_amount.to_string())
-                        .set_alignment(CellAlignment::Center)
-                } else {
-                    let range_str = match (single_order.min_amount, single_order.max_amount) {
-                        (Some(min), Some(max)) => format!("{}-{}", min, max),
-                        (Some(min), None) => format!("{}-?", min),
-                        (None, Some(max)) => format!("?-{}", max),
-                        (None, None) => "?".to_string(),
-                    };
-                    Cell::new(range_str).set_alignment(CellAlignment::Center)
-                },
+                Cell::new(view.fiat_amount_text()).set_alignment(CellAlignment::Center),
                 Cell::new(single_order.payment_method.to_string())
                     .set_alignment(CellAlignment::Center),
                 Cell::new(single_order.premium.to_string()).set_alignment(CellAlignment::Center),
+                Cell::new(estimate.price_text(&single_order.fiat_code))
+                    .set_alignment(CellAlignment::Center),
+                Cell::new(maker_rating_text(view.maker_rating.as_ref()))
+                    .set_alignment(CellAlignment::Center),
+                Cell::new(expires_text(view.expires_at, now)).set_alignment(CellAlignment::Center),
                 Cell::new(
                     date.map(|d| d.format("%Y-%m-%d %H:%M").to_string())
                         .unwrap_or_else(|| "Invalid date".to_string()),
@@ -294,5 +496,85 @@ pub fn print_orders_table(orders_table: Vec<Event>) -> Result<String> {
     Ok(table.to_string())
 }
 
+/// Field/value view of one public order with every tag of its event, for
+/// `showorder`. `market_price` (fiat per BTC) adds the market estimates.
+pub fn print_order_detail(view: &OrderView, market_price: Option<f64>, now: i64) -> String {
+    let order = &view.order;
+    let estimate = OrderEstimate::for_view(view, market_price);
+    let mut table = create_standard_table();
+    table.set_header(create_field_value_header());
+
+    let text = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+    let date = |ts: Option<i64>| ts.map(format_timestamp).unwrap_or_else(|| "-".to_string());
+    let fiat_amount = format!("{} {}", view.fiat_amount_text(), order.fiat_code);
+
+    let rows = [
+        (
+            "🆔 ",
+            "Order Id",
+            order
+                .id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+        ),
+        (
+            "📈 ",
+            "Kind",
+            order
+                .kind
+                .map(|k| k.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "📊 ",
+            "Status",
+            order
+                .status
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+        ),
+        ("₿ ", "Amount (sats)", estimate.sats_text(order)),
+        ("💵 ", "Fiat Amount", fiat_amount),
+        ("💳 ", "Payment Methods", order.payment_method.clone()),
+        ("📊 ", "Premium", format!("{}%", order.premium)),
+        (
+            "💹 ",
+            "Price vs Market",
+            estimate.price_text(&order.fiat_code),
+        ),
+        ("👤 ", "Maker", text(&view.name)),
+        (
+            "⭐ ",
+            "Maker Rating",
+            maker_rating_long_text(view.maker_rating.as_ref()),
+        ),
+        ("📅 ", "Published", date(order.created_at)),
+        (
+            "⏳ ",
+            "Expires",
+            match view.expires_at {
+                Some(at) => format!("{} ({})", format_timestamp(at), expires_text(Some(at), now)),
+                None => "-".to_string(),
+            },
+        ),
+        ("🗑️ ", "Event Expiration", date(view.expiration)),
+        ("🌐 ", "Network", text(&view.network)),
+        ("⚡ ", "Layer", text(&view.layer)),
+        ("🏛️ ", "Platform", text(&view.platform)),
+        ("🔗 ", "Source", text(&view.source)),
+        ("📍 ", "Geohash", text(&view.geohash)),
+    ];
+    for (emoji, field, value) in rows {
+        table.add_row(create_emoji_field_row(emoji, field, &value));
+    }
+    for (name, value) in &view.bond {
+        table.add_row(create_emoji_field_row("🔒 ", name, value));
+    }
+    for (name, values) in &view.other {
+        table.add_row(create_emoji_field_row("🏷️ ", name, &values.join(", ")));
+    }
+    table.to_string()
+}
+
 #[cfg(test)]
 mod tests {}
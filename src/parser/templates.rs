@@ -0,0 +1,52 @@
+use comfy_table::*;
+
+use crate::db::OrderTemplate;
+use crate::parser::common::{create_bold_header, create_empty_table, create_standard_table};
+
+/// Table of the saved order templates.
+pub fn print_templates_table(templates: &[OrderTemplate]) -> String {
+    if templates.is_empty() {
+        return create_empty_table(
+            "📭 No Templates",
+            "No order templates yet, save one with savetemplate…",
+        )
+        .to_string();
+    }
+
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        "🏷️ Name",
+        "📈 Kind",
+        "💱 Currency",
+        "💰 Fiat Amount",
+        "₿ Sats",
+        "💳 Payment Method",
+        "📊 Premium",
+        "⏳ Expiration",
+        "⚡ Invoice",
+    ]));
+    for template in templates {
+        let sats = match template.amount {
+            0 => "market".to_string(),
+            amount => amount.to_string(),
+        };
+        let expiration = match template.expiration_days {
+            0 => "-".to_string(),
+            days => format!("{} days", days),
+        };
+        table.add_row(Row::from(vec![
+            Cell::new(&template.name).set_alignment(CellAlignment::Center),
+            Cell::new(&template.kind).set_alignment(CellAlignment::Center),
+            Cell::new(&template.fiat_code).set_alignment(CellAlignment::Center),
+            Cell::new(&template.fiat_amount).set_alignment(CellAlignment::Center),
+            Cell::new(sats).set_alignment(CellAlignment::Center),
+            Cell::new(&template.payment_method).set_alignment(CellAlignment::Center),
+            Cell::new(format!("{:+}%", template.premium)).set_alignment(CellAlignment::Center),
+            Cell::new(expiration).set_alignment(CellAlignment::Center),
+            Cell::new(template.invoice.as_deref().unwrap_or("-"))
+                .set_alignment(CellAlignment::Center),
+        ]));
+    }
+
+    table.to_string()
+}
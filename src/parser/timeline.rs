@@ -0,0 +1,42 @@
+use comfy_table::*;
+
+use crate::db::ProtocolMessage;
+use crate::parser::common::{
+    create_bold_header, create_empty_table, create_standard_table, format_timestamp,
+};
+
+/// Table of the protocol messages recorded for one order, oldest first.
+pub fn print_timeline_table(messages: &[ProtocolMessage]) -> String {
+    if messages.is_empty() {
+        return create_empty_table("📭 No Messages", "No messages recorded for this order yet…")
+            .to_string();
+    }
+
+    let mut table = create_standard_table();
+    table.set_header(create_bold_header(&[
+        "🕒 Time",
+        "↕️ Direction",
+        "🎬 Action",
+        "🆔 Request ID",
+        "🔢 Trade Index",
+        "📦 Payload",
+    ]));
+    for message in messages {
+        let direction = match message.direction.as_str() {
+            "sent" => "📤 sent",
+            "received" => "📥 received",
+            other => other,
+        };
+        let optional = |value: Option<i64>| value.map_or("-".to_string(), |v| v.to_string());
+        table.add_row(Row::from(vec![
+            Cell::new(format_timestamp(message.created_at)).set_alignment(CellAlignment::Center),
+            Cell::new(direction).set_alignment(CellAlignment::Center),
+            Cell::new(&message.action).set_alignment(CellAlignment::Center),
+            Cell::new(optional(message.request_id)).set_alignment(CellAlignment::Center),
+            Cell::new(optional(message.trade_index)).set_alignment(CellAlignment::Center),
+            Cell::new(message.payload.as_deref().unwrap_or("-")),
+        ]));
+    }
+
+    table.to_string()
+}
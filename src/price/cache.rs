@@ -0,0 +1,228 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::env::var;
+
+use super::{BtcRates, FiatNames, PriceProvider};
+use crate::db::PriceCacheEntry;
+
+/// Cache lifetime used when `PRICE_CACHE_TTL` is unset or invalid.
+pub const DEFAULT_PRICE_CACHE_TTL_SECS: i64 = 300;
+
+const CURRENCIES: &str = "currencies";
+const BTC_RATES: &str = "btc_rates";
+
+/// Seconds cached prices stay fresh, from `PRICE_CACHE_TTL`.
+pub fn price_cache_ttl() -> i64 {
+    var("PRICE_CACHE_TTL")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|ttl| *ttl >= 0)
+        .unwrap_or(DEFAULT_PRICE_CACHE_TTL_SECS)
+}
+
+/// [`PriceProvider`] that answers from the `price_cache` table while entries
+/// are younger than the TTL and asks `inner` otherwise.
+///
+/// When `inner` fails, the last cached answer is returned however old it is,
+/// so commands keep working offline; only a cache miss is an error.
+pub struct CachedPriceProvider<P> {
+    inner: P,
+    pool: SqlitePool,
+    ttl_secs: i64,
+    refresh: bool,
+}
+
+impl<P: PriceProvider> CachedPriceProvider<P> {
+    pub fn new(inner: P, pool: SqlitePool, ttl_secs: i64) -> Self {
+        Self {
+            inner,
+            pool,
+            ttl_secs,
+            refresh: false,
+        }
+    }
+
+    /// Skip fresh cache entries and always ask the provider first.
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Return the cached `kind` entry if fresh, otherwise fetch it with
+    /// `fetch`, store it and return it. Values travel as JSON text.
+    async fn cached<F, Fut>(&self, kind: &str, fetch: F) -> Result<(String, i64)>
+    where
+        F: FnOnce() -> Fut + Send,
+        Fut: std::future::Future<Output = Result<(String, i64)>> + Send,
+    {
+        let source = self.inner.name();
+        let now = chrono::Utc::now().timestamp();
+        let cached = PriceCacheEntry::get(&self.pool, &source, kind).await?;
+        if let Some(entry) = &cached {
+            if !self.refresh && now - entry.fetched_at < self.ttl_secs {
+                return Ok((entry.data.clone(), entry.fetched_at));
+            }
+        }
+
+        match fetch().await {
+            Ok((data, fetched_at)) => {
+                PriceCacheEntry {
+                    source,
+                    kind: kind.to_string(),
+                    data: data.clone(),
+                    fetched_at,
+                }
+                .upsert(&self.pool)
+                .await?;
+                Ok((data, fetched_at))
+            }
+            Err(e) => match cached {
+                Some(entry) => {
+                    log::warn!(
+                        "Price provider unavailable ({}); using {} cached {} seconds ago",
+                        e,
+                        kind,
+                        now - entry.fetched_at
+                    );
+                    Ok((entry.data, entry.fetched_at))
+                }
+                None => Err(anyhow!("{} (and no cached {} to fall back on)", e, kind)),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<P: PriceProvider> PriceProvider for CachedPriceProvider<P> {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn currencies(&self) -> Result<FiatNames> {
+        let (data, _) = self
+            .cached(CURRENCIES, || async {
+                let names = self.inner.currencies().await?;
+                Ok((
+                    serde_json::to_string(&names)?,
+                    chrono::Utc::now().timestamp(),
+                ))
+            })
+            .await?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    async fn btc_rates(&self) -> Result<BtcRates> {
+        let (data, fetched_at) = self
+            .cached(BTC_RATES, || async {
+                let rates = self.inner.btc_rates().await?;
+                Ok((serde_json::to_string(&rates.rates)?, rates.fetched_at))
+            })
+            .await?;
+        Ok(BtcRates {
+            rates: serde_json::from_str(&data)?,
+            fetched_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Provider answering from memory, failing once `online` is cleared.
+    struct FakeProvider {
+        calls: Arc<AtomicUsize>,
+        online: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl PriceProvider for FakeProvider {
+        fn name(&self) -> String {
+            "fake".to_string()
+        }
+
+        async fn currencies(&self) -> Result<FiatNames> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if !self.online.load(Ordering::SeqCst) {
+                return Err(anyhow!("offline"));
+            }
+            Ok(BTreeMap::from([(
+                "USD".to_string(),
+                "US Dollar".to_string(),
+            )]))
+        }
+
+        async fn btc_rates(&self) -> Result<BtcRates> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if !self.online.load(Ordering::SeqCst) {
+                return Err(anyhow!("offline"));
+            }
+            Ok(BtcRates {
+                rates: BTreeMap::from([("USD".to_string(), 50_000.0)]),
+                fetched_at: chrono::Utc::now().timestamp(),
+            })
+        }
+    }
+
+    async fn setup(
+        ttl_secs: i64,
+    ) -> (
+        CachedPriceProvider<FakeProvider>,
+        Arc<AtomicUsize>,
+        Arc<AtomicBool>,
+    ) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::db::migrate_add_tables(&pool).await.unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let online = Arc::new(AtomicBool::new(true));
+        let provider = FakeProvider {
+            calls: calls.clone(),
+            online: online.clone(),
+        };
+        (
+            CachedPriceProvider::new(provider, pool, ttl_secs),
+            calls,
+            online,
+        )
+    }
+
+    #[tokio::test]
+    async fn fresh_entries_are_served_from_the_cache() {
+        let (cached, calls, _) = setup(300).await;
+        assert_eq!(
+            cached.btc_rates().await.unwrap().price("usd"),
+            Some(50_000.0)
+        );
+        assert_eq!(
+            cached.btc_rates().await.unwrap().price("USD"),
+            Some(50_000.0)
+        );
+        assert!(cached.currencies().await.unwrap().contains_key("USD"));
+        assert!(cached.currencies().await.unwrap().contains_key("USD"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // --refresh always asks the provider
+        let cached = cached.with_refresh(true);
+        cached.btc_rates().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn stale_entries_are_used_when_the_provider_is_down() {
+        let (cached, calls, online) = setup(0).await;
+        assert!(cached.btc_rates().await.is_ok());
+        online.store(false, Ordering::SeqCst);
+        // Expired (TTL 0) and unreachable: the stale copy is returned
+        assert_eq!(
+            cached.btc_rates().await.unwrap().price("USD"),
+            Some(50_000.0)
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        // Nothing cached at all is still an error
+        assert!(cached.currencies().await.is_err());
+    }
+}
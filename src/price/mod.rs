@@ -0,0 +1,176 @@
+//! Fiat price oracle.
+//!
+//! Market-price orders need to know whether a currency has a BTC rate, and
+//! the `price` command shows those rates. Both go through [`PriceProvider`]:
+//! [`YadioProvider`] talks to the Yadio API (or any server speaking the same
+//! protocol at `PRICE_API_URL`), and [`CachedPriceProvider`] keeps the last
+//! answers in SQLite so repeated calls, and machines without access to the
+//! API, still get data.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use mostro_core::prelude::SmallOrder;
+use std::collections::BTreeMap;
+
+use crate::nip33::OrderView;
+
+pub mod cache;
+pub mod yadio;
+
+pub use cache::{price_cache_ttl, CachedPriceProvider};
+pub use yadio::YadioProvider;
+
+/// Fiat currency code → currency name.
+pub type FiatNames = BTreeMap<String, String>;
+
+/// Price of one bitcoin in each fiat currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BtcRates {
+    /// Fiat currency code → fiat units per BTC.
+    pub rates: BTreeMap<String, f64>,
+    /// Unix time the rates were fetched from the provider.
+    pub fetched_at: i64,
+}
+
+impl BtcRates {
+    /// Fiat units per BTC for `fiat_code`, matched case-insensitively.
+    pub fn price(&self, fiat_code: &str) -> Option<f64> {
+        self.rates
+            .get(&fiat_code.to_uppercase())
+            .copied()
+            .filter(|price| *price > 0.0)
+    }
+}
+
+/// Satoshis `fiat_amount` buys at `btc_price` fiat units per BTC.
+pub fn fiat_to_sats(fiat_amount: f64, btc_price: f64) -> i64 {
+    (fiat_amount / btc_price * 100_000_000.0).round() as i64
+}
+
+/// Fiat value of `sats` at `btc_price` fiat units per BTC.
+pub fn sats_to_fiat(sats: i64, btc_price: f64) -> f64 {
+    sats as f64 / 100_000_000.0 * btc_price
+}
+
+/// Sats Mostro pays for `fiat_amount` on a market-price order, following
+/// mostrod's quote: the market amount reduced by `premium` percent.
+pub fn market_sats(fiat_amount: f64, premium: i64, btc_price: f64) -> i64 {
+    let sats = fiat_amount / btc_price * 100_000_000.0;
+    (sats - premium as f64 / 100.0 * sats).round() as i64
+}
+
+/// Fiat units per BTC implied by trading `fiat_amount` for `sats`.
+pub fn implied_btc_price(fiat_amount: f64, sats: i64) -> Option<f64> {
+    (sats > 0 && fiat_amount > 0.0).then(|| fiat_amount / sats as f64 * 100_000_000.0)
+}
+
+/// What an order is worth at the current market price.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OrderEstimate {
+    /// Sats for the (minimum) fiat amount; the order's own amount when fixed.
+    pub min_sats: Option<i64>,
+    /// Sats for the maximum fiat amount of a range order.
+    pub max_sats: Option<i64>,
+    /// Fiat units per BTC the order trades at.
+    pub btc_price: Option<f64>,
+    /// How far that price is from the market, in percent.
+    pub vs_market: Option<f64>,
+}
+
+impl OrderEstimate {
+    /// Estimate `order` at `market_price` fiat units per BTC, if known.
+    ///
+    /// Market-price orders (`amount == 0`) get their sats from the market
+    /// price and premium; fixed-sats orders get the BTC price they imply.
+    pub fn new(order: &SmallOrder, market_price: Option<f64>) -> Self {
+        let (min_fiat, max_fiat) = match (order.min_amount, order.max_amount) {
+            (Some(min), Some(max)) => (min as f64, Some(max as f64)),
+            _ => (order.fiat_amount as f64, None),
+        };
+        Self::for_fiat(order, min_fiat, max_fiat, market_price)
+    }
+
+    /// Like [`OrderEstimate::new`], but from the exact fiat amount of the
+    /// order event rather than the whole units `SmallOrder` keeps.
+    pub fn for_view(view: &OrderView, market_price: Option<f64>) -> Self {
+        match &view.fiat_amount {
+            Some(fiat) => Self::for_fiat(
+                &view.order,
+                fiat.min.to_f64(),
+                fiat.max.map(|max| max.to_f64()),
+                market_price,
+            ),
+            None => Self::new(&view.order, market_price),
+        }
+    }
+
+    fn for_fiat(
+        order: &SmallOrder,
+        min_fiat: f64,
+        max_fiat: Option<f64>,
+        market_price: Option<f64>,
+    ) -> Self {
+        let (min_sats, max_sats) = if order.amount > 0 {
+            (Some(order.amount), None)
+        } else if let Some(price) = market_price {
+            (
+                Some(market_sats(min_fiat, order.premium, price)),
+                max_fiat.map(|max| market_sats(max, order.premium, price)),
+            )
+        } else {
+            (None, None)
+        };
+        let btc_price = min_sats.and_then(|sats| implied_btc_price(min_fiat, sats));
+        let vs_market = btc_price
+            .zip(market_price)
+            .filter(|(_, market)| *market > 0.0)
+            .map(|(price, market)| (price / market - 1.0) * 100.0);
+        Self {
+            min_sats,
+            max_sats,
+            btc_price,
+            vs_market,
+        }
+    }
+
+    /// Sats text for tables: `100000` for fixed amounts, `~95000` or
+    /// `~9500-95000` for estimates, `market` when no price is known.
+    pub fn sats_text(&self, order: &SmallOrder) -> String {
+        if order.amount > 0 {
+            return order.amount.to_string();
+        }
+        match (self.min_sats, self.max_sats) {
+            (Some(min), Some(max)) => format!("~{}-{}", min, max),
+            (Some(min), None) => format!("~{}", min),
+            _ => "market".to_string(),
+        }
+    }
+
+    /// Price text for tables: `67000.00 USD (+1.5%)`, or `-` when unknown.
+    pub fn price_text(&self, fiat_code: &str) -> String {
+        match (self.btc_price, self.vs_market) {
+            (Some(price), Some(diff)) => format!("{:.2} {} ({:+.1}%)", price, fiat_code, diff),
+            (Some(price), None) => format!("{:.2} {}", price, fiat_code),
+            _ => "-".to_string(),
+        }
+    }
+}
+
+/// Source of fiat currencies and BTC exchange rates.
+#[async_trait]
+pub trait PriceProvider: Send + Sync {
+    /// Human-readable source, shown next to prices.
+    fn name(&self) -> String;
+
+    /// Fiat currencies the provider has a market price for.
+    async fn currencies(&self) -> Result<FiatNames>;
+
+    /// Current price of one bitcoin in every supported currency.
+    async fn btc_rates(&self) -> Result<BtcRates>;
+}
+
+/// The provider commands use: Yadio (or `PRICE_API_URL`) behind the SQLite
+/// cache, with the TTL from `PRICE_CACHE_TTL`.
+pub fn default_price_provider(pool: &sqlx::SqlitePool) -> CachedPriceProvider<YadioProvider> {
+    CachedPriceProvider::new(YadioProvider::from_env(), pool.clone(), price_cache_ttl())
+}
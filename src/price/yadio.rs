@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::env::var;
+use std::time::Duration;
+
+use super::{BtcRates, FiatNames, PriceProvider};
+
+/// Public Yadio API, used unless `PRICE_API_URL` points elsewhere.
+pub const YADIO_API_URL: &str = "https://api.yadio.io";
+/// Upper bound for each request to the price API.
+pub const PRICE_API_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// [`PriceProvider`] backed by the Yadio API.
+///
+/// Uses `GET {base}/currencies` for the currency list and
+/// `GET {base}/exrates/BTC` for the rates.
+#[derive(Debug, Clone)]
+pub struct YadioProvider {
+    base_url: String,
+}
+
+impl YadioProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Read the API location from `PRICE_API_URL`, defaulting to Yadio.
+    pub fn from_env() -> Self {
+        match var("PRICE_API_URL") {
+            Ok(url) if !url.trim().is_empty() => Self::new(url.trim()),
+            _ => Self::new(YADIO_API_URL),
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    async fn get_json(&self, path: &str) -> Result<serde_json::Value> {
+        let url = format!("{}{}", self.base_url, path);
+        let client = reqwest::Client::builder()
+            .timeout(PRICE_API_TIMEOUT)
+            .build()?;
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Could not reach the price API at {}: {}", url, e))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("{} returned HTTP {}", url, status));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+/// Parse the `/currencies` body: an object of code → name.
+pub fn parse_currencies(json: serde_json::Value) -> Result<FiatNames> {
+    let names: FiatNames = serde_json::from_value(json)
+        .map_err(|e| anyhow!("Invalid currency list from the price API: {}", e))?;
+    Ok(names
+        .into_iter()
+        .map(|(code, name)| (code.to_uppercase(), name))
+        .collect())
+}
+
+/// Parse the `/exrates/BTC` body: `{"BTC": {"USD": 67000.1, ...}, ...}`.
+/// The `BTC` → `BTC` self rate and non-numeric entries are dropped.
+pub fn parse_btc_rates(json: serde_json::Value, fetched_at: i64) -> Result<BtcRates> {
+    let table = json
+        .get("BTC")
+        .and_then(|t| t.as_object())
+        .ok_or_else(|| anyhow!("Invalid rates from the price API: missing BTC table"))?;
+    let rates: BTreeMap<String, f64> = table
+        .iter()
+        .filter(|(code, _)| code.as_str() != "BTC")
+        .filter_map(|(code, rate)| Some((code.to_uppercase(), rate.as_f64()?)))
+        .collect();
+    if rates.is_empty() {
+        return Err(anyhow!("Invalid rates from the price API: no rates"));
+    }
+    Ok(BtcRates { rates, fetched_at })
+}
+
+#[async_trait]
+impl PriceProvider for YadioProvider {
+    fn name(&self) -> String {
+        if self.base_url == YADIO_API_URL {
+            "Yadio".to_string()
+        } else {
+            format!("Yadio API at {}", self.base_url)
+        }
+    }
+
+    async fn currencies(&self) -> Result<FiatNames> {
+        parse_currencies(self.get_json("/currencies").await?)
+    }
+
+    async fn btc_rates(&self) -> Result<BtcRates> {
+        let json = self.get_json("/exrates/BTC").await?;
+        parse_btc_rates(json, chrono::Utc::now().timestamp())
+    }
+}
@@ -0,0 +1,547 @@
+//! Trade steps for the `trade` wizard.
+//!
+//! A [`TradeStep`] is where a trade stands for one side of it, and decides
+//! which [`TradeChoice`]s the wizard offers. Steps move with the messages
+//! Mostro sends about the order ([`step_after`]); a trade picked up again
+//! starts from the order status ([`step_for_status`]).
+//!
+//! The same rules guard the order commands: [`action_problem`] tells why
+//! Mostro would refuse an action, from the order status and the user's
+//! [`TradeParty`], and [`status_after`] follows the status through Mostro's
+//! messages. [`check_order_action`] applies them to a stored order before
+//! `send_msg`, `add_invoice` or `rate_user` publish anything.
+
+use anyhow::Result;
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::{Keys, PublicKey};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::cli::Context;
+use crate::db::Order;
+use crate::parser::parse_dm_events;
+use crate::util::{create_filter, ListKind, FETCH_EVENTS_TIMEOUT};
+
+/// Side of the user in a trade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeRole {
+    Buyer,
+    Seller,
+}
+
+impl TradeRole {
+    /// Side of the maker (`maker`) or the taker of an order of `kind`.
+    pub fn new(kind: mostro_core::order::Kind, maker: bool) -> Self {
+        match (kind, maker) {
+            (mostro_core::order::Kind::Buy, true) | (mostro_core::order::Kind::Sell, false) => {
+                Self::Buyer
+            }
+            _ => Self::Seller,
+        }
+    }
+}
+
+impl fmt::Display for TradeRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TradeRole::Buyer => write!(f, "buyer"),
+            TradeRole::Seller => write!(f, "seller"),
+        }
+    }
+}
+
+/// Where a trade stands for one side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeStep {
+    /// Our order is in the book, waiting for a taker.
+    WaitingForTaker,
+    /// The buyer has to send a payout invoice.
+    AddInvoice,
+    /// The user has to pay the hold or bond invoice Mostro sent.
+    PayInvoice,
+    /// Waiting for the counterparty's invoice or payment.
+    WaitingForCounterparty,
+    /// The buyer has to send the fiat, then say so.
+    SendFiat,
+    /// The seller waits for the buyer's fiat.
+    WaitingForFiat,
+    /// The buyer sent the fiat; the seller has to release the sats.
+    Release,
+    /// The buyer waits for the seller to release.
+    WaitingForRelease,
+    /// The trade is over and the counterparty can be rated.
+    Rate,
+    /// A solver decides the trade.
+    Dispute,
+    /// Nothing left to do.
+    Done,
+}
+
+/// What the user can do from the wizard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeChoice {
+    AddInvoice,
+    FiatSent,
+    Release,
+    Rate,
+    Dispute,
+    Cancel,
+}
+
+impl TradeChoice {
+    /// Command name, as typed at the prompt.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TradeChoice::AddInvoice => "addinvoice",
+            TradeChoice::FiatSent => "fiatsent",
+            TradeChoice::Release => "release",
+            TradeChoice::Rate => "rate",
+            TradeChoice::Dispute => "dispute",
+            TradeChoice::Cancel => "cancel",
+        }
+    }
+
+    /// The choice `input` picks among `choices`: its name or its 1-based
+    /// position.
+    pub fn pick(input: &str, choices: &[TradeChoice]) -> Option<TradeChoice> {
+        let input = input.trim().to_lowercase();
+        if let Ok(n) = input.parse::<usize>() {
+            return n.checked_sub(1).and_then(|i| choices.get(i)).copied();
+        }
+        choices
+            .iter()
+            .find(|c| c.name() == input.replace(['-', '_'], ""))
+            .copied()
+    }
+}
+
+impl TradeStep {
+    /// What the user can do at this step. Cancel stays available until the
+    /// trade is settled (a cooperative cancel once the sats are locked);
+    /// dispute once the sats are locked.
+    pub fn choices(&self) -> Vec<TradeChoice> {
+        use TradeChoice::*;
+        match self {
+            TradeStep::WaitingForTaker
+            | TradeStep::PayInvoice
+            | TradeStep::WaitingForCounterparty => vec![Cancel],
+            TradeStep::AddInvoice => vec![AddInvoice, Cancel],
+            TradeStep::SendFiat => vec![FiatSent, Dispute, Cancel],
+            TradeStep::WaitingForFiat | TradeStep::WaitingForRelease => vec![Dispute, Cancel],
+            TradeStep::Release => vec![Release, Dispute, Cancel],
+            TradeStep::Rate => vec![Rate],
+            TradeStep::Dispute | TradeStep::Done => vec![],
+        }
+    }
+
+    /// What the user should know or do at this step.
+    pub fn description(&self) -> &'static str {
+        match self {
+            TradeStep::WaitingForTaker => "Your order is in the book, waiting for a taker",
+            TradeStep::AddInvoice => "Send the invoice or Lightning address to be paid at",
+            TradeStep::PayInvoice => {
+                "Pay the invoice shown above from your wallet; the trade goes on once Mostro sees it"
+            }
+            TradeStep::WaitingForCounterparty => "Waiting for your counterparty",
+            TradeStep::SendFiat => "The sats are locked: send the fiat, then confirm with fiatsent",
+            TradeStep::WaitingForFiat => "The sats are locked: waiting for the buyer's fiat",
+            TradeStep::Release => "Check the fiat arrived, then release the sats",
+            TradeStep::WaitingForRelease => "Waiting for the seller to release the sats",
+            TradeStep::Rate => "The trade is complete: rate your counterparty",
+            TradeStep::Dispute => "The trade is in dispute: a solver will contact you",
+            TradeStep::Done => "The trade is over",
+        }
+    }
+}
+
+/// The step `role` is at after Mostro sent `action` about the order;
+/// `None` when the message doesn't move the trade.
+pub fn step_after(role: TradeRole, action: &Action) -> Option<TradeStep> {
+    let buyer = role == TradeRole::Buyer;
+    let step = match action {
+        Action::NewOrder => TradeStep::WaitingForTaker,
+        Action::AddInvoice | Action::PaymentFailed => TradeStep::AddInvoice,
+        Action::PayInvoice | Action::PayBondInvoice => TradeStep::PayInvoice,
+        Action::WaitingSellerToPay
+        | Action::WaitingBuyerInvoice
+        | Action::BuyerInvoiceAccepted
+        | Action::BondInvoiceAccepted => TradeStep::WaitingForCounterparty,
+        Action::HoldInvoicePaymentAccepted | Action::BuyerTookOrder if buyer => TradeStep::SendFiat,
+        Action::HoldInvoicePaymentAccepted | Action::BuyerTookOrder => TradeStep::WaitingForFiat,
+        Action::FiatSentOk if buyer => TradeStep::WaitingForRelease,
+        Action::FiatSentOk => TradeStep::Release,
+        Action::Released
+        | Action::HoldInvoicePaymentSettled
+        | Action::PurchaseCompleted
+        | Action::Rate => TradeStep::Rate,
+        Action::DisputeInitiatedByYou
+        | Action::DisputeInitiatedByPeer
+        | Action::AdminTookDispute => TradeStep::Dispute,
+        Action::RateReceived
+        | Action::Canceled
+        | Action::CooperativeCancelAccepted
+        | Action::HoldInvoicePaymentCanceled
+        | Action::AdminCanceled
+        | Action::AdminSettled => TradeStep::Done,
+        _ => return None,
+    };
+    Some(step)
+}
+
+/// The step `role` is at when the order is in `status`.
+pub fn step_for_status(role: TradeRole, status: Status) -> TradeStep {
+    let buyer = role == TradeRole::Buyer;
+    match status {
+        Status::Pending => TradeStep::WaitingForTaker,
+        Status::WaitingBuyerInvoice if buyer => TradeStep::AddInvoice,
+        Status::WaitingPayment if !buyer => TradeStep::PayInvoice,
+        Status::WaitingBuyerInvoice
+        | Status::WaitingPayment
+        | Status::WaitingTakerBond
+        | Status::WaitingMakerBond
+        | Status::InProgress => TradeStep::WaitingForCounterparty,
+        Status::Active if buyer => TradeStep::SendFiat,
+        Status::Active => TradeStep::WaitingForFiat,
+        Status::FiatSent if buyer => TradeStep::WaitingForRelease,
+        Status::FiatSent => TradeStep::Release,
+        Status::SettledHoldInvoice | Status::Success => TradeStep::Rate,
+        Status::Dispute => TradeStep::Dispute,
+        Status::Canceled
+        | Status::CanceledByAdmin
+        | Status::CooperativelyCanceled
+        | Status::SettledByAdmin
+        | Status::CompletedByAdmin
+        | Status::Expired => TradeStep::Done,
+    }
+}
+
+/// The order a Mostro message carries, if any.
+fn message_order(message: &MessageKind) -> Option<&SmallOrder> {
+    match &message.payload {
+        Some(Payload::Order(order)) | Some(Payload::PaymentRequest(Some(order), _, _)) => {
+            Some(order)
+        }
+        _ => None,
+    }
+}
+
+/// The user's side in a trade from the trade pubkeys some of Mostro's
+/// messages carry in their order payload.
+pub fn role_from_payloads(trade_pubkey: &PublicKey, messages: &[MessageKind]) -> Option<TradeRole> {
+    let ours = trade_pubkey.to_hex();
+    messages.iter().filter_map(message_order).find_map(|order| {
+        if order.buyer_trade_pubkey.as_deref() == Some(ours.as_str()) {
+            Some(TradeRole::Buyer)
+        } else if order.seller_trade_pubkey.as_deref() == Some(ours.as_str()) {
+            Some(TradeRole::Seller)
+        } else {
+            None
+        }
+    })
+}
+
+/// The user's side in a trade of `kind`, from the messages exchanged on
+/// `trade_pubkey` about it, oldest first: the trade pubkeys some payloads
+/// carry, else a `new-order` (we made it) or `take-sell`/`take-buy` (we took
+/// it). Only without those does the local order row's `mine` decide, and
+/// last whether the history starts with anything but a `new-order`.
+pub fn role_from_messages(
+    kind: Option<mostro_core::order::Kind>,
+    mine: Option<bool>,
+    trade_pubkey: &PublicKey,
+    messages: &[MessageKind],
+) -> Option<TradeRole> {
+    if let Some(role) = role_from_payloads(trade_pubkey, messages) {
+        return Some(role);
+    }
+    let kind = kind?;
+    let made = messages.iter().find_map(|message| match message.action {
+        Action::NewOrder => Some(true),
+        Action::TakeSell | Action::TakeBuy => Some(false),
+        _ => None,
+    });
+    if let Some(maker) = made.or(mine) {
+        return Some(TradeRole::new(kind, maker));
+    }
+    (!messages.is_empty()).then(|| TradeRole::new(kind, false))
+}
+
+/// A user's seat in a trade: their side, and whether they made the order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeParty {
+    pub role: TradeRole,
+    pub maker: bool,
+}
+
+impl TradeParty {
+    /// The maker (`maker`) or the taker of an order of `kind`.
+    pub fn new(kind: mostro_core::order::Kind, maker: bool) -> Self {
+        Self {
+            role: TradeRole::new(kind, maker),
+            maker,
+        }
+    }
+
+    /// Whoever is on the `role` side of an order of `kind`.
+    pub fn with_role(kind: mostro_core::order::Kind, role: TradeRole) -> Self {
+        Self {
+            role,
+            maker: TradeRole::new(kind, true) == role,
+        }
+    }
+}
+
+impl fmt::Display for TradeParty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seat = if self.maker { "maker" } else { "taker" };
+        write!(f, "the {} ({})", self.role, seat)
+    }
+}
+
+/// Whether an order in `status` is over for good.
+pub fn is_final(status: Status) -> bool {
+    matches!(
+        status,
+        Status::Success
+            | Status::Canceled
+            | Status::CanceledByAdmin
+            | Status::CooperativelyCanceled
+            | Status::SettledByAdmin
+            | Status::CompletedByAdmin
+            | Status::Expired
+    )
+}
+
+/// The status of an order in `status` once Mostro sent `action` about it.
+/// Final statuses never change, and messages that say nothing about the
+/// status leave it as it is.
+pub fn status_after(status: Status, action: &Action) -> Status {
+    if is_final(status) {
+        return status;
+    }
+    match action {
+        Action::NewOrder => Status::Pending,
+        Action::AddInvoice | Action::WaitingBuyerInvoice => Status::WaitingBuyerInvoice,
+        Action::PayInvoice | Action::WaitingSellerToPay => Status::WaitingPayment,
+        Action::HoldInvoicePaymentAccepted | Action::BuyerTookOrder => Status::Active,
+        Action::FiatSentOk => Status::FiatSent,
+        Action::Released | Action::HoldInvoicePaymentSettled | Action::PaymentFailed => {
+            Status::SettledHoldInvoice
+        }
+        Action::PurchaseCompleted => Status::Success,
+        Action::Canceled | Action::HoldInvoicePaymentCanceled => Status::Canceled,
+        Action::CooperativeCancelAccepted => Status::CooperativelyCanceled,
+        Action::DisputeInitiatedByYou
+        | Action::DisputeInitiatedByPeer
+        | Action::AdminTookDispute => Status::Dispute,
+        Action::AdminCanceled => Status::CanceledByAdmin,
+        Action::AdminSettled => Status::SettledByAdmin,
+        _ => status,
+    }
+}
+
+/// The statuses an order can be in for a trader to send `action`; `None`
+/// for actions that are not part of a trade.
+pub fn action_statuses(action: &Action) -> Option<&'static [Status]> {
+    let statuses: &'static [Status] = match action {
+        Action::AddInvoice => &[Status::WaitingBuyerInvoice, Status::SettledHoldInvoice],
+        Action::FiatSent => &[Status::Active],
+        Action::Release => &[Status::Active, Status::FiatSent, Status::Dispute],
+        Action::Dispute => &[Status::Active, Status::FiatSent],
+        Action::Cancel => &[
+            Status::Pending,
+            Status::WaitingBuyerInvoice,
+            Status::WaitingPayment,
+            Status::WaitingTakerBond,
+            Status::WaitingMakerBond,
+            Status::InProgress,
+            Status::Active,
+            Status::FiatSent,
+        ],
+        Action::RateUser => &[Status::SettledHoldInvoice, Status::Success],
+        _ => return None,
+    };
+    Some(statuses)
+}
+
+/// The command that sends `action`.
+fn command_name(action: &Action) -> String {
+    match action {
+        Action::AddInvoice => "addinvoice".to_string(),
+        Action::FiatSent => "fiatsent".to_string(),
+        Action::Release => "release".to_string(),
+        Action::Dispute => "dispute".to_string(),
+        Action::Cancel => "cancel".to_string(),
+        Action::RateUser => "rate".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Why an action would be refused by Mostro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActionProblem {
+    /// The order is not in a status the action applies to.
+    Status { action: Action, status: Status },
+    /// The action is for another party of the trade.
+    Party {
+        action: Action,
+        party: TradeParty,
+        allowed: &'static str,
+    },
+}
+
+impl fmt::Display for ActionProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionProblem::Status { action, status } => {
+                let expected: Vec<String> = action_statuses(action)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                write!(
+                    f,
+                    "{} needs an order that is {}, but this order is {}",
+                    command_name(action),
+                    expected.join(", "),
+                    status
+                )
+            }
+            ActionProblem::Party {
+                action,
+                party,
+                allowed,
+            } => write!(
+                f,
+                "only {} can send {} on this order, and you are {}",
+                allowed,
+                command_name(action),
+                party
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ActionProblem {}
+
+/// Why `party` can't send `action` on an order in `status`, if it can't.
+/// Without a known party only the status is checked.
+pub fn action_problem(
+    action: &Action,
+    status: Status,
+    party: Option<TradeParty>,
+) -> Option<ActionProblem> {
+    let statuses = action_statuses(action)?;
+    if !statuses.contains(&status) {
+        return Some(ActionProblem::Status {
+            action: action.clone(),
+            status,
+        });
+    }
+    let party = party?;
+    let allowed = match action {
+        Action::AddInvoice | Action::FiatSent if party.role != TradeRole::Buyer => "the buyer",
+        Action::Release if party.role != TradeRole::Seller => "the seller",
+        Action::Cancel if status == Status::Pending && !party.maker => "the maker",
+        _ => return None,
+    };
+    Some(ActionProblem::Party {
+        action: action.clone(),
+        party,
+        allowed,
+    })
+}
+
+/// Mostro's messages about `order_id` to `keys`, oldest first: those of the
+/// last `since` minutes, or of the last days.
+pub async fn order_messages(
+    order_id: &Uuid,
+    keys: &Keys,
+    since: Option<&i64>,
+    ctx: &Context,
+) -> Result<Vec<(Message, u64, PublicKey)>> {
+    let filter = create_filter(
+        ListKind::DirectMessagesUser,
+        keys.public_key(),
+        None,
+        ctx.mostro_pubkey,
+    )?;
+    let events = ctx
+        .client
+        .fetch_events(filter, FETCH_EVENTS_TIMEOUT)
+        .await?;
+    let mut messages: Vec<_> = parse_dm_events(events, keys, since, true, Some(&ctx.pool))
+        .await
+        .into_iter()
+        .filter(|(message, _, sender)| {
+            *sender == ctx.mostro_pubkey && message.get_inner_message_kind().id == Some(*order_id)
+        })
+        .collect();
+    messages.sort_by_key(|(_, created_at, _)| *created_at);
+    Ok(messages)
+}
+
+/// The user's seat in a stored order, from its kind and whether we made it.
+fn order_party(order: &Order) -> Option<TradeParty> {
+    let kind = mostro_core::order::Kind::from_str(order.kind.as_deref()?).ok()?;
+    Some(TradeParty::new(kind, order.is_mine?))
+}
+
+/// Check `action` can be sent on the stored `order` before publishing it.
+///
+/// The stored status only follows the replies this client saw, so a problem
+/// found with it is checked again against Mostro's recent messages about the
+/// order: a problem they confirm blocks the action, one they can't confirm
+/// is printed as a warning. A final status always blocks.
+pub async fn check_order_action(order: &Order, action: &Action, ctx: &Context) -> Result<()> {
+    let Some(status) = order.status() else {
+        return Ok(());
+    };
+    let party = order_party(order);
+    let Some(problem) = action_problem(action, status, party) else {
+        return Ok(());
+    };
+    if is_final(status) {
+        return Err(problem.into());
+    }
+
+    let warn = |problem: ActionProblem| {
+        println!(
+            "⚠️  {}; sending anyway, as Mostro's messages don't confirm it",
+            problem
+        );
+        Ok(())
+    };
+    let order_id = order.id.as_deref().and_then(|id| Uuid::parse_str(id).ok());
+    let keys = order
+        .trade_keys
+        .as_deref()
+        .and_then(|k| Keys::parse(k).ok());
+    let (Some(order_id), Some(keys)) = (order_id, keys) else {
+        return warn(problem);
+    };
+    let history = match order_messages(&order_id, &keys, None, ctx).await {
+        Ok(history) if !history.is_empty() => history,
+        _ => return warn(problem),
+    };
+    let kinds: Vec<MessageKind> = history
+        .iter()
+        .map(|(message, _, _)| message.get_inner_message_kind().clone())
+        .collect();
+    let status = kinds.iter().fold(status, |status, message| {
+        status_after(status, &message.action)
+    });
+    let kind = order
+        .kind
+        .as_deref()
+        .and_then(|k| mostro_core::order::Kind::from_str(k).ok());
+    let confirmed_party = role_from_payloads(&keys.public_key(), &kinds)
+        .zip(kind)
+        .map(|(role, kind)| TradeParty::with_role(kind, role));
+    match action_problem(action, status, confirmed_party.or(party)) {
+        None => Ok(()),
+        Some(problem @ ActionProblem::Party { .. }) if confirmed_party.is_none() => warn(problem),
+        Some(problem) => Err(problem.into()),
+    }
+}
@@ -0,0 +1,81 @@
+//! Live Mostro messages to one trade key.
+//!
+//! The long-running counterpart of [`wait_for_dm`](super::wait_for_dm): it
+//! follows everything Mostro sends about a trade, replies and unsolicited
+//! updates alike, until it is closed.
+
+use anyhow::Result;
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::cli::Context;
+use crate::parser::parse_dm_events;
+use crate::util::messaging::parse_transport_env;
+
+/// An open subscription to Mostro's messages for one trade key.
+pub struct DmStream<'a> {
+    ctx: &'a Context,
+    keys: Keys,
+    kind: nostr_sdk::Kind,
+    notifications: Receiver<RelayPoolNotification>,
+    subscription: SubscriptionId,
+}
+
+impl<'a> DmStream<'a> {
+    /// Subscribe to the messages Mostro sends to `keys` from now on.
+    pub async fn start(ctx: &'a Context, keys: &Keys) -> Result<Self> {
+        // Same filter as `wait_for_dm`: the transport's event kind, and on
+        // v2 only events authored by Mostro (kind 14 is shared with peer chat)
+        let transport = parse_transport_env()?;
+        let kind = transport.event_kind();
+        let mut filter = Filter::new().pubkey(keys.public_key()).kind(kind).limit(0);
+        if transport == Transport::Nip44Direct {
+            filter = filter.author(ctx.mostro_pubkey);
+        }
+        // Listen before subscribing, so no event slips in between
+        let notifications = ctx.client.notifications();
+        let subscription = ctx.client.subscribe(filter, None).await?.val;
+        Ok(Self {
+            ctx,
+            keys: keys.clone(),
+            kind,
+            notifications,
+            subscription,
+        })
+    }
+
+    /// Wait for the next message from Mostro, with its timestamp.
+    pub async fn next(&mut self) -> Result<(Message, u64)> {
+        let trade_pubkey = self.keys.public_key();
+        loop {
+            let event = match self.notifications.recv().await {
+                Ok(RelayPoolNotification::Event { event, .. }) => event,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    log::warn!("Skipped {} relay notifications", skipped);
+                    continue;
+                }
+                Err(e) => return Err(anyhow::anyhow!("Relay notifications closed: {}", e)),
+            };
+            if event.kind != self.kind || !event.tags.public_keys().any(|pk| *pk == trade_pubkey) {
+                continue;
+            }
+            let mut events = Events::default();
+            events.insert(*event);
+            let messages =
+                parse_dm_events(events, &self.keys, None, true, Some(&self.ctx.pool)).await;
+            if let Some((message, created_at, _)) = messages
+                .into_iter()
+                .find(|(_, _, sender)| *sender == self.ctx.mostro_pubkey)
+            {
+                return Ok((message, created_at));
+            }
+        }
+    }
+
+    /// Close the subscription on every relay.
+    pub async fn close(self) {
+        self.ctx.client.unsubscribe(&self.subscription).await;
+    }
+}
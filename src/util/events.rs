@@ -3,7 +3,10 @@ use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
 
 use crate::db::User;
+use crate::nip33::OrderView;
+use crate::parser::orders::parse_order_views;
 use crate::parser::{parse_dispute_events, parse_dm_events, parse_orders_events};
+use crate::util::history::{fetch_paginated, HistoryWindow, HISTORY_PAGE_SIZE};
 use crate::util::messaging::get_admin_keys;
 
 pub const FETCH_EVENTS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
@@ -19,23 +22,24 @@ fn create_fake_timestamp() -> Result<Timestamp> {
     Ok(Timestamp::from(fake_since_time))
 }
 
-fn create_seven_days_filter(
+/// Filter for the `z`-tagged Mostro events of `window`, one page at a time.
+fn create_window_filter(
     letter: Alphabet,
     value: String,
     pubkey: PublicKey,
     event_kind: u16,
-) -> Result<Filter> {
-    let since_time = chrono::Utc::now()
-        .checked_sub_signed(chrono::Duration::days(7))
-        .ok_or(anyhow::anyhow!("Failed to get since days ago"))?
-        .timestamp() as u64;
-    let timestamp = Timestamp::from(since_time);
-    Ok(Filter::new()
+    window: &HistoryWindow,
+) -> Filter {
+    let mut filter = Filter::new()
         .author(pubkey)
-        .limit(50)
-        .since(timestamp)
+        .limit(HISTORY_PAGE_SIZE)
+        .since(Timestamp::from(window.since.max(0) as u64))
         .custom_tag(SingleLetterTag::lowercase(letter), value)
-        .kind(nostr_sdk::Kind::Custom(event_kind)))
+        .kind(nostr_sdk::Kind::Custom(event_kind));
+    if let Some(until) = window.until {
+        filter = filter.until(Timestamp::from(until.max(0) as u64));
+    }
+    filter
 }
 
 pub fn create_filter(
@@ -45,18 +49,20 @@ pub fn create_filter(
     mostro_pubkey: PublicKey,
 ) -> Result<Filter> {
     match list_kind {
-        ListKind::Orders => create_seven_days_filter(
+        ListKind::Orders => Ok(create_window_filter(
             Alphabet::Z,
             "order".to_string(),
             pubkey,
             NOSTR_ORDER_EVENT_KIND,
-        ),
-        ListKind::Disputes => create_seven_days_filter(
+            &HistoryWindow::default_at(chrono::Utc::now().timestamp()),
+        )),
+        ListKind::Disputes => Ok(create_window_filter(
             Alphabet::Z,
             "dispute".to_string(),
             pubkey,
             NOSTR_DISPUTE_EVENT_KIND,
-        ),
+            &HistoryWindow::default_at(chrono::Utc::now().timestamp()),
+        )),
         ListKind::DirectMessagesAdmin | ListKind::DirectMessagesUser => {
             let fake_timestamp = create_fake_timestamp()?;
             // Mostro→user/admin DMs travel on the node's transport: gift wrap
@@ -94,11 +100,6 @@ pub fn create_filter(
     }
 }
 
-/// Fetch the Mostro instance's kind-38385 info event and read a tag whose
-/// first slot equals `tag_name`. Returns the second slot as a string.
-///
-/// Best-effort: any relay error or missing tag degrades to `None` so callers
-/// keep working against older daemons that don't publish the tag.
 /// Pure: read the second slot of the first tag whose first slot equals
 /// `tag_name`. Split out so the kind-38385 tag-parsing logic stays unit
 /// testable without spinning up a relay.
@@ -113,26 +114,27 @@ fn read_info_tag_from_event(event: &nostr_sdk::Event, tag_name: &str) -> Option<
     })
 }
 
-async fn fetch_info_tag(ctx: &crate::cli::Context, tag_name: &str) -> Option<String> {
+/// Fetch the newest revision of the Mostro instance's kind-38385 info event.
+///
+/// kind-38385 is replaceable, but pick the newest revision by `created_at`
+/// explicitly: a lagging relay (or several relays at once) can still surface
+/// an older copy. Best-effort: any relay error or a node that publishes no
+/// info event degrades to `None` so callers keep working against older
+/// daemons.
+pub async fn fetch_info_event_with(
+    client: &Client,
+    mostro_pubkey: PublicKey,
+    timeout: std::time::Duration,
+) -> Option<nostr_sdk::Event> {
     let filter = Filter::new()
-        .author(ctx.mostro_pubkey)
+        .author(mostro_pubkey)
         .kind(nostr_sdk::Kind::Custom(NOSTR_INFO_EVENT_KIND));
-
-    let events = ctx
-        .client
-        .fetch_events(filter, FETCH_EVENTS_TIMEOUT)
-        .await
-        .ok()?;
-
-    // kind-38385 is replaceable, but pick the newest revision by `created_at`
-    // explicitly: a lagging relay (or several relays at once) can still surface
-    // an older copy.
-    let event = events.iter().max_by_key(|e| e.created_at)?;
-    read_info_tag_from_event(event, tag_name)
+    let events = client.fetch_events(filter, timeout).await.ok()?;
+    events.into_iter().max_by_key(|e| e.created_at)
 }
 
-/// Fetch the Mostro instance's kind-38385 info event and read the
-/// `bond_payout_claim_window_days` tag.
+/// Read the node's `bond_payout_claim_window_days` from its kind-38385 info
+/// event (see [`crate::cli::Context::node_info`]).
 ///
 /// Returns `None` when the node publishes no info event, the tag is absent
 /// (older daemon or bonds disabled), or the value can't be parsed. Used to
@@ -140,11 +142,9 @@ async fn fetch_info_tag(ctx: &crate::cli::Context, tag_name: &str) -> Option<Str
 /// the protocol's "Bond payout invoice" / "Other events" docs. Best-effort:
 /// any relay error degrades to `None` rather than failing the caller.
 pub async fn fetch_bond_claim_window_days(ctx: &crate::cli::Context) -> Option<i64> {
-    // A stale claim window would render the wrong, very user-facing forfeit
-    // deadline — same newest-revision caveat as the rest of the info event.
-    fetch_info_tag(ctx, "bond_payout_claim_window_days")
+    ctx.node_info()
         .await
-        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|info| info.bond_payout_claim_window_days)
 }
 
 /// Fetch the Mostro instance's required NIP-13 proof-of-work difficulty from
@@ -164,15 +164,8 @@ pub async fn fetch_required_pow(ctx: &crate::cli::Context) -> Option<u8> {
 /// answer is already in hand by the time the wait times out (zero added
 /// latency in the timeout path, instead of a sequential second fetch).
 pub async fn fetch_required_pow_with(client: Client, mostro_pubkey: PublicKey) -> Option<u8> {
-    let filter = Filter::new()
-        .author(mostro_pubkey)
-        .kind(nostr_sdk::Kind::Custom(NOSTR_INFO_EVENT_KIND));
-    let events = client
-        .fetch_events(filter, FETCH_EVENTS_TIMEOUT)
-        .await
-        .ok()?;
-    let event = events.iter().max_by_key(|e| e.created_at)?;
-    read_info_tag_from_event(event, "pow").and_then(|v| v.parse::<u8>().ok())
+    let event = fetch_info_event_with(&client, mostro_pubkey, FETCH_EVENTS_TIMEOUT).await?;
+    read_info_tag_from_event(&event, "pow").and_then(|v| v.parse::<u8>().ok())
 }
 
 /// Timeout for the startup transport-capability probe. Deliberately short: it
@@ -191,12 +184,87 @@ pub const INFO_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_se
 /// caller as "assume v1/gift-wrap". Used by the CLI's startup auto-detection
 /// when the operator didn't pick a transport explicitly.
 pub async fn fetch_protocol_version_with(client: Client, mostro_pubkey: PublicKey) -> Option<u8> {
+    let event = fetch_info_event_with(&client, mostro_pubkey, INFO_PROBE_TIMEOUT).await?;
+    read_info_tag_from_event(&event, "protocol_version").and_then(|v| v.trim().parse::<u8>().ok())
+}
+
+/// Fetch the latest kind-38383 event Mostro published for `order_id` and
+/// parse it into a [`SmallOrder`].
+///
+/// Returns `Ok(None)` when no relay knows the order (already taken long ago,
+/// expired, or a typo in the id).
+pub async fn fetch_order(
+    ctx: &crate::cli::Context,
+    order_id: &uuid::Uuid,
+) -> Result<Option<SmallOrder>> {
+    Ok(fetch_order_view(ctx, order_id)
+        .await?
+        .map(|view| view.order))
+}
+
+/// Like [`fetch_order`], keeping every tag of the event in an [`OrderView`].
+pub async fn fetch_order_view(
+    ctx: &crate::cli::Context,
+    order_id: &uuid::Uuid,
+) -> Result<Option<OrderView>> {
     let filter = Filter::new()
-        .author(mostro_pubkey)
-        .kind(nostr_sdk::Kind::Custom(NOSTR_INFO_EVENT_KIND));
-    let events = client.fetch_events(filter, INFO_PROBE_TIMEOUT).await.ok()?;
-    let event = events.iter().max_by_key(|e| e.created_at)?;
-    read_info_tag_from_event(event, "protocol_version").and_then(|v| v.trim().parse::<u8>().ok())
+        .author(ctx.mostro_pubkey)
+        .kind(nostr_sdk::Kind::Custom(NOSTR_ORDER_EVENT_KIND))
+        .identifier(order_id.to_string());
+    let events = ctx
+        .client
+        .fetch_events(filter, FETCH_EVENTS_TIMEOUT)
+        .await?;
+    Ok(parse_order_views(events, None, None, None)
+        .into_iter()
+        .next())
+}
+
+/// Fetch the raw kind-38383 order events the node published in `window`,
+/// following relay pagination, for callers that need more than the parsed
+/// [`SmallOrder`]s (e.g. maker ratings).
+pub async fn fetch_order_events(
+    ctx: &crate::cli::Context,
+    window: &HistoryWindow,
+) -> Result<Events> {
+    let filter = create_window_filter(
+        Alphabet::Z,
+        "order".to_string(),
+        ctx.mostro_pubkey,
+        NOSTR_ORDER_EVENT_KIND,
+        window,
+    );
+    fetch_paginated(&ctx.client, filter, window).await
+}
+
+/// Subscribe to the order events the node publishes from `since` on. They
+/// arrive on `ctx.client.notifications()`; unsubscribe with the returned id.
+pub async fn subscribe_order_events(
+    ctx: &crate::cli::Context,
+    since: i64,
+) -> Result<SubscriptionId> {
+    let filter = Filter::new()
+        .author(ctx.mostro_pubkey)
+        .kind(nostr_sdk::Kind::Custom(NOSTR_ORDER_EVENT_KIND))
+        .custom_tag(SingleLetterTag::lowercase(Alphabet::Z), "order")
+        .since(Timestamp::from(since.max(0) as u64));
+    Ok(ctx.client.subscribe(filter, None).await?.val)
+}
+
+/// Fetch the raw kind-38386 dispute events the node published in `window`,
+/// following relay pagination.
+pub async fn fetch_dispute_events(
+    ctx: &crate::cli::Context,
+    window: &HistoryWindow,
+) -> Result<Events> {
+    let filter = create_window_filter(
+        Alphabet::Z,
+        "dispute".to_string(),
+        ctx.mostro_pubkey,
+        NOSTR_DISPUTE_EVENT_KIND,
+        window,
+    );
+    fetch_paginated(&ctx.client, filter, window).await
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -210,11 +278,8 @@ pub async fn fetch_events_list(
 ) -> Result<Vec<Event>> {
     match list_kind {
         ListKind::Orders => {
-            let filters = create_filter(list_kind, ctx.mostro_pubkey, None, ctx.mostro_pubkey)?;
-            let fetched_events = ctx
-                .client
-                .fetch_events(filters, FETCH_EVENTS_TIMEOUT)
-                .await?;
+            let window = HistoryWindow::default_at(chrono::Utc::now().timestamp());
+            let fetched_events = fetch_order_events(ctx, &window).await?;
             let orders = parse_orders_events(fetched_events, currency, status, kind);
             Ok(orders.into_iter().map(Event::SmallOrder).collect())
         }
@@ -229,7 +294,7 @@ pub async fn fetch_events_list(
                 .fetch_events(filters, FETCH_EVENTS_TIMEOUT)
                 .await?;
             let direct_messages_mostro =
-                parse_dm_events(fetched_events, admin_keys, since, true).await;
+                parse_dm_events(fetched_events, admin_keys, since, true, Some(&ctx.pool)).await;
             Ok(direct_messages_mostro
                 .into_iter()
                 .map(|(message, timestamp, sender_pubkey)| {
@@ -254,7 +319,7 @@ pub async fn fetch_events_list(
                 // NIP-17 peer-to-peer chat (not Mostro-protocol): decode with
                 // the trade↔peer conversation key.
                 let direct_messages_for_trade_key =
-                    parse_dm_events(fetched_user_messages, &trade_key, since, false).await;
+                    parse_dm_events(fetched_user_messages, &trade_key, since, false, None).await;
                 // Extend the direct messages
                 direct_messages.extend(direct_messages_for_trade_key);
             }
@@ -281,8 +346,14 @@ pub async fn fetch_events_list(
                 // selected the right kind per transport — gift wrap (v1) or
                 // kind-14 NIP-44 direct (v2) — and `unwrap_incoming` decodes
                 // whichever arrived.
-                let direct_messages_for_trade_key =
-                    parse_dm_events(fetched_user_messages, &trade_key, since, true).await;
+                let direct_messages_for_trade_key = parse_dm_events(
+                    fetched_user_messages,
+                    &trade_key,
+                    since,
+                    true,
+                    Some(&ctx.pool),
+                )
+                .await;
                 // Extend the direct messages
                 direct_messages.extend(direct_messages_for_trade_key);
             }
@@ -292,11 +363,8 @@ pub async fn fetch_events_list(
                 .collect())
         }
         ListKind::Disputes => {
-            let filters = create_filter(list_kind, ctx.mostro_pubkey, None, ctx.mostro_pubkey)?;
-            let fetched_events = ctx
-                .client
-                .fetch_events(filters, FETCH_EVENTS_TIMEOUT)
-                .await?;
+            let window = HistoryWindow::default_at(chrono::Utc::now().timestamp());
+            let fetched_events = fetch_dispute_events(ctx, &window).await?;
             let disputes = parse_dispute_events(fetched_events);
             Ok(disputes.into_iter().map(Event::Dispute).collect())
         }
@@ -306,6 +374,8 @@ pub async fn fetch_events_list(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lightning::InvoiceRequirements;
+    use crate::util::NodeInfo;
 
     async fn make_info_event(keys: &Keys, tags: Vec<Tag>) -> nostr_sdk::Event {
         EventBuilder::new(nostr_sdk::Kind::Custom(NOSTR_INFO_EVENT_KIND), "")
@@ -366,6 +436,69 @@ mod tests {
         assert_eq!(read_info_tag_from_event(&bare, "protocol_version"), None);
     }
 
+    #[tokio::test]
+    async fn node_info_reads_every_published_tag() {
+        let keys = Keys::generate();
+        let tag = |name: &str, value: &str| Tag::parse([name, value]).unwrap();
+        let event = make_info_event(
+            &keys,
+            vec![
+                tag("mostro_version", "0.15.2"),
+                tag("protocol_version", "2"),
+                tag("fee", "0.006"),
+                pow_tag("12"),
+                tag("min_order_amount", "100"),
+                tag("max_order_amount", "1000000"),
+                tag("expiration_hours", "24"),
+                tag("invoice_expiration_window", "3600"),
+                tag("fiat_currencies_accepted", "USD, EUR"),
+                tag("lnd_networks", "mainnet"),
+                tag("bond_payout_claim_window_days", "7"),
+                tag("bond_enabled", "true"),
+                tag("future_setting", "42"),
+                tag("y", "mostro"),
+                tag("z", "info"),
+            ],
+        )
+        .await;
+        let info = NodeInfo::from_event(&event);
+        assert_eq!(info.mostro_version.as_deref(), Some("0.15.2"));
+        assert_eq!(info.protocol_version, Some(2));
+        assert_eq!(info.fee, Some(0.006));
+        assert_eq!(info.pow, Some(12));
+        assert_eq!(info.max_order_amount, Some(1_000_000));
+        assert_eq!(info.expiration_hours, Some(24));
+        assert_eq!(info.fiat_currencies_accepted, vec!["USD", "EUR"]);
+        assert!(info.accepts_currency("eur"));
+        assert!(!info.accepts_currency("ARS"));
+        assert_eq!(info.bond_payout_claim_window_days, Some(7));
+        assert_eq!(info.bond_settings.len(), 2);
+        assert_eq!(
+            info.other.get("future_setting").map(String::as_str),
+            Some("42")
+        );
+        assert!(!info.other.contains_key("y"));
+
+        let requirements = info.invoice_requirements();
+        assert_eq!(requirements.amount_sats, None);
+        assert_eq!(requirements.min_amount_sats, Some(100));
+        assert_eq!(requirements.min_expiry_secs, Some(3600));
+        assert_eq!(
+            requirements.networks,
+            vec![lightning_invoice::Currency::Bitcoin]
+        );
+        let bond_payout = info.bond_payout_requirements();
+        assert_eq!(bond_payout.min_amount_sats, None);
+        assert_eq!(bond_payout.min_expiry_secs, Some(3600));
+        assert_eq!(bond_payout.networks, requirements.networks);
+
+        // An older daemon publishing none of these leaves every check off.
+        let bare = NodeInfo::from_event(&make_info_event(&keys, vec![pow_tag("0")]).await);
+        assert_eq!(bare.invoice_requirements(), InvoiceRequirements::default());
+        assert_eq!(bare.fee, None);
+        assert!(bare.accepts_currency("USD"));
+    }
+
     #[tokio::test]
     async fn pow_tag_parses_as_u8() {
         // u8 parse is what fetch_required_pow chains after the helper.
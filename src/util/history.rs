@@ -0,0 +1,205 @@
+//! Time windows and pagination for relay history queries.
+//!
+//! Relays cap how many events one query returns, so listing a long window
+//! means asking again below the oldest event received until the window's
+//! start is reached.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use nostr_sdk::prelude::*;
+use std::collections::HashSet;
+use std::future::Future;
+
+use crate::util::FETCH_EVENTS_TIMEOUT;
+
+/// Window used when no `--since` is given.
+pub const DEFAULT_HISTORY_DAYS: i64 = 7;
+/// Events asked for per relay query.
+pub const HISTORY_PAGE_SIZE: usize = 50;
+/// Upper bound on queries per listing, against relays that ignore `until`.
+pub const MAX_HISTORY_PAGES: usize = 200;
+
+/// `[since, until]` range of event `created_at`, in unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryWindow {
+    pub since: i64,
+    /// `None` means up to now.
+    pub until: Option<i64>,
+}
+
+impl HistoryWindow {
+    /// The last [`DEFAULT_HISTORY_DAYS`] days before `now`.
+    pub fn default_at(now: i64) -> Self {
+        Self {
+            since: now - DEFAULT_HISTORY_DAYS * 86_400,
+            until: None,
+        }
+    }
+
+    /// Window from optional `--since` / `--until` values (see
+    /// [`parse_time_bound`]); a missing `since` falls back to the default.
+    pub fn parse(since: Option<&str>, until: Option<&str>, now: i64) -> Result<Self> {
+        let since = match since {
+            Some(value) => parse_time_bound(value, now)?,
+            None => Self::default_at(now).since,
+        };
+        let until = until
+            .map(|value| parse_time_bound(value, now))
+            .transpose()?;
+        if let Some(until) = until {
+            if until <= since {
+                return Err(anyhow!("--until must be later than --since"));
+            }
+        }
+        Ok(Self { since, until })
+    }
+
+    /// Human-readable range, e.g. `2024-05-01 00:00 UTC → now`.
+    pub fn describe(&self) -> String {
+        let date = |ts: i64| {
+            DateTime::from_timestamp(ts, 0)
+                .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| ts.to_string())
+        };
+        format!(
+            "{} → {}",
+            date(self.since),
+            self.until.map(date).unwrap_or_else(|| "now".to_string())
+        )
+    }
+}
+
+/// Parse a point in time relative to `now`:
+/// - a duration back from now: `90s`, `30m`, `2h`, `7d`, `2w`;
+/// - a UTC date or date-time: `2024-05-01`, `2024-05-01 14:30`,
+///   `2024-05-01T14:30:00`, or RFC 3339 with an offset;
+/// - a unix timestamp prefixed with `@`: `@1714521600`.
+pub fn parse_time_bound(value: &str, now: i64) -> Result<i64> {
+    let value = value.trim();
+    if let Some(ts) = value.strip_prefix('@') {
+        return ts
+            .parse::<i64>()
+            .map_err(|_| anyhow!("Invalid unix timestamp '{}'", value));
+    }
+    if let Some(secs) = parse_duration_secs(value) {
+        return Ok(now - secs);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.timestamp());
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(Utc.from_utc_datetime(&dt).timestamp());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Utc
+            .from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .timestamp());
+    }
+    Err(anyhow!(
+        "Invalid time '{}': use a duration like 30m, 2h or 30d, a date like 2024-05-01, or @<unix timestamp>",
+        value
+    ))
+}
+
+/// Seconds in `<number><s|m|h|d|w>`.
+fn parse_duration_secs(value: &str) -> Option<i64> {
+    let unit = value.chars().last()?;
+    let multiplier = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 604_800,
+        _ => return None,
+    };
+    let number = value[..value.len() - 1].parse::<i64>().ok()?;
+    (number >= 0).then(|| number.saturating_mul(multiplier))
+}
+
+/// Collect every event of `window` by asking `fetch_page(until)` for pages
+/// of up to `page_size` events, newest first, each one ending at the oldest
+/// event of the previous page. Stops at a short page, at the window start or
+/// after [`MAX_HISTORY_PAGES`].
+pub async fn paginate<F, Fut>(
+    window: &HistoryWindow,
+    page_size: usize,
+    mut fetch_page: F,
+) -> Result<Vec<Event>>
+where
+    F: FnMut(Option<i64>) -> Fut,
+    Fut: Future<Output = Result<Vec<Event>>>,
+{
+    let mut cursor = window.until;
+    let mut seen = HashSet::new();
+    let mut events = Vec::new();
+    for _ in 0..MAX_HISTORY_PAGES {
+        let page = fetch_page(cursor).await?;
+        let full = page.len() >= page_size;
+        let oldest = page.iter().map(|e| e.created_at.as_secs() as i64).min();
+        let before = events.len();
+        for event in page {
+            if seen.insert(event.id) {
+                events.push(event);
+            }
+        }
+        let Some(oldest) = oldest else {
+            return Ok(events);
+        };
+        if !full {
+            return Ok(events);
+        }
+        // `until` is inclusive: the next page repeats the oldest second.
+        // When that second alone fills a page, step past it; a page with
+        // nothing new from before the cursor means the relay ignores `until`.
+        cursor = Some(if events.len() > before {
+            oldest
+        } else if cursor.is_none_or(|c| oldest <= c) {
+            oldest - 1
+        } else {
+            log::warn!("Relay ignores `until`; history may be incomplete");
+            return Ok(events);
+        });
+        if oldest <= window.since {
+            return Ok(events);
+        }
+    }
+    log::warn!(
+        "Stopped after {} pages of history; narrow the window with --since/--until",
+        MAX_HISTORY_PAGES
+    );
+    Ok(events)
+}
+
+/// Fetch every event matching `filter` inside `window`, page by page.
+pub async fn fetch_paginated(
+    client: &Client,
+    filter: Filter,
+    window: &HistoryWindow,
+) -> Result<Events> {
+    let base = filter.since(Timestamp::from(window.since.max(0) as u64));
+    let events = paginate(window, HISTORY_PAGE_SIZE, |until| {
+        let mut page_filter = base.clone().limit(HISTORY_PAGE_SIZE);
+        if let Some(until) = until {
+            page_filter = page_filter.until(Timestamp::from(until.max(0) as u64));
+        }
+        async move {
+            Ok(client
+                .fetch_events(page_filter, FETCH_EVENTS_TIMEOUT)
+                .await?
+                .into_iter()
+                .collect())
+        }
+    })
+    .await?;
+    // Unbounded: `Events::new(&filter)` would cap the merge at the page size
+    let mut all = Events::default();
+    all.extend(events);
+    Ok(all)
+}
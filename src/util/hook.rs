@@ -0,0 +1,39 @@
+//! User-supplied shell commands run on the user's behalf: alert hooks and
+//! the `autotake` payment command.
+
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Run `command` with `sh -c`, with `env` added to its environment and
+/// `input` on stdin. Fails if it can't start, exits non-zero, or outlives
+/// `timeout`; without a timeout it is waited for however long it takes.
+pub async fn run_hook(
+    command: &str,
+    env: &[(&str, String)],
+    input: &str,
+    timeout: Option<Duration>,
+) -> Result<()> {
+    let mut child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(std::process::Stdio::piped())
+        .kill_on_drop(timeout.is_some())
+        .spawn()
+        .map_err(|e| anyhow!("Could not run '{}': {}", command, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores stdin closes it early; that is fine
+        let _ = stdin.write_all(input.as_bytes()).await;
+    }
+    let status = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, child.wait())
+            .await
+            .map_err(|_| anyhow!("'{}' timed out", command))??,
+        None => child.wait().await?,
+    };
+    if !status.success() {
+        return Err(anyhow!("'{}' exited with {}", command, status));
+    }
+    Ok(())
+}
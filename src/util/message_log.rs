@@ -0,0 +1,29 @@
+//! Local record of every Mostro protocol message.
+//!
+//! `send_dm` and `parse_dm_events` take the pool to record in, `ctx.pool` from
+//! the commands. Without one (peer chat, library use, tests) nothing is
+//! recorded.
+
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use sqlx::SqlitePool;
+
+use crate::db::{MessageDirection, ProtocolMessage};
+
+/// Store one message in `pool`. A failure is only logged: it must never
+/// break the command that sent or read the message.
+pub async fn log_message(
+    pool: &SqlitePool,
+    event_id: &EventId,
+    direction: MessageDirection,
+    message: &Message,
+    created_at: u64,
+) {
+    let result = match ProtocolMessage::new(event_id, direction, message, created_at as i64) {
+        Ok(record) => record.insert(pool).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        log::warn!("Could not record {} message {}: {}", direction, event_id, e);
+    }
+}
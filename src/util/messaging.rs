@@ -4,13 +4,17 @@ use base64::Engine;
 use mostro_core::prelude::*;
 use nip44::v2::{encrypt_to_bytes, ConversationKey};
 use nostr_sdk::prelude::*;
+use sqlx::SqlitePool;
 use std::env::var;
 use std::str::FromStr;
 
 use crate::cli::Context;
+use crate::db::MessageDirection;
+use crate::error::CantDoError;
 use crate::parser::dms::print_commands_results;
 use crate::parser::parse_dm_events;
 use crate::util::events::FETCH_EVENTS_TIMEOUT;
+use crate::util::message_log::log_message;
 
 /// Helper function to retrieve and validate admin keys from context
 pub fn get_admin_keys(ctx: &Context) -> Result<&Keys> {
@@ -222,7 +226,9 @@ pub fn parse_transport_env() -> Result<Transport> {
 /// (kind 14) per `transport`. Follows the dual-key split: `identity_keys`
 /// sign the seal / identity proof (long-lived reputation binding),
 /// `trade_keys` author the event and produce the inner tuple signature. Pass
-/// the same `Keys` for both to opt into full-privacy mode.
+/// the same `Keys` for both to opt into full-privacy mode. The published
+/// message is recorded in `pool` when one is given.
+#[allow(clippy::too_many_arguments)]
 async fn publish_wrapped(
     client: &Client,
     transport: Transport,
@@ -231,6 +237,7 @@ async fn publish_wrapped(
     receiver_pubkey: &PublicKey,
     message: &Message,
     opts: WrapOptions,
+    pool: Option<&SqlitePool>,
 ) -> Result<()> {
     let event = wrap_message_with(
         transport,
@@ -243,6 +250,16 @@ async fn publish_wrapped(
     .await
     .map_err(|e| anyhow::anyhow!("Failed to wrap message: {e}"))?;
     client.send_event(&event).await?;
+    if let Some(pool) = pool {
+        log_message(
+            pool,
+            &event.id,
+            MessageDirection::Sent,
+            message,
+            Timestamp::now().as_secs(),
+        )
+        .await;
+    }
     Ok(())
 }
 
@@ -251,12 +268,14 @@ async fn publish_wrapped(
 /// The wrap uses `signed = false` so the inner rumor carries `(Message, None)`.
 /// `identity_keys` sign the seal and `trade_keys` author the rumor; admin
 /// flows that do not rotate trade keys should pass the admin keys for both.
+/// The message is recorded in `pool` when one is given.
 pub async fn send_plain_text_dm(
     client: &Client,
     identity_keys: &Keys,
     trade_keys: &Keys,
     receiver_pubkey: &PublicKey,
     text: &str,
+    pool: Option<&SqlitePool>,
 ) -> Result<()> {
     let pow = parse_pow_env()?;
     let dm_message = Message::new_dm(
@@ -278,6 +297,7 @@ pub async fn send_plain_text_dm(
         receiver_pubkey,
         &dm_message,
         opts,
+        pool,
     )
     .await
 }
@@ -502,6 +522,10 @@ async fn create_private_dm_event(
 /// Respects the `POW` and `SECRET` env vars: PoW is mined on the outer
 /// wrap (or kind-14 event), and `SECRET=true` flips the inner tuple to
 /// unsigned. Gift wraps go through [`mostro_core::prelude::wrap_message`].
+///
+/// Mostro protocol messages are recorded in `pool` when one is given; peer
+/// chat never is.
+#[allow(clippy::too_many_arguments)]
 pub async fn send_dm(
     client: &Client,
     identity_keys: &Keys,
@@ -510,6 +534,7 @@ pub async fn send_dm(
     payload: String,
     expiration: Option<Timestamp>,
     to_user: bool,
+    pool: Option<&SqlitePool>,
 ) -> Result<()> {
     let pow = parse_pow_env()?;
 
@@ -536,6 +561,7 @@ pub async fn send_dm(
         receiver_pubkey,
         &message,
         opts,
+        pool,
     )
     .await
 }
@@ -545,10 +571,10 @@ pub async fn print_dm_events(
     request_id: u64,
     ctx: &crate::cli::Context,
     order_trade_keys: Option<&Keys>,
-) -> Result<()> {
+) -> Result<MessageKind> {
     let trade_keys = order_trade_keys.unwrap_or(&ctx.trade_keys);
     // Mostro-protocol reply: unwrap via the transport-agnostic dispatcher.
-    let messages = parse_dm_events(recv_event, trade_keys, None, true).await;
+    let messages = parse_dm_events(recv_event, trade_keys, None, true, Some(&ctx.pool)).await;
     let (message, _, _) = messages
         .first()
         .ok_or_else(|| anyhow::anyhow!("No response received from Mostro"))?;
@@ -564,8 +590,20 @@ pub async fn print_dm_events(
         Err(e) => return Err(anyhow::anyhow!("Unexpected response from Mostro: {e}")),
     }
 
-    print_commands_results(inner, ctx).await?;
-    Ok(())
+    let printed = print_commands_results(inner, ctx).await;
+    if let (Action::CantDo, Err(e)) = (&inner.action, &printed) {
+        let reason = match &inner.payload {
+            Some(Payload::CantDo(reason)) => reason.clone(),
+            _ => None,
+        };
+        return Err(CantDoError {
+            reason,
+            message: e.to_string(),
+        }
+        .into());
+    }
+    printed?;
+    Ok(inner.clone())
 }
 
 #[cfg(test)]
@@ -1,15 +1,27 @@
+pub mod dm_stream;
 pub mod events;
+pub mod history;
+pub mod hook;
+pub mod message_log;
 pub mod messaging;
 pub mod misc;
 pub mod net;
+pub mod node_info;
+pub mod order_stream;
+pub mod qr;
 pub mod storage;
 pub mod types;
 
 // Re-export commonly used items to preserve existing import paths
+pub use dm_stream::DmStream;
 pub use events::{
-    create_filter, fetch_bond_claim_window_days, fetch_events_list, fetch_required_pow,
+    create_filter, fetch_bond_claim_window_days, fetch_dispute_events, fetch_events_list,
+    fetch_order, fetch_order_events, fetch_order_view, fetch_required_pow, subscribe_order_events,
     FETCH_EVENTS_TIMEOUT,
 };
+pub use history::{parse_time_bound, HistoryWindow};
+pub use hook::run_hook;
+pub use message_log::log_message;
 pub use messaging::{
     derive_shared_key_hex, derive_shared_keys, keys_from_shared_hex, print_dm_events,
     send_admin_chat_message_via_shared_key, send_dm, send_plain_text_dm, wait_for_dm,
@@ -17,5 +29,8 @@ pub use messaging::{
 };
 pub use misc::{ensure_private_dir, get_mcli_path, uppercase_first};
 pub use net::connect_nostr;
-pub use storage::{admin_send_dm, run_simple_order_msg, save_order};
+pub use node_info::{fetch_node_info_with, NodeInfo};
+pub use order_stream::OrderStream;
+pub use qr::{print_invoice_qr, QrOptions};
+pub use storage::{admin_send_dm, record_bond_message, run_simple_order_msg, save_order};
 pub use types::{Event, ListKind};
@@ -0,0 +1,188 @@
+//! Typed view of the Mostro instance info event (kind 38385).
+//!
+//! mostrod publishes its limits, fees and Lightning backend details as
+//! single-value `[name, value]` tags. [`NodeInfo`] reads all of them at once
+//! so commands stop issuing one relay round-trip per tag.
+
+use lightning_invoice::Currency;
+use nostr_sdk::prelude::*;
+use std::collections::BTreeMap;
+
+use crate::lightning::{parse_network, InvoiceRequirements};
+use crate::util::events::{fetch_info_event_with, FETCH_EVENTS_TIMEOUT};
+
+/// Tags every addressable Mostro event carries; not node settings.
+const STRUCTURAL_TAGS: [&str; 3] = ["d", "y", "z"];
+
+/// Settings a Mostro node advertises in its kind-38385 info event.
+///
+/// Every field is optional: older daemons publish fewer tags, and a missing or
+/// unparseable tag simply leaves the matching field empty.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NodeInfo {
+    /// `created_at` of the info event revision this was read from.
+    pub published_at: u64,
+    pub mostro_version: Option<String>,
+    pub mostro_commit_hash: Option<String>,
+    /// `1` = gift wrap, `2` = NIP-44 direct (docs/TRANSPORT_V2_SPEC.md).
+    pub protocol_version: Option<u8>,
+    /// Mostro fee as a fraction of the order amount, split between both parties.
+    pub fee: Option<f64>,
+    /// Required NIP-13 proof-of-work difficulty.
+    pub pow: Option<u8>,
+    pub min_order_amount: Option<u64>,
+    pub max_order_amount: Option<u64>,
+    /// Hours a pending order stays published.
+    pub expiration_hours: Option<u64>,
+    /// Seconds a taken order waits for the next step.
+    pub expiration_seconds: Option<u64>,
+    /// Longest expiration a maker may request for a pending order.
+    pub max_expiration_days: Option<u64>,
+    /// Largest premium or discount, in percent, a market price order may set.
+    pub max_premium: Option<u64>,
+    pub max_orders_per_response: Option<u64>,
+    pub hold_invoice_expiration_window: Option<u64>,
+    pub hold_invoice_cltv_delta: Option<u64>,
+    /// Minimum seconds a buyer invoice must stay valid.
+    pub invoice_expiration_window: Option<u64>,
+    /// Empty when the node accepts any currency.
+    pub fiat_currencies_accepted: Vec<String>,
+    pub lnd_version: Option<String>,
+    pub lnd_node_pubkey: Option<String>,
+    pub lnd_commit_hash: Option<String>,
+    pub lnd_node_alias: Option<String>,
+    pub lnd_chains: Vec<String>,
+    pub lnd_networks: Vec<String>,
+    pub lnd_uris: Vec<String>,
+    pub bond_payout_claim_window_days: Option<i64>,
+    /// Every `bond_*` tag, raw, including the claim window above.
+    pub bond_settings: BTreeMap<String, String>,
+    /// Tags this client does not know about yet, raw.
+    pub other: BTreeMap<String, String>,
+}
+
+fn parse_num<T: std::str::FromStr>(value: &str) -> Option<T> {
+    value.trim().parse().ok()
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl NodeInfo {
+    /// Read every tag of a kind-38385 event. When a tag repeats, the first
+    /// occurrence wins, as with single-tag lookups.
+    pub fn from_event(event: &nostr_sdk::Event) -> Self {
+        let mut info = NodeInfo {
+            published_at: event.created_at.as_secs(),
+            ..Default::default()
+        };
+        let mut seen = std::collections::HashSet::new();
+        for tag in event.tags.iter() {
+            let slice = tag.as_slice();
+            let (Some(name), Some(value)) = (slice.first(), slice.get(1)) else {
+                continue;
+            };
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            info.set(name, value);
+        }
+        info
+    }
+
+    fn set(&mut self, name: &str, value: &str) {
+        let text = || Some(value.trim().to_string());
+        match name {
+            "mostro_version" => self.mostro_version = text(),
+            "mostro_commit_hash" => self.mostro_commit_hash = text(),
+            "protocol_version" => self.protocol_version = parse_num(value),
+            "fee" => self.fee = parse_num(value),
+            "pow" => self.pow = parse_num(value),
+            "min_order_amount" => self.min_order_amount = parse_num(value),
+            "max_order_amount" => self.max_order_amount = parse_num(value),
+            "expiration_hours" => self.expiration_hours = parse_num(value),
+            "expiration_seconds" => self.expiration_seconds = parse_num(value),
+            "max_expiration_days" => self.max_expiration_days = parse_num(value),
+            "max_premium" => self.max_premium = parse_num(value),
+            "max_orders_per_response" => self.max_orders_per_response = parse_num(value),
+            "hold_invoice_expiration_window" => {
+                self.hold_invoice_expiration_window = parse_num(value)
+            }
+            "hold_invoice_cltv_delta" => self.hold_invoice_cltv_delta = parse_num(value),
+            "invoice_expiration_window" => self.invoice_expiration_window = parse_num(value),
+            "fiat_currencies_accepted" => self.fiat_currencies_accepted = parse_list(value),
+            "lnd_version" => self.lnd_version = text(),
+            "lnd_node_pubkey" => self.lnd_node_pubkey = text(),
+            "lnd_commit_hash" => self.lnd_commit_hash = text(),
+            "lnd_node_alias" => self.lnd_node_alias = text(),
+            "lnd_chains" => self.lnd_chains = parse_list(value),
+            "lnd_networks" => self.lnd_networks = parse_list(value),
+            "lnd_uris" => self.lnd_uris = parse_list(value),
+            name if name.starts_with("bond_") => {
+                if name == "bond_payout_claim_window_days" {
+                    self.bond_payout_claim_window_days = parse_num(value);
+                }
+                self.bond_settings
+                    .insert(name.to_string(), value.to_string());
+            }
+            name if STRUCTURAL_TAGS.contains(&name) => {}
+            name => {
+                self.other.insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// Lightning networks the node's backend runs on, as invoice currencies.
+    pub fn networks(&self) -> Vec<Currency> {
+        self.lnd_networks
+            .iter()
+            .filter_map(|n| parse_network(n))
+            .collect()
+    }
+
+    /// Whether the node takes orders in `fiat_code` (any, when it lists none).
+    pub fn accepts_currency(&self, fiat_code: &str) -> bool {
+        self.fiat_currencies_accepted.is_empty()
+            || self
+                .fiat_currencies_accepted
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(fiat_code))
+    }
+
+    /// The [`InvoiceRequirements`] a buyer invoice must meet:
+    /// `invoice_expiration_window`, `min_order_amount` and `lnd_networks`.
+    /// Missing tags leave the matching check disabled; the expected amount is
+    /// order-specific and left for the caller to set.
+    pub fn invoice_requirements(&self) -> InvoiceRequirements {
+        InvoiceRequirements {
+            amount_sats: None,
+            min_amount_sats: self.min_order_amount,
+            min_expiry_secs: self.invoice_expiration_window,
+            networks: self.networks(),
+        }
+    }
+
+    /// [`NodeInfo::invoice_requirements`] for a bond payout invoice. A share
+    /// of a slashed bond can be smaller than the node's `min_order_amount`,
+    /// so only the network and expiry window apply.
+    pub fn bond_payout_requirements(&self) -> InvoiceRequirements {
+        InvoiceRequirements {
+            min_amount_sats: None,
+            ..self.invoice_requirements()
+        }
+    }
+}
+
+/// Fetch and parse the node's newest kind-38385 info event. Best-effort:
+/// `None` when no relay has one or the fetch fails.
+pub async fn fetch_node_info_with(client: &Client, mostro_pubkey: PublicKey) -> Option<NodeInfo> {
+    fetch_info_event_with(client, mostro_pubkey, FETCH_EVENTS_TIMEOUT)
+        .await
+        .map(|event| NodeInfo::from_event(&event))
+}
@@ -0,0 +1,89 @@
+//! Live kind-38383 order events, merged into an [`OrderFeed`].
+//!
+//! Shared by every command that keeps watching the order book
+//! (`listorders --follow`, `watchalerts`): one subscription, the newest
+//! revision of each order, and periodic local expiry.
+
+use anyhow::Result;
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use std::time::Duration;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::cli::Context;
+use crate::parser::order_feed::{OrderChange, OrderFeed};
+use crate::util::events::subscribe_order_events;
+
+/// How often listed orders are checked against their `expires_at`.
+pub const ORDER_EXPIRY_CHECK: Duration = Duration::from_secs(30);
+
+/// An open subscription to the node's order events.
+pub struct OrderStream<'a> {
+    ctx: &'a Context,
+    notifications: Receiver<RelayPoolNotification>,
+    subscription: SubscriptionId,
+    expiry_check: tokio::time::Interval,
+    pub feed: OrderFeed,
+}
+
+impl<'a> OrderStream<'a> {
+    /// Subscribe to order events published from `since` on and merge them
+    /// into `feed`, which should already hold the snapshot up to `since`.
+    pub async fn start(ctx: &'a Context, feed: OrderFeed, since: i64) -> Result<Self> {
+        // Listen before subscribing, so no event slips in between
+        let notifications = ctx.client.notifications();
+        let subscription = subscribe_order_events(ctx, since).await?;
+        Ok(Self {
+            ctx,
+            notifications,
+            subscription,
+            expiry_check: tokio::time::interval(ORDER_EXPIRY_CHECK),
+            feed,
+        })
+    }
+
+    /// Wait for the next changes to the listed orders, with the unix time
+    /// they were seen. `None` once the user presses Ctrl-C.
+    pub async fn next(&mut self) -> Result<Option<(Vec<OrderChange>, i64)>> {
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return Ok(None),
+                _ = self.expiry_check.tick() => {
+                    let now = chrono::Utc::now().timestamp();
+                    let changes = self.feed.expire(now);
+                    if !changes.is_empty() {
+                        return Ok(Some((changes, now)));
+                    }
+                }
+                notification = self.notifications.recv() => {
+                    let event = match notification {
+                        Ok(RelayPoolNotification::Event { event, .. }) => event,
+                        Ok(_) => continue,
+                        // Lagging behind the broadcast drops events, not the feed
+                        Err(RecvError::Lagged(skipped)) => {
+                            log::warn!("Skipped {} relay notifications", skipped);
+                            continue;
+                        }
+                        Err(e) => {
+                            return Err(anyhow::anyhow!("Relay notifications closed: {}", e));
+                        }
+                    };
+                    if event.pubkey != self.ctx.mostro_pubkey
+                        || event.kind != nostr_sdk::Kind::Custom(NOSTR_ORDER_EVENT_KIND)
+                    {
+                        continue;
+                    }
+                    let now = chrono::Utc::now().timestamp();
+                    if let Some(change) = self.feed.apply(&event, now) {
+                        return Ok(Some((vec![change], now)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Close the subscription on every relay.
+    pub async fn close(self) {
+        self.ctx.client.unsubscribe(&self.subscription).await;
+    }
+}
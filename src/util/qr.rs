@@ -0,0 +1,143 @@
+use anyhow::{anyhow, Result};
+use qrcode::render::{svg, unicode};
+use qrcode::{Color, EcLevel, QrCode};
+use std::env::var;
+use std::path::{Path, PathBuf};
+
+/// Pixels per QR module in PNG output.
+const PNG_MODULE_SIZE: usize = 8;
+/// Blank modules around the code, as required by the QR spec.
+const QUIET_ZONE: usize = 4;
+
+/// Where invoice QR codes are rendered.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct QrOptions {
+    /// Print the code in the terminal with Unicode half-blocks.
+    pub terminal: bool,
+    /// Also write it to this `.png` or `.svg` file.
+    pub file: Option<PathBuf>,
+}
+
+impl QrOptions {
+    /// Read `MOSTRO_QR=true` and `MOSTRO_QR_FILE=<path>` so QR output can be switched on
+    /// for every command that displays an invoice.
+    pub fn from_env() -> Self {
+        Self {
+            terminal: var("MOSTRO_QR").is_ok_and(|v| v == "true"),
+            file: var("MOSTRO_QR_FILE")
+                .ok()
+                .filter(|f| !f.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.terminal || self.file.is_some()
+    }
+}
+
+/// Text encoded for a bolt11: an upper-case `lightning:` URI, which phone
+/// cameras hand to the wallet and which fits QR alphanumeric mode, giving a
+/// noticeably smaller code than lower case.
+pub fn invoice_qr_payload(invoice: &str) -> String {
+    let invoice = invoice.trim();
+    let invoice = match invoice.get(..10) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("lightning:") => &invoice[10..],
+        _ => invoice,
+    };
+    format!("LIGHTNING:{}", invoice.to_uppercase())
+}
+
+fn encode(data: &str) -> Result<QrCode> {
+    QrCode::with_error_correction_level(data, EcLevel::L)
+        .map_err(|e| anyhow!("Could not encode QR code: {}", e))
+}
+
+/// Render `data` as a QR code made of Unicode half-blocks (two modules per
+/// character row). Colors are inverted for the usual dark terminal theme.
+pub fn render_qr_terminal(data: &str) -> Result<String> {
+    Ok(encode(data)?
+        .render::<unicode::Dense1x2>()
+        .dark_color(unicode::Dense1x2::Light)
+        .light_color(unicode::Dense1x2::Dark)
+        .build())
+}
+
+/// Render `data` as a standalone SVG document.
+pub fn render_qr_svg(data: &str) -> Result<String> {
+    Ok(encode(data)?
+        .render::<svg::Color>()
+        .min_dimensions(256, 256)
+        .build())
+}
+
+/// Render `data` as an 8-bit grayscale PNG.
+pub fn render_qr_png(data: &str) -> Result<Vec<u8>> {
+    let code = encode(data)?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let side = (modules + 2 * QUIET_ZONE) * PNG_MODULE_SIZE;
+
+    let mut pixels = vec![255u8; side * side];
+    for y in 0..modules {
+        for x in 0..modules {
+            if colors[y * modules + x] != Color::Dark {
+                continue;
+            }
+            let top = (y + QUIET_ZONE) * PNG_MODULE_SIZE;
+            let left = (x + QUIET_ZONE) * PNG_MODULE_SIZE;
+            for row in top..top + PNG_MODULE_SIZE {
+                pixels[row * side + left..row * side + left + PNG_MODULE_SIZE].fill(0);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, side as u32, side as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(out)
+}
+
+/// Write `data` as a QR code to `path`, choosing PNG or SVG by extension.
+pub fn write_qr_file(data: &str, path: &Path) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => std::fs::write(path, render_qr_png(data)?)?,
+        Some("svg") => std::fs::write(path, render_qr_svg(data)?)?,
+        _ => {
+            return Err(anyhow!(
+                "Unsupported QR file type for {}: use .png or .svg",
+                path.display()
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Show `invoice` as a QR code according to `options`. Failures are only
+/// reported: the invoice string itself is always printed by the caller.
+pub fn print_invoice_qr(invoice: &str, options: &QrOptions) {
+    let payload = invoice_qr_payload(invoice);
+    if options.terminal {
+        match render_qr_terminal(&payload) {
+            Ok(qr) => {
+                println!("📱 Scan with your wallet:");
+                println!("{}", qr);
+            }
+            Err(e) => println!("⚠️  {}", e),
+        }
+    }
+    if let Some(path) = &options.file {
+        match write_qr_file(&payload, path) {
+            Ok(()) => println!("🖼️  QR code written to {}", path.display()),
+            Err(e) => println!("⚠️  Could not write QR code: {}", e),
+        }
+    }
+}
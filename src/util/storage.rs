@@ -6,18 +6,22 @@ use uuid::Uuid;
 
 use crate::cli::send_msg::execute_send_msg;
 use crate::cli::{Commands, Context};
-use crate::db::{Order, User};
+use crate::db::{Bond, BondRole, BondStatus, Order, User};
+use crate::lightning::decode_invoice;
 use crate::util::messaging::get_admin_keys;
 use crate::util::send_dm;
 
+/// Store an order we made (`maker`) or took, and move the last trade index
+/// to the one it uses.
 pub async fn save_order(
     order: SmallOrder,
     trade_keys: &Keys,
     request_id: u64,
     trade_index: i64,
+    maker: bool,
     pool: &SqlitePool,
 ) -> Result<()> {
-    if let Ok(order) = Order::new(pool, order, trade_keys, Some(request_id as i64)).await {
+    if let Ok(order) = Order::new(pool, order, trade_keys, Some(request_id as i64), maker).await {
         if let Some(order_id) = order.id {
             println!("Order {} created", order_id);
         } else {
@@ -59,7 +63,147 @@ pub async fn admin_send_dm(ctx: &Context, msg: String) -> Result<()> {
         msg,
         None,
         false,
+        Some(&ctx.pool),
     )
     .await?;
     Ok(())
 }
+
+/// Order a bond-related message refers to.
+fn message_order_id(message: &MessageKind) -> Option<String> {
+    let from_payload = match &message.payload {
+        Some(Payload::Order(order)) => order.id,
+        Some(Payload::PaymentRequest(Some(order), _, _)) => order.id,
+        Some(Payload::BondPayoutRequest(req)) => req.order.id,
+        _ => None,
+    };
+    message.id.or(from_payload).map(|id| id.to_string())
+}
+
+fn payload_order_amount(message: &MessageKind) -> i64 {
+    match &message.payload {
+        Some(Payload::Order(order)) => order.amount,
+        Some(Payload::BondPayoutRequest(req)) => req.order.amount,
+        _ => 0,
+    }
+}
+
+/// Bond row that results from applying `message` to the stored `posted` and
+/// `owed` bonds of its order, or `None` when the message changes nothing.
+///
+/// Final statuses never move, and a message can only advance a bond, so
+/// replaying old DMs (e.g. a resent `add-bond-invoice`) is harmless.
+pub fn bond_update_from_message(
+    posted: Option<&Bond>,
+    owed: Option<&Bond>,
+    message: &MessageKind,
+    claim_window_days: Option<i64>,
+) -> Option<Bond> {
+    let order_id = message_order_id(message)?;
+    let status_of = |bond: Option<&Bond>| bond.and_then(|b| b.status());
+    let advance = |bond: Option<&Bond>, role: BondRole, status: BondStatus| {
+        let mut next = bond
+            .cloned()
+            .unwrap_or_else(|| Bond::new(&order_id, role, status, 0));
+        next.status = status.to_string();
+        next.updated_at = chrono::Utc::now().timestamp();
+        next
+    };
+
+    match message.action {
+        Action::PayBondInvoice => {
+            if status_of(posted).is_some_and(|s| s != BondStatus::Pending) {
+                return None;
+            }
+            let Some(Payload::PaymentRequest(_, invoice, _)) = &message.payload else {
+                return None;
+            };
+            let mut bond = advance(posted, BondRole::Posted, BondStatus::Pending);
+            if let Some(sats) = decode_invoice(invoice).ok().and_then(|i| i.amount_sats()) {
+                bond.amount = sats as i64;
+            }
+            bond.hold_invoice = Some(invoice.clone());
+            Some(bond)
+        }
+        Action::BondSlashed => {
+            if status_of(posted) == Some(BondStatus::Slashed) {
+                return None;
+            }
+            let mut bond = advance(posted, BondRole::Posted, BondStatus::Slashed);
+            let amount = payload_order_amount(message);
+            if amount > 0 {
+                bond.amount = amount;
+            }
+            Some(bond)
+        }
+        Action::AddBondInvoice => {
+            let Some(Payload::BondPayoutRequest(req)) = &message.payload else {
+                return None;
+            };
+            if status_of(owed).is_some_and(|s| s != BondStatus::Claimable) {
+                return None;
+            }
+            let mut bond = advance(owed, BondRole::Owed, BondStatus::Claimable);
+            bond.amount = req.order.amount;
+            bond.slashed_at = Some(req.slashed_at);
+            bond.claim_window_days = claim_window_days.or(bond.claim_window_days);
+            Some(bond)
+        }
+        Action::BondInvoiceAccepted => {
+            if status_of(owed).is_some_and(|s| matches!(s, BondStatus::Claimed | BondStatus::Paid))
+            {
+                return None;
+            }
+            let mut bond = advance(owed, BondRole::Owed, BondStatus::Claimed);
+            let amount = payload_order_amount(message);
+            if amount > 0 {
+                bond.amount = amount;
+            }
+            Some(bond)
+        }
+        Action::BondPayoutCompleted => {
+            if status_of(owed) == Some(BondStatus::Paid) {
+                return None;
+            }
+            Some(advance(owed, BondRole::Owed, BondStatus::Paid))
+        }
+        Action::Released
+        | Action::PurchaseCompleted
+        | Action::Canceled
+        | Action::CooperativeCancelAccepted
+        | Action::AdminSettled
+        | Action::AdminCanceled => match status_of(posted) {
+            Some(status) if !status.is_final() => {
+                Some(advance(posted, BondRole::Posted, BondStatus::Released))
+            }
+            _ => None,
+        },
+        // Any other progress on the order means the bond hold invoice was paid.
+        Action::CantDo => None,
+        _ => match status_of(posted) {
+            Some(BondStatus::Pending) => {
+                Some(advance(posted, BondRole::Posted, BondStatus::Locked))
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Update the `bonds` table from a message received from Mostro.
+pub async fn record_bond_message(
+    pool: &SqlitePool,
+    message: &MessageKind,
+    claim_window_days: Option<i64>,
+) -> Result<Option<Bond>> {
+    let Some(order_id) = message_order_id(message) else {
+        return Ok(None);
+    };
+    let posted = Bond::get(pool, &order_id, BondRole::Posted).await?;
+    let owed = Bond::get(pool, &order_id, BondRole::Owed).await?;
+    let update =
+        bond_update_from_message(posted.as_ref(), owed.as_ref(), message, claim_window_days);
+    if let Some(bond) = &update {
+        bond.upsert(pool).await?;
+    }
+    Ok(update)
+}
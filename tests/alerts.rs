@@ -0,0 +1,237 @@
+mod common;
+
+use common::order_view;
+use mostro_client::alerts::{
+    alert_payload, new_alert_rule, rule_description, rule_matches, AlertNotifier, NotifierKind,
+};
+use mostro_client::db::AlertRule;
+use mostro_client::fiat::{FiatAmount, FiatRange};
+use mostro_client::nip33::OrderView;
+use mostro_core::prelude::*;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn view(kind: Kind, fiat_code: &str, fiat: &str, premium: i64, methods: &str) -> OrderView {
+    order_view(kind, fiat_code, fiat, premium, methods, 0)
+}
+
+/// "sell order, USD, premium ≤ 1%, pays Zelle, 50–500 fiat"
+fn zelle_rule() -> AlertRule {
+    new_alert_rule(
+        "zelle",
+        Some("Sell"),
+        Some("usd"),
+        &["Zelle".to_string()],
+        Some(&FiatRange::parse("50-500").unwrap()),
+        None,
+        Some(1),
+        None,
+        &AlertNotifier::Stdout,
+    )
+    .unwrap()
+}
+
+#[test]
+fn rules_match_kind_currency_method_premium_and_fiat_range() {
+    let rule = zelle_rule();
+    assert_eq!(rule.kind.as_deref(), Some("sell"));
+    assert_eq!(rule.fiat_code.as_deref(), Some("USD"));
+    assert_eq!(
+        rule_description(&rule),
+        "sell · USD · Zelle · 50-500 · premium ≤ 1%"
+    );
+
+    assert!(rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "100", 0, "zelle,cash"),
+        0
+    ));
+    // A range overlapping 50-500 can be traded inside it
+    assert!(rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "20-60.5", 1, "Zelle"),
+        0
+    ));
+    assert!(rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "499.99", 1, "Zelle"),
+        0
+    ));
+
+    assert!(!rule_matches(
+        &rule,
+        &view(Kind::Buy, "USD", "100", 0, "Zelle"),
+        0
+    ));
+    assert!(!rule_matches(
+        &rule,
+        &view(Kind::Sell, "EUR", "100", 0, "Zelle"),
+        0
+    ));
+    assert!(!rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "100", 2, "Zelle"),
+        0
+    ));
+    assert!(!rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "100", 0, "SEPA"),
+        0
+    ));
+    assert!(!rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "500.01", 0, "Zelle"),
+        0
+    ));
+    assert!(!rule_matches(
+        &rule,
+        &view(Kind::Sell, "USD", "10-49", 0, "Zelle"),
+        0
+    ));
+
+    let picky = AlertRule {
+        min_rating: Some(4.8),
+        ..rule
+    };
+    assert!(!rule_matches(
+        &picky,
+        &view(Kind::Sell, "USD", "100", 0, "Zelle"),
+        0
+    ));
+}
+
+#[test]
+fn rules_and_notifiers_are_validated() {
+    let rule = |kind: Option<&str>, min: Option<i64>, max: Option<i64>, rating: Option<f64>| {
+        new_alert_rule(
+            "x",
+            kind,
+            None,
+            &[],
+            None,
+            min,
+            max,
+            rating,
+            &AlertNotifier::Stdout,
+        )
+    };
+    assert!(rule(Some("swap"), None, None, None).is_err());
+    assert!(rule(None, Some(3), Some(1), None).is_err());
+    assert!(rule(None, None, None, Some(6.0)).is_err());
+    assert!(rule(None, Some(-2), Some(1), Some(4.0)).is_ok());
+
+    assert_eq!(
+        AlertNotifier::new(NotifierKind::Stdout, None, false).unwrap(),
+        AlertNotifier::Stdout
+    );
+    assert!(AlertNotifier::new(NotifierKind::Stdout, Some("x"), false).is_err());
+    assert!(AlertNotifier::new(NotifierKind::Exec, None, false).is_err());
+    assert!(AlertNotifier::new(NotifierKind::Webhook, Some("ftp://host/x"), false).is_err());
+    assert!(AlertNotifier::new(NotifierKind::Webhook, Some("not a url"), false).is_err());
+    let webhook = AlertNotifier::new(
+        NotifierKind::Webhook,
+        Some("http://127.0.0.1:9000/hook"),
+        false,
+    )
+    .unwrap();
+
+    // The stored rule rebuilds the same notifier
+    let stored = new_alert_rule("x", None, None, &[], None, None, None, None, &webhook).unwrap();
+    assert_eq!(stored.notifier, "webhook");
+    assert_eq!(AlertNotifier::for_rule(&stored).unwrap(), webhook);
+}
+
+#[test]
+fn remote_webhooks_need_an_explicit_opt_in() {
+    for local in [
+        "http://localhost:8080/hook",
+        "http://127.0.0.1/hook",
+        "https://[::1]:9000/hook",
+    ] {
+        assert!(
+            AlertNotifier::new(NotifierKind::Webhook, Some(local), false).is_ok(),
+            "{}",
+            local
+        );
+    }
+    let remote = "https://alerts.example.com/hook";
+    let err = AlertNotifier::new(NotifierKind::Webhook, Some(remote), false).unwrap_err();
+    assert!(err.to_string().contains("--allowremote"), "{}", err);
+    assert!(
+        AlertNotifier::new(NotifierKind::Webhook, Some("http://10.0.0.5/hook"), false).is_err()
+    );
+
+    // The opt-in is saved with the rule, so watchalerts accepts it again
+    let webhook = AlertNotifier::new(NotifierKind::Webhook, Some(remote), true).unwrap();
+    let stored = new_alert_rule("x", None, None, &[], None, None, None, None, &webhook).unwrap();
+    assert!(stored.allow_remote);
+    assert_eq!(AlertNotifier::for_rule(&stored).unwrap(), webhook);
+    let edited = AlertRule {
+        allow_remote: false,
+        ..stored
+    };
+    assert!(AlertNotifier::for_rule(&edited).is_err());
+}
+
+#[tokio::test]
+async fn webhook_receives_the_match_as_json() {
+    let rule = zelle_rule();
+    let order = view(Kind::Sell, "USD", "12.50", 0, "Zelle");
+    let id = order.order.id.unwrap().to_string();
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .and(body_partial_json(serde_json::json!({
+            "rule": { "name": "zelle" },
+            "order": { "id": id, "fiat_amount": "12.50", "kind": "sell" },
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let notifier = AlertNotifier::new(
+        NotifierKind::Webhook,
+        Some(&format!("{}/hook", server.uri())),
+        false,
+    )
+    .unwrap();
+    notifier.notify(&rule, &order, 1_700_000_000).await.unwrap();
+
+    // Errors from the endpoint are reported
+    let missing = AlertNotifier::new(
+        NotifierKind::Webhook,
+        Some(&format!("{}/other", server.uri())),
+        false,
+    )
+    .unwrap();
+    assert!(missing.notify(&rule, &order, 0).await.is_err());
+}
+
+#[tokio::test]
+async fn exec_hook_gets_the_match_in_its_environment() {
+    let rule = zelle_rule();
+    let order = view(Kind::Sell, "USD", "100", 0, "Zelle");
+    let out = std::env::temp_dir().join(format!("mcli-alert-hook-{}", std::process::id()));
+    let command = format!(
+        "printf '%s %s' \"$MOSTRO_ALERT_RULE\" \"$MOSTRO_ORDER_ID\" > '{}'",
+        out.display()
+    );
+    let notifier = AlertNotifier::new(NotifierKind::Exec, Some(&command), false).unwrap();
+    notifier.notify(&rule, &order, 0).await.unwrap();
+    let written = std::fs::read_to_string(&out).unwrap();
+    let _ = std::fs::remove_file(&out);
+    assert_eq!(written, format!("zelle {}", order.order.id.unwrap()));
+
+    let failing = AlertNotifier::new(NotifierKind::Exec, Some("exit 3"), false).unwrap();
+    assert!(failing.notify(&rule, &order, 0).await.is_err());
+
+    let payload = alert_payload(&rule, &order, 7);
+    assert_eq!(payload["matched_at"], 7);
+    assert_eq!(payload["order"]["maker_rating"]["total_reviews"], 10);
+    assert_eq!(
+        FiatAmount::parse("100").unwrap().to_string(),
+        payload["order"]["fiat_amount"]
+    );
+}
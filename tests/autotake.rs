@@ -0,0 +1,182 @@
+mod common;
+
+use common::order_view;
+use mostro_client::autotake::{
+    trade_finished, AutoTakeLedger, AutoTakeRules, TakeAmount, TradeLimits, VOLUME_WINDOW_SECS,
+};
+use mostro_client::cli::take_order::MakerRequirements;
+use mostro_client::fiat::{FiatAmount, FiatRange};
+use mostro_client::nip33::OrderView;
+use mostro_client::parser::orders::OrderFilter;
+use mostro_core::prelude::*;
+
+fn view(kind: Kind, fiat: &str, premium: i64, created_at: i64) -> OrderView {
+    order_view(kind, "USD", fiat, premium, "zelle", created_at)
+}
+
+fn rules() -> AutoTakeRules {
+    AutoTakeRules {
+        kind: Some(Kind::Sell),
+        fiat_code: "USD".to_string(),
+        fiat: Some(FiatRange::parse("50-500").unwrap()),
+        filter: OrderFilter {
+            payment_methods: vec!["Zelle".to_string()],
+            max_premium: Some(1),
+            ..Default::default()
+        },
+        maker: MakerRequirements {
+            min_rating: Some(4.5),
+            min_reviews: Some(10),
+        },
+    }
+}
+
+fn whole(amount: i64) -> FiatAmount {
+    FiatAmount::from_whole(amount)
+}
+
+#[test]
+fn rules_pick_orders_and_amounts() {
+    let rules = rules();
+    assert!(rules.matches(&view(Kind::Sell, "100", 1, 0), 0));
+    assert!(!rules.matches(&view(Kind::Buy, "100", 1, 0), 0));
+    assert!(!rules.matches(&view(Kind::Sell, "100", 2, 0), 0));
+    let mut unrated = view(Kind::Sell, "100", 0, 0);
+    unrated.maker_rating = None;
+    assert!(!rules.matches(&unrated, 0));
+    let mut taken = view(Kind::Sell, "100", 0, 0);
+    taken.order.status = Some(Status::InProgress);
+    assert!(!rules.matches(&taken, 0));
+
+    // Fixed amounts must fit the bounds and the room left
+    let fixed = view(Kind::Sell, "120.50", 0, 0);
+    assert_eq!(
+        rules.take_amount(&fixed, None),
+        Some(TakeAmount {
+            fiat: FiatAmount::parse("120.5").unwrap(),
+            amount: None
+        })
+    );
+    assert_eq!(rules.take_amount(&fixed, Some(whole(100))), None);
+    assert_eq!(rules.take_amount(&view(Kind::Sell, "20", 0, 0), None), None);
+
+    // Ranges are taken for the most every bound allows, in whole units
+    let range = view(Kind::Sell, "10-1000", 0, 0);
+    let take = |room| rules.take_amount(&range, room).and_then(|t| t.amount);
+    assert_eq!(take(None), Some(500));
+    assert_eq!(take(Some(FiatAmount::parse("321.75").unwrap())), Some(321));
+    assert_eq!(take(Some(whole(49))), None);
+    assert_eq!(
+        rules.take_amount(&view(Kind::Sell, "60.5-70.5", 0, 0), None),
+        Some(TakeAmount {
+            fiat: whole(70),
+            amount: Some(70)
+        })
+    );
+    assert_eq!(
+        rules.take_amount(&view(Kind::Sell, "600-900", 0, 0), None),
+        None
+    );
+}
+
+#[test]
+fn ledger_enforces_open_trades_and_daily_volume() {
+    let now = 1_700_000_000;
+    let limits = TradeLimits {
+        max_open: Some(2),
+        daily_volume: Some(whole(300)),
+    };
+    let mut ledger = AutoTakeLedger::default();
+    assert_eq!(ledger.room(&limits, now), Some(whole(300)));
+    assert_eq!(ledger.limit_reached(&limits, now), None);
+
+    let first = view(Kind::Sell, "100", 0, now - 10);
+    let take = TakeAmount {
+        fiat: whole(100),
+        amount: None,
+    };
+    // Taken yesterday: counts as open but not against today's volume
+    ledger.record(&first, &take, now - VOLUME_WINDOW_SECS - 1);
+    let second = view(Kind::Sell, "250", 0, now - 10);
+    let entry = ledger.record(
+        &second,
+        &TakeAmount {
+            fiat: whole(250),
+            amount: None,
+        },
+        now,
+    );
+    assert_eq!(entry.fiat_amount, "250");
+    assert!(ledger.contains(&second.order.id.unwrap()));
+    assert_eq!(ledger.room(&limits, now), Some(whole(50)));
+    assert_eq!(
+        ledger.limit_reached(&limits, now).as_deref(),
+        Some("2 trades open, the maximum")
+    );
+
+    // The first trade ends: one slot is free again
+    let mut done = first.clone();
+    done.order.status = Some(Status::Success);
+    done.order.created_at = Some(now);
+    assert!(ledger.update(&done).unwrap().finished);
+    assert!(ledger.update(&done).is_none());
+    assert_eq!(ledger.open_count(), 1);
+    assert_eq!(ledger.limit_reached(&limits, now), None);
+
+    let third = view(Kind::Sell, "50", 0, now);
+    ledger.record(
+        &third,
+        &TakeAmount {
+            fiat: whole(50),
+            amount: None,
+        },
+        now,
+    );
+    assert_eq!(
+        ledger.limit_reached(&TradeLimits::default(), now),
+        None,
+        "no limits set"
+    );
+    assert_eq!(
+        ledger
+            .limit_reached(
+                &TradeLimits {
+                    max_open: None,
+                    daily_volume: Some(whole(300))
+                },
+                now
+            )
+            .as_deref(),
+        Some("daily volume of 300 reached")
+    );
+
+    // A take refused by Mostro gives its room back
+    ledger.forget(&third.order.id.unwrap());
+    assert!(!ledger.contains(&third.order.id.unwrap()));
+    assert_eq!(ledger.volume_since(now - VOLUME_WINDOW_SECS), whole(250));
+
+    // An open take whose order event is gone stops holding a slot, once
+    let second_id = second.order.id.unwrap();
+    let expired = ledger.expire(&second_id).unwrap();
+    assert!(expired.finished);
+    assert_eq!(expired.status, "pending");
+    assert!(ledger.expire(&second_id).is_none());
+    assert_eq!(ledger.open_count(), 0);
+}
+
+#[test]
+fn trades_end_on_final_status_or_back_in_the_book() {
+    let taken_at = 1_000;
+    let mut order = view(Kind::Buy, "100", 0, 900);
+    assert!(!trade_finished(&order, taken_at));
+    order.order.status = Some(Status::Active);
+    order.order.created_at = Some(1_100);
+    assert!(!trade_finished(&order, taken_at));
+    order.order.status = Some(Status::Pending);
+    assert!(
+        trade_finished(&order, taken_at),
+        "republished after the take"
+    );
+    order.order.status = Some(Status::CooperativelyCanceled);
+    assert!(trade_finished(&order, taken_at));
+}
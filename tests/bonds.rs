@@ -0,0 +1,154 @@
+use mostro_client::db::{Bond, BondRole, BondStatus};
+use mostro_client::parser::bonds::{bond_deadline_text, print_bonds_table};
+use mostro_client::util::storage::bond_update_from_message;
+use mostro_core::prelude::*;
+use uuid::Uuid;
+
+const ORDER_ID: &str = "308e1272-d5f4-47e6-bd97-3504baea9c23";
+
+fn order_id() -> Uuid {
+    Uuid::parse_str(ORDER_ID).unwrap()
+}
+
+fn message(action: Action, payload: Option<Payload>) -> MessageKind {
+    MessageKind::new(Some(order_id()), None, None, action, payload)
+}
+
+fn payout_request(amount: i64, slashed_at: i64) -> MessageKind {
+    let order = SmallOrder {
+        id: Some(order_id()),
+        amount,
+        ..Default::default()
+    };
+    message(
+        Action::AddBondInvoice,
+        Some(Payload::BondPayoutRequest(BondPayoutRequest {
+            order,
+            slashed_at,
+        })),
+    )
+}
+
+fn bond(role: BondRole, status: BondStatus) -> Bond {
+    Bond::new(ORDER_ID, role, status, 1_000)
+}
+
+#[test]
+fn pay_bond_invoice_creates_pending_posted_bond() {
+    let msg = message(
+        Action::PayBondInvoice,
+        Some(Payload::PaymentRequest(
+            None,
+            "lnbcrt1invalid".to_string(),
+            None,
+        )),
+    );
+    let bond = bond_update_from_message(None, None, &msg, None).unwrap();
+    assert_eq!(bond.order_id, ORDER_ID);
+    assert_eq!(bond.role(), Some(BondRole::Posted));
+    assert_eq!(bond.status(), Some(BondStatus::Pending));
+    assert_eq!(bond.hold_invoice.as_deref(), Some("lnbcrt1invalid"));
+}
+
+#[test]
+fn next_order_step_locks_pending_bond() {
+    let posted = bond(BondRole::Posted, BondStatus::Pending);
+    let msg = message(Action::WaitingSellerToPay, None);
+    let bond = bond_update_from_message(Some(&posted), None, &msg, None).unwrap();
+    assert_eq!(bond.status(), Some(BondStatus::Locked));
+
+    let locked = bond;
+    assert!(bond_update_from_message(Some(&locked), None, &msg, None).is_none());
+}
+
+#[test]
+fn completed_trade_releases_and_slash_is_final() {
+    let locked = bond(BondRole::Posted, BondStatus::Locked);
+    let released = bond_update_from_message(
+        Some(&locked),
+        None,
+        &message(Action::PurchaseCompleted, None),
+        None,
+    )
+    .unwrap();
+    assert_eq!(released.status(), Some(BondStatus::Released));
+
+    let slashed = bond_update_from_message(
+        Some(&locked),
+        None,
+        &message(Action::BondSlashed, None),
+        None,
+    )
+    .unwrap();
+    assert_eq!(slashed.status(), Some(BondStatus::Slashed));
+    // A later cancel cannot bring a slashed bond back
+    assert!(
+        bond_update_from_message(Some(&slashed), None, &message(Action::Canceled, None), None)
+            .is_none()
+    );
+}
+
+#[test]
+fn payout_request_makes_owed_share_claimable() {
+    let bond = bond_update_from_message(None, None, &payout_request(2_500, 1_700_000_000), Some(3))
+        .unwrap();
+    assert_eq!(bond.role(), Some(BondRole::Owed));
+    assert_eq!(bond.status(), Some(BondStatus::Claimable));
+    assert_eq!(bond.amount, 2_500);
+    assert_eq!(bond.slashed_at, Some(1_700_000_000));
+    assert_eq!(bond.claim_deadline(), Some(1_700_000_000 + 3 * 86_400));
+}
+
+#[test]
+fn resent_payout_request_does_not_reopen_claim() {
+    let claimed = bond(BondRole::Owed, BondStatus::Claimed);
+    assert!(bond_update_from_message(
+        None,
+        Some(&claimed),
+        &payout_request(2_500, 1_700_000_000),
+        Some(3)
+    )
+    .is_none());
+
+    let paid = bond_update_from_message(
+        None,
+        Some(&claimed),
+        &message(Action::BondPayoutCompleted, None),
+        None,
+    )
+    .unwrap();
+    assert_eq!(paid.status(), Some(BondStatus::Paid));
+}
+
+#[test]
+fn deadline_text_flags_expired_claims() {
+    let mut owed = bond(BondRole::Owed, BondStatus::Claimable);
+    owed.slashed_at = Some(1_700_000_000);
+    owed.claim_window_days = Some(1);
+    let deadline = 1_700_000_000 + 86_400;
+
+    let open = bond_deadline_text(&owed, deadline - 90);
+    assert!(open.contains("1m 30s left"), "{}", open);
+    let expired = bond_deadline_text(&owed, deadline + 1);
+    assert!(expired.ends_with("(expired)"), "{}", expired);
+
+    owed.claim_window_days = None;
+    assert!(bond_deadline_text(&owed, deadline).starts_with("unknown"));
+    assert_eq!(
+        bond_deadline_text(&bond(BondRole::Posted, BondStatus::Locked), deadline),
+        "-"
+    );
+}
+
+#[test]
+fn bonds_table_lists_each_bond() {
+    let mut owed = bond(BondRole::Owed, BondStatus::Claimed);
+    owed.payout_invoice = Some("lnbcrt25u1pjexampleexampleexampleexample".to_string());
+    let table = print_bonds_table(&[bond(BondRole::Posted, BondStatus::Locked), owed], 0);
+    assert!(table.contains(ORDER_ID));
+    assert!(table.contains("locked"));
+    assert!(table.contains("claimed"));
+    assert!(table.contains("lnbcrt25u1pjex…"));
+
+    assert!(print_bonds_table(&[], 0).contains("No Bonds"));
+}
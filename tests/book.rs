@@ -0,0 +1,159 @@
+use mostro_client::fiat::{FiatAmount, FiatRange};
+use mostro_client::nip33::OrderView;
+use mostro_client::parser::book::{book_summary, build_order_book, print_order_book};
+use mostro_client::parser::orders::OrderFilter;
+use mostro_client::price::BtcRates;
+use mostro_core::prelude::*;
+use uuid::Uuid;
+
+fn order(kind: Kind, fiat_code: &str, amount: i64, fiat_amount: i64, premium: i64) -> OrderView {
+    OrderView::from(SmallOrder {
+        id: Some(Uuid::new_v4()),
+        kind: Some(kind),
+        status: Some(Status::Pending),
+        amount,
+        fiat_code: fiat_code.to_string(),
+        fiat_amount,
+        payment_method: "bank transfer".to_string(),
+        premium,
+        created_at: Some(1_700_000_000),
+        ..Default::default()
+    })
+}
+
+fn usd_rates() -> BtcRates {
+    BtcRates {
+        rates: [("USD".to_string(), 50_000.0)].into_iter().collect(),
+        fetched_at: 0,
+    }
+}
+
+#[test]
+fn book_groups_by_currency_and_sorts_sides() {
+    let orders = vec![
+        order(Kind::Sell, "USD", 0, 100, 5),
+        order(Kind::Sell, "USD", 0, 50, -2),
+        // 100 USD for 250k sats: 40000 USD/BTC, 20% under the market
+        order(Kind::Sell, "USD", 250_000, 100, 0),
+        order(Kind::Buy, "USD", 0, 20, 1),
+        order(Kind::Buy, "USD", 0, 30, 3),
+        order(Kind::Sell, "EUR", 0, 10, 0),
+    ];
+    let books = build_order_book(orders, Some(&usd_rates()), &OrderFilter::default());
+    assert_eq!(books.len(), 2);
+    assert_eq!(books[0].fiat_code, "EUR");
+    let usd = &books[1];
+
+    // Asks: cheapest first
+    let ask_premiums: Vec<(i64, i64)> = usd
+        .asks
+        .iter()
+        .map(|e| (e.view.order.amount, e.view.order.premium))
+        .collect();
+    assert_eq!(ask_premiums, vec![(250_000, 0), (0, -2), (0, 5)]);
+    let depths: Vec<String> = usd.asks.iter().map(|e| e.depth.to_string()).collect();
+    assert_eq!(depths, vec!["100", "150", "250"]);
+
+    // Bids: highest paying first
+    assert_eq!(usd.best_bid().unwrap().view.order.premium, 3);
+    assert_eq!(usd.bids[1].depth, FiatAmount::from_whole(50));
+
+    let summary = book_summary(usd).join("\n");
+    assert!(summary.contains("Market: 50000.00 USD"), "{}", summary);
+    assert!(
+        summary.contains("Best ask: 40000.00 USD (-20.0%)"),
+        "{}",
+        summary
+    );
+    assert!(summary.contains("Spread"), "{}", summary);
+}
+
+#[test]
+fn book_without_prices_ranks_market_orders_by_premium() {
+    let orders = vec![
+        order(Kind::Sell, "USD", 250_000, 100, 0),
+        order(Kind::Sell, "USD", 0, 100, 4),
+        order(Kind::Sell, "USD", 0, 100, 1),
+    ];
+    let books = build_order_book(orders, None, &OrderFilter::default());
+    let amounts: Vec<(i64, i64)> = books[0]
+        .asks
+        .iter()
+        .map(|e| (e.view.order.amount, e.view.order.premium))
+        .collect();
+    // Fixed-sats orders can't be compared and go last
+    assert_eq!(amounts, vec![(0, 1), (0, 4), (250_000, 0)]);
+    assert!(book_summary(&books[0])
+        .join("\n")
+        .contains("Best ask: market +1.0%"));
+}
+
+#[test]
+fn book_applies_payment_method_and_amount_filters() {
+    let mut cash = order(Kind::Sell, "USD", 0, 0, 0);
+    cash.order.payment_method = "Cash,Zelle".to_string();
+    cash.order.min_amount = Some(10);
+    cash.order.max_amount = Some(200);
+    let bank = order(Kind::Sell, "USD", 0, 100, 0);
+    let orders = vec![cash, bank];
+
+    let filter = OrderFilter {
+        payment_methods: vec!["zelle".to_string(), "revolut".to_string()],
+        ..Default::default()
+    };
+    let books = build_order_book(orders.clone(), None, &filter);
+    assert_eq!(books[0].asks.len(), 1);
+    assert_eq!(books[0].asks[0].depth, FiatAmount::from_whole(200));
+
+    let filter = OrderFilter {
+        fiat_amount: Some(FiatAmount::from_whole(150)),
+        ..Default::default()
+    };
+    let books = build_order_book(orders.clone(), None, &filter);
+    assert_eq!(books[0].asks.len(), 1);
+    assert_eq!(books[0].asks[0].view.order.payment_method, "Cash,Zelle");
+
+    let filter = OrderFilter {
+        fiat_amount: Some(FiatAmount::from_whole(100)),
+        ..Default::default()
+    };
+    assert_eq!(build_order_book(orders, None, &filter)[0].asks.len(), 2);
+}
+
+#[test]
+fn book_uses_exact_decimal_amounts() {
+    let mut range = order(Kind::Sell, "USD", 0, 0, 0);
+    range.fiat_amount = Some(FiatRange::parse("10.5-20.25").unwrap());
+    let mut fixed = order(Kind::Sell, "USD", 0, 0, 1);
+    fixed.fiat_amount = Some(FiatRange::parse("12.50").unwrap());
+    let books = build_order_book(vec![range, fixed], None, &OrderFilter::default());
+    assert_eq!(books[0].asks[1].depth.to_string(), "32.75");
+
+    let filter = OrderFilter {
+        fiat_amount: Some(FiatAmount::parse("20.5").unwrap()),
+        ..Default::default()
+    };
+    assert!(build_order_book(
+        books[0].asks.iter().map(|e| e.view.clone()).collect(),
+        None,
+        &filter
+    )
+    .is_empty());
+    let out = print_order_book(&books);
+    assert!(out.contains("10.5-20.25"), "{}", out);
+    assert!(out.contains("12.50"), "{}", out);
+}
+
+#[test]
+fn print_order_book_renders_sides_and_empty_book() {
+    let books = build_order_book(
+        vec![order(Kind::Buy, "USD", 0, 100, 0)],
+        Some(&usd_rates()),
+        &OrderFilter::default(),
+    );
+    let out = print_order_book(&books);
+    assert!(out.contains("Asks (sell)"));
+    assert!(out.contains("No orders on this side"));
+    assert!(out.contains("~200000"));
+    assert!(print_order_book(&[]).contains("No pending orders match"));
+}
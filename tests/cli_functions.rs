@@ -302,3 +302,284 @@ fn test_last_trade_index_message() {
     assert!(inner.id.is_none());
     assert!(inner.payload.is_none());
 }
+
+fn new_order(amount: i64, fiat: (i64, Option<i64>, Option<i64>), premium: i64) -> SmallOrder {
+    SmallOrder {
+        kind: Some(mostro_core::order::Kind::Sell),
+        amount,
+        fiat_code: "USD".to_string(),
+        fiat_amount: fiat.0,
+        min_amount: fiat.1,
+        max_amount: fiat.2,
+        payment_method: "cash".to_string(),
+        premium,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_new_order_problems_checks_order_shape() {
+    use mostro_client::cli::new_order::new_order_problems;
+
+    assert!(new_order_problems(&new_order(0, (100, None, None), 2), 0, None, None).is_empty());
+    assert!(
+        new_order_problems(&new_order(0, (0, Some(10), Some(100)), 0), 3, None, None).is_empty()
+    );
+
+    // Inverted range, sats amount on a range order
+    let problems = new_order_problems(&new_order(5000, (0, Some(100), Some(10)), 0), 0, None, None);
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    // Zero fiat amount, premium with a fixed sats amount, negative expiration
+    let problems = new_order_problems(&new_order(5000, (0, None, None), 3), -1, None, None);
+    assert_eq!(problems.len(), 3, "{:?}", problems);
+    // No premium bound without the node's terms
+    assert!(new_order_problems(&new_order(0, (100, None, None), -100), 0, None, None).is_empty());
+}
+
+#[test]
+fn test_new_order_problems_checks_node_terms() {
+    use mostro_client::cli::new_order::new_order_problems;
+    use mostro_client::util::NodeInfo;
+
+    let info = NodeInfo {
+        min_order_amount: Some(1_000),
+        max_order_amount: Some(1_000_000),
+        max_expiration_days: Some(15),
+        max_premium: Some(10),
+        fiat_currencies_accepted: vec!["EUR".to_string(), "USD".to_string()],
+        ..Default::default()
+    };
+    assert!(new_order_problems(
+        &new_order(50_000, (100, None, None), 0),
+        15,
+        Some(&info),
+        None
+    )
+    .is_empty());
+
+    let problems = new_order_problems(&new_order(500, (100, None, None), 0), 30, Some(&info), None);
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].contains("below the node minimum of 1000 sats"));
+    assert!(problems[1].contains("node maximum of 15 days"));
+
+    let mut ars = new_order(2_000_000, (100, None, None), 0);
+    ars.fiat_code = "ARS".to_string();
+    let problems = new_order_problems(&ars, 0, Some(&info), None);
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].contains("does not accept ARS"));
+    assert!(problems[1].contains("above the node maximum"));
+
+    // The premium bound is inclusive, discounts included
+    assert!(
+        new_order_problems(&new_order(0, (100, None, None), -10), 0, Some(&info), None).is_empty()
+    );
+    let problems = new_order_problems(&new_order(0, (100, None, None), 11), 0, Some(&info), None);
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(problems[0].contains("node bound of ±10%"));
+
+    // Market-price orders are estimated at the market price: at 200000
+    // USD/BTC, 100 USD is 50000 sats and 1 USD only 500 sats
+    let market = new_order(0, (100, None, None), 0);
+    assert!(new_order_problems(&market, 0, Some(&info), Some(200_000.0)).is_empty());
+    assert!(new_order_problems(&market, 0, Some(&info), None).is_empty());
+    let tiny = new_order(0, (1, None, None), 0);
+    let problems = new_order_problems(&tiny, 0, Some(&info), Some(200_000.0));
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(
+        problems[0].contains("1 USD (about 500 sats"),
+        "{:?}",
+        problems
+    );
+    assert!(problems[0].contains("below the node minimum"));
+
+    // Both ends of a range are checked
+    let range = new_order(0, (0, Some(1), Some(5_000)), 0);
+    let problems = new_order_problems(&range, 0, Some(&info), Some(200_000.0));
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].contains("1 USD"), "{:?}", problems);
+    assert!(problems[1].contains("5000 USD (about 2500000 sats"));
+    assert!(problems[1].contains("above the node maximum of 1000000 sats"));
+}
+
+#[test]
+fn test_maker_requirements_refuse_unproven_makers() {
+    use mostro_client::cli::take_order::MakerRequirements;
+    use mostro_client::nip33::MakerRating;
+
+    let veteran = MakerRating {
+        total_reviews: 40,
+        total_rating: 4.8,
+        days: 300,
+    };
+    let newcomer = MakerRating {
+        total_reviews: 1,
+        total_rating: 5.0,
+        days: 2,
+    };
+
+    // No requirements: anyone goes, even makers without a rating tag
+    let none = MakerRequirements::default();
+    assert!(none.problems(None).is_empty());
+    assert!(none.problems(Some(&newcomer)).is_empty());
+
+    let strict = MakerRequirements {
+        min_rating: Some(4.5),
+        min_reviews: Some(10),
+    };
+    assert!(strict.problems(Some(&veteran)).is_empty());
+    let problems = strict.problems(Some(&newcomer));
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(problems[0].contains("1 review, fewer than the required 10"));
+    assert!(strict.problems(None)[0].contains("no maker rating"));
+
+    let problems = MakerRequirements {
+        min_rating: Some(4.9),
+        min_reviews: None,
+    }
+    .problems(Some(&veteran));
+    assert!(problems[0].contains("4.8 is below the minimum of 4.9"));
+
+    // Flags override the environment defaults one bound at a time
+    let merged = strict.with_overrides(Some(3.0), None);
+    assert_eq!(merged.min_rating, Some(3.0));
+    assert_eq!(merged.min_reviews, Some(10));
+}
+
+#[test]
+fn test_min_rating_flags_must_be_between_0_and_5() {
+    use clap::Parser;
+    use mostro_client::cli::Cli;
+
+    let order_id = Uuid::new_v4().to_string();
+    let parse = |args: &[&str]| Cli::try_parse_from([&["mostro-cli"], args].concat());
+    for rating in ["-1", "5.5", "NaN", "inf"] {
+        for args in [
+            vec!["listorders", "--minrating", rating],
+            vec!["takesell", "-o", &order_id, "--minrating", rating],
+            vec!["takebuy", "-o", &order_id, "--minrating", rating],
+        ] {
+            assert!(parse(&args).is_err(), "{:?} was accepted", args);
+        }
+    }
+    assert!(parse(&["listorders", "--minrating", "4.5"]).is_ok());
+    assert!(parse(&["takebuy", "-o", &order_id, "--minrating", "0"]).is_ok());
+    assert!(parse(&["takesell", "-o", &order_id, "--minrating", "5"]).is_ok());
+}
+
+#[test]
+fn test_new_order_confirmation() {
+    use mostro_client::cli::new_order::check_confirmation;
+    use mostro_client::error::NewOrderError;
+
+    assert_eq!(check_confirmation(Some("\n")), Ok(()));
+    assert_eq!(check_confirmation(Some("Y\n")), Ok(()));
+    assert_eq!(check_confirmation(Some("yes\n")), Ok(()));
+    assert_eq!(
+        check_confirmation(Some("n\n")),
+        Err(NewOrderError::Declined)
+    );
+    assert_eq!(
+        check_confirmation(Some("No\n")),
+        Err(NewOrderError::Declined)
+    );
+    assert_eq!(
+        check_confirmation(Some("maybe\n")),
+        Err(NewOrderError::UnrecognizedAnswer("maybe".to_string()))
+    );
+    // A closed stdin is not a yes: scripts must pass --yes
+    assert_eq!(
+        check_confirmation(None),
+        Err(NewOrderError::ConfirmationRequired)
+    );
+}
+
+#[test]
+fn test_wait_for_action_names() {
+    use mostro_client::cli::wait_for::parse_action;
+
+    assert_eq!(
+        parse_action("hold-invoice-payment-accepted").unwrap(),
+        Action::HoldInvoicePaymentAccepted
+    );
+    assert_eq!(
+        parse_action("HoldInvoicePaymentAccepted").unwrap(),
+        Action::HoldInvoicePaymentAccepted
+    );
+    assert_eq!(parse_action(" fiat_sent_ok ").unwrap(), Action::FiatSentOk);
+    assert_eq!(parse_action("cant-do").unwrap(), Action::CantDo);
+    assert!(parse_action("hold-invoice").is_err());
+}
+
+#[test]
+fn test_wait_for_outcome() {
+    use mostro_client::cli::wait_for::wait_outcome;
+    use mostro_client::error::WaitForError;
+
+    let order_id = Uuid::new_v4();
+    let awaited = [Action::HoldInvoicePaymentAccepted, Action::Canceled];
+    let message = |id, action, payload| MessageKind::new(Some(id), None, None, action, payload);
+
+    let accepted = message(order_id, Action::HoldInvoicePaymentAccepted, None);
+    assert_eq!(wait_outcome(&accepted, &order_id, &awaited), Some(Ok(())));
+    // Other orders and other actions keep waiting
+    let other_order = message(Uuid::new_v4(), Action::Canceled, None);
+    assert_eq!(wait_outcome(&other_order, &order_id, &awaited), None);
+    let other_action = message(order_id, Action::BuyerTookOrder, None);
+    assert_eq!(wait_outcome(&other_action, &order_id, &awaited), None);
+
+    let reason = Some(CantDoReason::NotAllowedByStatus);
+    let cant_do = message(
+        order_id,
+        Action::CantDo,
+        Some(Payload::CantDo(reason.clone())),
+    );
+    assert_eq!(
+        wait_outcome(&cant_do, &order_id, &awaited),
+        Some(Err(WaitForError::CantDo(reason)))
+    );
+    assert_eq!(WaitForError::Timeout(30).exit_code(), 2);
+    // ... unless cant-do is what the script waits for
+    assert_eq!(
+        wait_outcome(&cant_do, &order_id, &[Action::CantDo]),
+        Some(Ok(()))
+    );
+}
+
+#[test]
+fn test_wait_for_lookback_prefers_the_awaited_action() {
+    use mostro_client::cli::wait_for::lookback_outcome;
+    use mostro_client::error::WaitForError;
+
+    let order_id = Uuid::new_v4();
+    let awaited = [Action::HoldInvoicePaymentAccepted];
+    let message = |action, payload| Message::new_order(Some(order_id), None, None, action, payload);
+    let cant_do = |reason| message(Action::CantDo, Some(Payload::CantDo(Some(reason))));
+
+    // An old cant-do doesn't hide the awaited action that followed it
+    let history = [
+        cant_do(CantDoReason::InvalidInvoice),
+        message(Action::BuyerTookOrder, None),
+        message(Action::HoldInvoicePaymentAccepted, None),
+    ];
+    let (found, outcome) = lookback_outcome(&history, &order_id, &awaited).unwrap();
+    assert_eq!(
+        found.get_inner_message_kind().action,
+        Action::HoldInvoicePaymentAccepted
+    );
+    assert_eq!(outcome, Ok(()));
+
+    // Without it, the latest cant-do ends the wait
+    let refused = [
+        cant_do(CantDoReason::InvalidInvoice),
+        cant_do(CantDoReason::NotAllowedByStatus),
+    ];
+    let (_, outcome) = lookback_outcome(&refused, &order_id, &awaited).unwrap();
+    assert_eq!(
+        outcome,
+        Err(WaitForError::CantDo(Some(CantDoReason::NotAllowedByStatus)))
+    );
+
+    let unrelated = [message(Action::BuyerTookOrder, None)];
+    assert!(lookback_outcome(&unrelated, &order_id, &awaited).is_none());
+    assert!(lookback_outcome(&[], &order_id, &awaited).is_none());
+}
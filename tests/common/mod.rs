@@ -0,0 +1,39 @@
+//! Fixtures shared by the integration tests.
+
+use mostro_client::nip33::{order_view_from_tags, OrderView};
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::{Tag, TagKind, Tags};
+use uuid::Uuid;
+
+/// A pending order as parsed from its event: `fiat` is the `fa` amount or
+/// `min-max` range, and the maker is rated 4.5 from 10 reviews over 100 days.
+pub fn order_view(
+    kind: Kind,
+    fiat_code: &str,
+    fiat: &str,
+    premium: i64,
+    methods: &str,
+    created_at: i64,
+) -> OrderView {
+    let tag = |name: &str, values: Vec<String>| {
+        Tag::custom(TagKind::Custom(name.to_string().into()), values)
+    };
+    let fa = fiat.split('-').map(str::to_string).collect();
+    let tags = Tags::from_list(vec![
+        tag("d", vec![Uuid::new_v4().to_string()]),
+        tag("k", vec![kind.to_string()]),
+        tag("f", vec![fiat_code.to_string()]),
+        tag("s", vec!["pending".to_string()]),
+        tag("amt", vec!["0".to_string()]),
+        tag("fa", fa),
+        tag("pm", vec![methods.to_string()]),
+        tag("premium", vec![premium.to_string()]),
+        tag(
+            "rating",
+            vec![r#"{"total_reviews":10,"total_rating":4.5,"days":100}"#.to_string()],
+        ),
+    ]);
+    let mut view = order_view_from_tags(tags).unwrap();
+    view.order.created_at = Some(created_at);
+    view
+}
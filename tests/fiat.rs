@@ -0,0 +1,102 @@
+use mostro_client::fiat::{currency_decimals, FiatAmount, FiatRange};
+
+#[test]
+fn currency_decimals_follow_iso_4217() {
+    assert_eq!(currency_decimals("usd"), 2);
+    assert_eq!(currency_decimals("JPY"), 0);
+    assert_eq!(currency_decimals("KWD"), 3);
+    assert_eq!(currency_decimals("CLF"), 4);
+    // Unknown codes get the common two decimals
+    assert_eq!(currency_decimals("SAT"), 2);
+}
+
+#[test]
+fn fiat_amount_parses_and_displays_exactly() {
+    let amount = FiatAmount::parse("12.50").unwrap();
+    assert_eq!(amount.to_string(), "12.50");
+    assert_eq!(amount.decimals(), 2);
+    assert_eq!(amount.significant_decimals(), 1);
+    assert_eq!(amount.to_f64(), 12.5);
+    assert_eq!(amount.whole(), None);
+    assert_eq!(
+        (amount.floor(), amount.round(), amount.ceil()),
+        (12, 13, 13)
+    );
+    assert_eq!(amount, FiatAmount::parse("12.5").unwrap());
+    assert!(amount < FiatAmount::parse("12.51").unwrap());
+
+    let whole = FiatAmount::parse("100.00").unwrap();
+    assert_eq!(whole.whole(), Some(100));
+    assert_eq!(whole, FiatAmount::from_whole(100));
+
+    for bad in ["", "1.", ".5", "1.2.3", "-1", "1e3", "abc", "0.123456789"] {
+        assert!(FiatAmount::parse(bad).is_err(), "{bad:?} should not parse");
+    }
+}
+
+#[test]
+fn fiat_amount_respects_currency_minor_units() {
+    let cents = FiatAmount::parse("12.50").unwrap();
+    assert!(cents.check_currency("EUR").is_ok());
+    assert!(cents.check_currency("JPY").is_err());
+    // Trailing zeros are not real decimals
+    assert!(FiatAmount::parse("1000.00")
+        .unwrap()
+        .check_currency("JPY")
+        .is_ok());
+    assert!(FiatAmount::parse("1.125")
+        .unwrap()
+        .check_currency("USD")
+        .is_err());
+    assert!(FiatAmount::parse("1.125")
+        .unwrap()
+        .check_currency("KWD")
+        .is_ok());
+}
+
+#[test]
+fn fiat_range_parses_checks_and_converts() {
+    let range = FiatRange::parse("10.5-20").unwrap();
+    assert_eq!(range.to_string(), "10.5-20");
+    assert!(range.contains(FiatAmount::parse("15").unwrap()));
+    assert!(!range.contains(FiatAmount::parse("10").unwrap()));
+
+    for bad in ["20-10", "10-10.0", "1-2-3", "10-", "x-20"] {
+        assert!(FiatRange::parse(bad).is_err(), "{bad:?} should not parse");
+    }
+
+    // The protocol only carries whole fiat units
+    assert_eq!(
+        FiatRange::parse("100-200")
+            .unwrap()
+            .to_whole_units("USD")
+            .unwrap(),
+        (100, Some(200))
+    );
+    assert_eq!(
+        FiatRange::parse("50.00")
+            .unwrap()
+            .to_whole_units("USD")
+            .unwrap(),
+        (50, None)
+    );
+    let err = FiatRange::parse("12.50")
+        .unwrap()
+        .to_whole_units("EUR")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("use 12 or 13"), "{err}");
+}
+
+#[test]
+fn fiat_amounts_add_and_subtract_exactly() {
+    let a = FiatAmount::parse("12.5").unwrap();
+    let b = FiatAmount::parse("0.75").unwrap();
+    assert_eq!(a.saturating_add(b).to_string(), "13.25");
+    assert_eq!(a.saturating_sub(b).to_string(), "11.75");
+    assert_eq!(b.saturating_sub(a), FiatAmount::default());
+    assert_eq!(
+        FiatAmount::from_whole(300).saturating_sub(FiatAmount::from_whole(250)),
+        FiatAmount::from_whole(50)
+    );
+}
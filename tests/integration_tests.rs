@@ -25,6 +25,8 @@ async fn create_test_context() -> anyhow::Result<Context> {
         pool,
         context_keys: Some(context_keys),
         mostro_pubkey,
+        node_info: Default::default(),
+        qr: Default::default(),
     })
 }
 
@@ -0,0 +1,300 @@
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::secp256k1::{Secp256k1, SecretKey};
+use lightning_invoice::{Currency, InvoiceBuilder, PaymentSecret};
+use lnurl::pay::PayResponse;
+use mostro_client::cli::decode_invoice::order_invoice_checks;
+use mostro_client::db::Order;
+use mostro_client::error::MostroError;
+use mostro_client::lightning::lnurl_pay::{
+    check_pay_response, parse_pay_response, LnurlDestination,
+};
+use mostro_client::lightning::{
+    decode_invoice, expected_buyer_amount, mostro_fee_share, network_name, parse_network,
+    resolve_payment_request, validate_invoice, InvoiceRequirements, LnurlOptions,
+};
+use std::time::Duration;
+
+fn build_invoice(currency: Currency, amount_sats: Option<u64>, expiry_secs: u64) -> String {
+    let private_key = SecretKey::from_slice(&[42; 32]).unwrap();
+    let builder = InvoiceBuilder::new(currency)
+        .description("mostro test".into())
+        .payment_hash(sha256::Hash::from_slice(&[7; 32]).unwrap())
+        .payment_secret(PaymentSecret([9; 32]))
+        .current_timestamp()
+        .min_final_cltv_expiry_delta(144)
+        .expiry_time(Duration::from_secs(expiry_secs));
+    let signer = |hash: &bitcoin::secp256k1::Message| {
+        Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key)
+    };
+    match amount_sats {
+        Some(sats) => builder
+            .amount_milli_satoshis(sats * 1000)
+            .build_signed(signer)
+            .unwrap()
+            .to_string(),
+        None => builder.build_signed(signer).unwrap().to_string(),
+    }
+}
+
+fn mainnet_requirements() -> InvoiceRequirements {
+    InvoiceRequirements {
+        amount_sats: None,
+        min_amount_sats: Some(100),
+        min_expiry_secs: Some(600),
+        networks: vec![Currency::Bitcoin],
+    }
+}
+
+#[test]
+fn validate_invoice_accepts_matching_invoice() {
+    let invoice = build_invoice(Currency::Bitcoin, Some(9_970), 3600);
+    let requirements = mainnet_requirements().with_amount(Some(9_970));
+    assert!(validate_invoice(&invoice, &requirements).is_ok());
+}
+
+#[test]
+fn validate_invoice_accepts_amountless_invoice() {
+    let invoice = build_invoice(Currency::Bitcoin, None, 3600);
+    let requirements = mainnet_requirements().with_amount(Some(9_970));
+    assert!(validate_invoice(&invoice, &requirements).is_ok());
+}
+
+#[test]
+fn validate_invoice_rejects_wrong_amount() {
+    let invoice = build_invoice(Currency::Bitcoin, Some(10_000), 3600);
+    let requirements = mainnet_requirements().with_amount(Some(9_970));
+    assert!(matches!(
+        validate_invoice(&invoice, &requirements),
+        Err(MostroError::WrongAmountError)
+    ));
+}
+
+#[test]
+fn validate_invoice_rejects_amount_below_node_minimum() {
+    let invoice = build_invoice(Currency::Bitcoin, Some(50), 3600);
+    assert!(matches!(
+        validate_invoice(&invoice, &mainnet_requirements()),
+        Err(MostroError::MinAmountError)
+    ));
+}
+
+#[test]
+fn validate_invoice_rejects_short_expiry() {
+    let invoice = build_invoice(Currency::Bitcoin, Some(1_000), 60);
+    assert!(matches!(
+        validate_invoice(&invoice, &mainnet_requirements()),
+        Err(MostroError::MinExpirationTimeError)
+    ));
+}
+
+#[test]
+fn validate_invoice_rejects_wrong_network() {
+    let invoice = build_invoice(Currency::Regtest, Some(1_000), 3600);
+    assert!(matches!(
+        validate_invoice(&invoice, &mainnet_requirements()),
+        Err(MostroError::WrongNetworkError)
+    ));
+}
+
+#[test]
+fn validate_invoice_without_requirements_only_parses() {
+    let invoice = build_invoice(Currency::Signet, Some(1), 30);
+    assert!(validate_invoice(&invoice, &InvoiceRequirements::default()).is_ok());
+    assert!(matches!(
+        validate_invoice("lnbc1notaninvoice", &InvoiceRequirements::default()),
+        Err(MostroError::ParsingInvoiceError)
+    ));
+}
+
+#[test]
+fn parse_network_maps_node_names() {
+    assert_eq!(parse_network("mainnet"), Some(Currency::Bitcoin));
+    assert_eq!(parse_network(" testnet "), Some(Currency::BitcoinTestnet));
+    assert_eq!(parse_network("signet"), Some(Currency::Signet));
+    assert_eq!(parse_network("REGTEST"), Some(Currency::Regtest));
+    assert_eq!(parse_network("liquid"), None);
+}
+
+#[test]
+fn buyer_amount_deducts_half_the_fee() {
+    // 0.6% fee split between both parties: the buyer pays 0.3%.
+    assert_eq!(mostro_fee_share(100_000, 0.006), 300);
+    assert_eq!(expected_buyer_amount(100_000, 0.006), 99_700);
+    assert_eq!(expected_buyer_amount(100_000, 0.0), 100_000);
+}
+
+fn pay_response(min_msats: u64, max_msats: u64, comment_allowed: Option<u32>) -> PayResponse {
+    let mut json = serde_json::json!({
+        "tag": "payRequest",
+        "callback": "https://example.com/lnurlp/alice/callback",
+        "minSendable": min_msats,
+        "maxSendable": max_msats,
+        "metadata": "[[\"text/plain\",\"Pay alice\"],[\"text/identifier\",\"alice@example.com\"]]",
+    });
+    if let Some(max) = comment_allowed {
+        json["commentAllowed"] = max.into();
+    }
+    parse_pay_response(json).unwrap()
+}
+
+#[test]
+fn lnurl_destination_parses_addresses_and_lnurls() {
+    let address = LnurlDestination::parse("alice@example.com").unwrap();
+    assert_eq!(
+        address.url(),
+        "https://example.com/.well-known/lnurlp/alice"
+    );
+    assert_eq!(
+        address.lightning_address().as_deref(),
+        Some("alice@example.com")
+    );
+
+    let prefixed = LnurlDestination::parse("lightning:alice@example.com").unwrap();
+    assert_eq!(prefixed, address);
+
+    let lnurl = LnurlDestination::parse(
+        "LNURL1DP68GURN8GHJ7UM9WFMXJCM99E3K7MF0V9CXJ0M385EKVCENXC6R2C35XVUKXEFCV5MKVV34X5EKZD3EV56NYD3HXQURZEPEXEJXXEPNXSCRVWFNV9NXZCN9XQ6XYEFHVGCXXCMYXYMNSERXFQ5FNS",
+    )
+    .unwrap();
+    assert!(lnurl.url().starts_with("https://service.com/api?q="));
+    // A plain LNURL has no address form Mostro could resolve by itself.
+    assert_eq!(lnurl.lightning_address(), None);
+
+    let invoice = build_invoice(Currency::Bitcoin, Some(1_000), 3600);
+    assert_eq!(LnurlDestination::parse(&invoice), None);
+}
+
+#[test]
+fn check_pay_response_enforces_sendable_range() {
+    let pay = pay_response(1_000, 50_000_000, None);
+    assert!(check_pay_response(&pay, Some(10_000), None).is_ok());
+    assert!(check_pay_response(&pay, None, None).is_ok());
+    assert!(check_pay_response(&pay, Some(60_000), None).is_err());
+    assert!(check_pay_response(&pay_response(0, 1_000, None), None, None).is_err());
+    assert!(check_pay_response(&pay_response(5_000, 1_000, None), None, None).is_err());
+}
+
+#[test]
+fn check_pay_response_enforces_comment_length() {
+    let pay = pay_response(1_000, 50_000_000, Some(10));
+    assert!(check_pay_response(&pay, None, Some("mostro")).is_ok());
+    assert!(check_pay_response(&pay, None, Some("a comment that is too long")).is_err());
+
+    let no_comments = pay_response(1_000, 50_000_000, None);
+    assert!(check_pay_response(&no_comments, None, Some("mostro")).is_err());
+    assert!(check_pay_response(&no_comments, None, Some("")).is_ok());
+}
+
+#[test]
+fn check_pay_response_requires_plain_text_metadata() {
+    let mut pay = pay_response(1_000, 50_000_000, None);
+    pay.metadata = "[[\"text/identifier\",\"alice@example.com\"]]".to_string();
+    assert!(check_pay_response(&pay, None, None).is_err());
+    pay.metadata = "not json".to_string();
+    assert!(check_pay_response(&pay, None, None).is_err());
+}
+
+#[test]
+fn parse_pay_response_rejects_service_errors() {
+    let error = serde_json::json!({ "status": "ERROR", "reason": "Unknown user" });
+    let err = parse_pay_response(error).unwrap_err();
+    assert!(err.to_string().contains("Unknown user"));
+
+    let withdraw = serde_json::json!({ "tag": "withdrawRequest" });
+    assert!(parse_pay_response(withdraw).is_err());
+}
+
+#[tokio::test]
+async fn resolve_payment_request_validates_bolt11_locally() {
+    let invoice = build_invoice(Currency::Bitcoin, Some(9_970), 3600);
+    let requirements = mainnet_requirements().with_amount(Some(9_970));
+    let resolved = resolve_payment_request(&invoice, &requirements, &LnurlOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(resolved, invoice);
+
+    let wrong = mainnet_requirements().with_amount(Some(1_000));
+    let err = resolve_payment_request(&invoice, &wrong, &LnurlOptions::default())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().starts_with("Invalid invoice"));
+}
+
+#[test]
+fn decode_invoice_breaks_down_bolt11() {
+    let invoice = build_invoice(Currency::Signet, Some(25_000), 3600);
+    let decoded = decode_invoice(&invoice).unwrap();
+    assert_eq!(decoded.network, Currency::Signet);
+    assert_eq!(network_name(&decoded.network), "signet");
+    assert_eq!(decoded.amount_sats(), Some(25_000));
+    assert_eq!(decoded.description.as_deref(), Some("mostro test"));
+    assert_eq!(decoded.payment_hash, "07".repeat(32));
+    assert_eq!(decoded.expiry_secs, 3600);
+    assert_eq!(decoded.min_final_cltv_expiry_delta, 144);
+    assert!(!decoded.is_expired());
+    assert!(decoded.route_hints.is_empty());
+
+    let secp = Secp256k1::new();
+    let payee = SecretKey::from_slice(&[42; 32])
+        .unwrap()
+        .public_key(&secp)
+        .to_string();
+    assert_eq!(decoded.payee, payee);
+}
+
+#[test]
+fn decode_invoice_keeps_expired_invoices() {
+    let invoice = build_invoice(Currency::Bitcoin, Some(1_000), 0);
+    let decoded = decode_invoice(&invoice).unwrap();
+    assert!(decoded.is_expired());
+    assert!(decode_invoice("not an invoice").is_err());
+}
+
+fn local_order(amount: i64) -> Order {
+    Order {
+        id: Some(uuid::Uuid::new_v4().to_string()),
+        kind: Some("sell".to_string()),
+        status: Some("active".to_string()),
+        amount,
+        fiat_code: "USD".to_string(),
+        min_amount: None,
+        max_amount: None,
+        fiat_amount: 100,
+        payment_method: "cash".to_string(),
+        premium: 0,
+        trade_keys: None,
+        counterparty_pubkey: None,
+        is_mine: Some(true),
+        buyer_invoice: None,
+        request_id: None,
+        created_at: None,
+        expires_at: None,
+        fiat_range: None,
+    }
+}
+
+#[test]
+fn order_invoice_checks_match_fee_adjusted_amounts() {
+    let order = local_order(100_000);
+    let networks = [Currency::Bitcoin];
+    for sats in [100_000, 99_700, 100_300] {
+        let decoded = decode_invoice(&build_invoice(Currency::Bitcoin, Some(sats), 3600)).unwrap();
+        let checks = order_invoice_checks(&decoded, &order, Some(0.006), &networks);
+        assert!(checks.iter().all(|c| c.passed), "{sats}: {checks:?}");
+    }
+
+    let decoded = decode_invoice(&build_invoice(Currency::Bitcoin, Some(99_700), 3600)).unwrap();
+    let checks = order_invoice_checks(&decoded, &order, None, &networks);
+    assert!(checks.iter().any(|c| !c.passed));
+}
+
+#[test]
+fn order_invoice_checks_flag_network_and_expiry() {
+    let order = local_order(100_000);
+    let decoded = decode_invoice(&build_invoice(Currency::Regtest, Some(100_000), 0)).unwrap();
+    let failed: Vec<_> = order_invoice_checks(&decoded, &order, None, &[Currency::Bitcoin])
+        .into_iter()
+        .filter(|c| !c.passed)
+        .collect();
+    assert_eq!(failed.len(), 2);
+}
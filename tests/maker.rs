@@ -0,0 +1,113 @@
+use mostro_client::db::{MakerOffer, MakerOrder};
+use mostro_client::fiat::FiatRange;
+use mostro_client::maker::{needs_reprice, new_maker_offer, offer_order, replacement_reason};
+use mostro_client::parser::order_feed::OrderChangeKind;
+
+fn offer(kind: &str, fiat: &str, fixed_price: bool, threshold: Option<f64>) -> MakerOffer {
+    new_maker_offer(
+        "desk",
+        kind,
+        "usd",
+        &FiatRange::parse(fiat).unwrap(),
+        "zelle",
+        2,
+        fixed_price,
+        threshold,
+        1,
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_new_maker_offer_validation() {
+    let range = FiatRange::parse("50-500").unwrap();
+    let single = FiatRange::parse("100").unwrap();
+    let build = |kind: &str,
+                 fiat: &FiatRange,
+                 method: &str,
+                 fixed: bool,
+                 threshold: Option<f64>,
+                 invoice: Option<&str>| {
+        new_maker_offer(
+            "desk", kind, "USD", fiat, method, 2, fixed, threshold, 1, invoice,
+        )
+    };
+
+    let saved = offer("SELL", "50-500", false, None);
+    assert_eq!(saved.kind, "sell");
+    assert_eq!(saved.fiat_code, "USD");
+    assert_eq!(saved.fiat_amount, "50-500");
+
+    assert!(build("swap", &single, "zelle", false, None, None).is_err());
+    assert!(build("sell", &single, "  ", false, None, None).is_err());
+    // Range orders can't carry a fixed sats amount
+    assert!(build("sell", &range, "zelle", true, None, None).is_err());
+    // Repricing is only for fixed-price offers, by a positive percentage
+    assert!(build("sell", &single, "zelle", false, Some(1.0), None).is_err());
+    assert!(build("sell", &single, "zelle", true, Some(0.0), None).is_err());
+    assert!(build("sell", &single, "zelle", true, Some(1.5), None).is_ok());
+    // Payout only on buy offers, and never a single-use bolt11
+    assert!(build(
+        "sell",
+        &single,
+        "zelle",
+        false,
+        None,
+        Some("me@example.com")
+    )
+    .is_err());
+    assert!(build("buy", &single, "zelle", false, None, Some("lnbc1invoice")).is_err());
+    assert!(build("buy", &single, "zelle", false, None, Some("me@example.com")).is_ok());
+}
+
+#[test]
+fn test_offer_order_terms() {
+    let market = offer("sell", "50-500", false, None);
+    let terms = offer_order(&market, None).unwrap();
+    assert_eq!(terms.amount, 0);
+    assert_eq!(terms.premium, 2);
+    assert_eq!(terms.reference_price, None);
+
+    let fixed = offer("sell", "100", true, Some(3.0));
+    assert!(offer_order(&fixed, None).is_err());
+    let terms = offer_order(&fixed, Some(50_000.0)).unwrap();
+    // 100 USD at 50,000 USD/BTC is 200,000 sats, 2% fewer with the premium
+    assert_eq!(terms.amount, 196_000);
+    assert_eq!(terms.premium, 0);
+    assert_eq!(terms.reference_price, Some(50_000.0));
+}
+
+#[test]
+fn test_needs_reprice() {
+    let fixed = offer("sell", "100", true, Some(3.0));
+    let order = MakerOrder {
+        reference_price: Some(50_000.0),
+        ..Default::default()
+    };
+    assert!(!needs_reprice(&fixed, &order, 51_000.0));
+    assert!(needs_reprice(&fixed, &order, 51_500.0));
+    assert!(needs_reprice(&fixed, &order, 48_000.0));
+
+    let market = offer("sell", "100", false, None);
+    assert!(!needs_reprice(&market, &order, 90_000.0));
+}
+
+#[test]
+fn test_replacement_reason() {
+    assert_eq!(replacement_reason(OrderChangeKind::New), None);
+    assert_eq!(replacement_reason(OrderChangeKind::Updated), None);
+    assert_eq!(replacement_reason(OrderChangeKind::Taken), Some("taken"));
+    assert_eq!(
+        replacement_reason(OrderChangeKind::Canceled),
+        Some("canceled")
+    );
+    assert_eq!(
+        replacement_reason(OrderChangeKind::Expired),
+        Some("expired")
+    );
+    assert_eq!(
+        replacement_reason(OrderChangeKind::Removed),
+        Some("removed")
+    );
+}
@@ -0,0 +1,129 @@
+use mostro_client::parser::order_feed::{order_change_line, OrderChangeKind, OrderFeed};
+use mostro_client::parser::orders::OrderFilter;
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::*;
+use uuid::Uuid;
+
+const NOW: i64 = 1_800_000_000;
+
+fn order_event(
+    id: Uuid,
+    status: &str,
+    premium: i64,
+    created_at: i64,
+    expires_at: Option<i64>,
+) -> nostr_sdk::Event {
+    let tag =
+        |name: &str, value: String| Tag::custom(TagKind::Custom(name.to_string().into()), [value]);
+    let mut tags = vec![
+        tag("d", id.to_string()),
+        tag("k", "sell".to_string()),
+        tag("f", "EUR".to_string()),
+        tag("s", status.to_string()),
+        tag("amt", "0".to_string()),
+        tag("fa", "50".to_string()),
+        tag("pm", "SEPA".to_string()),
+        tag("premium", premium.to_string()),
+    ];
+    if let Some(at) = expires_at {
+        tags.push(tag("expires_at", at.to_string()));
+    }
+    EventBuilder::new(nostr_sdk::Kind::TextNote, "")
+        .tags(tags)
+        .custom_created_at(Timestamp::from(created_at as u64))
+        .sign_with_keys(&Keys::generate())
+        .unwrap()
+}
+
+fn pending_feed(filter: OrderFilter) -> OrderFeed {
+    OrderFeed::new(Some("EUR".to_string()), Some(Status::Pending), None, filter)
+}
+
+#[test]
+fn feed_reports_new_taken_and_canceled_orders() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut feed = pending_feed(OrderFilter::default());
+    let mut snapshot = Events::new(&Filter::new());
+    snapshot.insert(order_event(a, "pending", 0, NOW - 60, None));
+    feed.seed(&snapshot, NOW);
+    assert_eq!(feed.listed().len(), 1);
+
+    // A revision older than the one seen is ignored
+    assert!(feed
+        .apply(&order_event(a, "canceled", 0, NOW - 120, None), NOW)
+        .is_none());
+
+    let change = feed
+        .apply(&order_event(a, "in-progress", 0, NOW, None), NOW)
+        .unwrap();
+    assert_eq!(change.kind, OrderChangeKind::Taken);
+    assert!(feed.listed().is_empty());
+
+    let change = feed
+        .apply(&order_event(b, "pending", 1, NOW, None), NOW)
+        .unwrap();
+    assert_eq!(change.kind, OrderChangeKind::New);
+    let line = order_change_line(&change, NOW);
+    assert!(line.contains("NEW sell 50 EUR @ +1% via SEPA"), "{line}");
+    assert!(line.contains(&b.to_string()), "{line}");
+
+    let change = feed
+        .apply(&order_event(b, "pending", 2, NOW + 1, None), NOW)
+        .unwrap();
+    assert_eq!(change.kind, OrderChangeKind::Updated);
+    assert_eq!(change.view.order.premium, 2);
+
+    let change = feed
+        .apply(&order_event(b, "canceled", 2, NOW + 2, None), NOW)
+        .unwrap();
+    assert_eq!(change.kind, OrderChangeKind::Canceled);
+    // Already gone: further revisions are not news
+    assert!(feed
+        .apply(&order_event(b, "canceled", 2, NOW + 3, None), NOW)
+        .is_none());
+}
+
+#[test]
+fn feed_applies_filters_and_expiry() {
+    let filter = OrderFilter {
+        max_premium: Some(2),
+        ..Default::default()
+    };
+    let mut feed = pending_feed(filter);
+    let (cheap, pricey, expiring) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+    assert!(feed
+        .apply(&order_event(pricey, "pending", 5, NOW, None), NOW)
+        .is_none());
+    // Repriced into the filter: it appears
+    let change = feed
+        .apply(&order_event(pricey, "pending", 1, NOW + 1, None), NOW)
+        .unwrap();
+    assert_eq!(change.kind, OrderChangeKind::New);
+    // ...and out again, still pending
+    let change = feed
+        .apply(&order_event(pricey, "pending", 9, NOW + 2, None), NOW)
+        .unwrap();
+    assert_eq!(change.kind, OrderChangeKind::Removed);
+
+    feed.apply(&order_event(cheap, "pending", 0, NOW, None), NOW);
+    feed.apply(
+        &order_event(expiring, "pending", 0, NOW, Some(NOW + 60)),
+        NOW,
+    );
+    assert_eq!(feed.listed().len(), 2);
+    assert!(feed.expire(NOW + 30).is_empty());
+    let expired = feed.expire(NOW + 60);
+    assert_eq!(expired.len(), 1);
+    assert_eq!(expired[0].kind, OrderChangeKind::Expired);
+    assert_eq!(expired[0].view.order.id, Some(expiring));
+    assert_eq!(feed.listed().len(), 1);
+
+    // Mostro's own expired revision arriving later is not reported twice
+    assert!(feed
+        .apply(
+            &order_event(expiring, "expired", 0, NOW + 90, None),
+            NOW + 90
+        )
+        .is_none());
+}
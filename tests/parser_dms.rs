@@ -1,4 +1,6 @@
+use mostro_client::db::{MessageDirection, ProtocolMessage};
 use mostro_client::parser::dms::{parse_dm_events, print_direct_messages};
+use mostro_client::parser::timeline::print_timeline_table;
 use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
 
@@ -6,7 +8,7 @@ use nostr_sdk::prelude::*;
 async fn parse_dm_empty() {
     let keys = Keys::generate();
     let events = Events::new(&Filter::new());
-    let out = parse_dm_events(events, &keys, None, true).await;
+    let out = parse_dm_events(events, &keys, None, true, None).await;
     assert!(out.is_empty());
 }
 
@@ -224,7 +226,7 @@ async fn parse_dm_with_time_filter() {
     let keys = Keys::generate();
     let events = Events::new(&Filter::new());
     let since = 1700000000i64;
-    let out = parse_dm_events(events, &keys, Some(&since), true).await;
+    let out = parse_dm_events(events, &keys, Some(&since), true, None).await;
     assert!(out.is_empty());
 }
 
@@ -258,7 +260,7 @@ async fn parse_dm_events_accepts_wrap_message_output() {
     let mut events = Events::new(&Filter::new());
     events.insert(wrapped);
 
-    let parsed = parse_dm_events(events, &receiver_keys, None, true).await;
+    let parsed = parse_dm_events(events, &receiver_keys, None, true, None).await;
     assert_eq!(parsed.len(), 1);
     let (message, _, sender) = &parsed[0];
     assert_eq!(sender, &sender_trade_keys.public_key());
@@ -291,7 +293,7 @@ async fn parse_dm_events_skips_events_for_other_keys() {
     let mut events = Events::new(&Filter::new());
     events.insert(wrapped);
 
-    let parsed = parse_dm_events(events, &eavesdropper, None, true).await;
+    let parsed = parse_dm_events(events, &eavesdropper, None, true, None).await;
     assert!(parsed.is_empty());
 }
 
@@ -418,3 +420,29 @@ async fn print_dms_with_invalid_timestamp() {
     let res = print_direct_messages(&msgs, None, None).await;
     assert!(res.is_ok());
 }
+
+#[test]
+fn timeline_table() {
+    assert!(print_timeline_table(&[]).contains("No messages recorded"));
+
+    let order_id = uuid::Uuid::new_v4();
+    let message = Message::new_order(
+        Some(order_id),
+        Some(42),
+        Some(5),
+        Action::AddInvoice,
+        Some(Payload::PaymentRequest(
+            None,
+            "lnbc1timeline".to_string(),
+            None,
+        )),
+    );
+    let record =
+        ProtocolMessage::new(&EventId::all_zeros(), MessageDirection::Sent, &message, 0).unwrap();
+    assert_eq!(record.order_id, Some(order_id.to_string()));
+    let table = print_timeline_table(&[record]);
+    assert!(table.contains("add-invoice"));
+    assert!(table.contains("📤 sent"));
+    assert!(table.contains("42"));
+    assert!(table.contains("lnbc1timeline"));
+}
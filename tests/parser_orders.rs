@@ -1,4 +1,8 @@
-use mostro_client::parser::orders::{parse_orders_events, print_orders_table};
+use mostro_client::nip33::{maker_rating_from_tags, order_view_from_tags, MakerRating, OrderView};
+use mostro_client::parser::orders::{
+    expires_text, maker_rating_text, parse_order_views, parse_orders_events, print_order_detail,
+    print_order_preview, print_orders_table, sort_orders, OrderFilter, OrderSort,
+};
 use mostro_core::prelude::*;
 use nostr_sdk::prelude::*;
 
@@ -72,11 +76,8 @@ fn parse_orders_basic_and_print() {
     );
     assert_eq!(out.len(), 1);
 
-    let printable = out
-        .into_iter()
-        .map(mostro_client::util::Event::SmallOrder)
-        .collect::<Vec<_>>();
-    let table = print_orders_table(printable).expect("table should render");
+    let printable = out.into_iter().map(OrderView::from).collect::<Vec<_>>();
+    let table = print_orders_table(printable, None).expect("table should render");
     assert!(table.contains("USD"));
 }
 
@@ -195,8 +196,8 @@ fn parse_orders_no_filters() {
 
 #[test]
 fn print_orders_empty_list() {
-    let orders: Vec<mostro_client::util::Event> = Vec::new();
-    let table = print_orders_table(orders);
+    let orders: Vec<OrderView> = Vec::new();
+    let table = print_orders_table(orders, None);
 
     assert!(table.is_ok());
     let table_str = table.unwrap();
@@ -229,12 +230,9 @@ fn print_orders_multiple_orders() {
     }
 
     let parsed = parse_orders_events(events, None, None, None);
-    let printable = parsed
-        .into_iter()
-        .map(mostro_client::util::Event::SmallOrder)
-        .collect::<Vec<_>>();
+    let printable = parsed.into_iter().map(OrderView::from).collect::<Vec<_>>();
 
-    let table = print_orders_table(printable);
+    let table = print_orders_table(printable, None);
     assert!(table.is_ok());
 
     let table_str = table.unwrap();
@@ -300,3 +298,343 @@ fn parse_orders_market_price() {
     let out = parse_orders_events(events, Some("USD".into()), None, None);
     assert_eq!(out.len(), 1);
 }
+
+#[test]
+fn print_order_preview_shows_mostro_fee() {
+    let order = SmallOrder {
+        kind: Some(mostro_core::order::Kind::Sell),
+        amount: 100_000,
+        fiat_code: "USD".to_string(),
+        fiat_amount: 50,
+        payment_method: "cash".to_string(),
+        ..Default::default()
+    };
+    let preview = print_order_preview(Payload::Order(order.clone()), Some(0.006), None).unwrap();
+    assert!(preview.contains("300 sats (0.3%)"), "{}", preview);
+
+    let market = SmallOrder { amount: 0, ..order };
+    let preview = print_order_preview(Payload::Order(market.clone()), Some(0.006), None).unwrap();
+    assert!(preview.contains("0.3% of the sats amount"), "{}", preview);
+    let preview = print_order_preview(Payload::Order(market), None, None).unwrap();
+    assert!(preview.contains("unknown"));
+}
+
+#[test]
+fn order_table_and_preview_show_market_estimates() {
+    let market = SmallOrder {
+        id: Some(uuid::Uuid::new_v4()),
+        kind: Some(mostro_core::order::Kind::Sell),
+        status: Some(Status::Pending),
+        amount: 0,
+        fiat_code: "USD".to_string(),
+        fiat_amount: 100,
+        payment_method: "cash".to_string(),
+        premium: 5,
+        ..Default::default()
+    };
+    let rates = mostro_client::price::BtcRates {
+        rates: [("USD".to_string(), 50_000.0)].into_iter().collect(),
+        fetched_at: 0,
+    };
+    let table = print_orders_table(vec![OrderView::from(market.clone())], Some(&rates)).unwrap();
+    assert!(table.contains("~190000"), "{}", table);
+    assert!(table.contains("+5.3%"), "{}", table);
+
+    let preview = print_order_preview(Payload::Order(market), Some(0.006), Some(50_000.0)).unwrap();
+    assert!(preview.contains("~190000"), "{}", preview);
+    assert!(preview.contains("~570 sats (0.3%)"), "{}", preview);
+}
+
+fn rating_tags(value: &str) -> Tags {
+    Tags::from_list(vec![Tag::custom(
+        TagKind::Custom("rating".into()),
+        vec![value.to_string()],
+    )])
+}
+
+#[test]
+fn maker_rating_reads_both_tag_formats() {
+    let plain = rating_tags(r#"{"total_reviews":12,"total_rating":4.5,"days":90}"#);
+    assert_eq!(
+        maker_rating_from_tags(&plain),
+        Some(MakerRating {
+            total_reviews: 12,
+            total_rating: 4.5,
+            days: 90
+        })
+    );
+    let wrapped = rating_tags(r#"["rating",{"days":3,"total_rating":0.0,"total_reviews":0}]"#);
+    assert_eq!(maker_rating_from_tags(&wrapped).unwrap().days, 3);
+    assert_eq!(maker_rating_from_tags(&rating_tags("n/a")), None);
+    assert_eq!(maker_rating_from_tags(&Tags::new()), None);
+}
+
+fn full_order_tags(id: uuid::Uuid) -> Vec<Tag> {
+    let tag = |name: &str, values: &[&str]| {
+        Tag::custom(
+            TagKind::Custom(name.to_string().into()),
+            values.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+        )
+    };
+    vec![
+        tag("d", &[&id.to_string()]),
+        tag("k", &["sell"]),
+        tag("f", &["EUR"]),
+        tag("s", &["pending"]),
+        tag("amt", &["0"]),
+        tag("fa", &["50"]),
+        tag("pm", &["SEPA", "Bizum"]),
+        tag("premium", &["1"]),
+        tag(
+            "rating",
+            &[r#"{"total_reviews":2,"total_rating":3.0,"days":5}"#],
+        ),
+        tag("name", &["alice"]),
+        tag("expires_at", &["1900000000"]),
+        tag("expiration", &["1900086400"]),
+        tag("network", &["mainnet"]),
+        tag("layer", &["lightning"]),
+        tag("source", &["https://example.com/order"]),
+        tag("g", &["u4pruyd"]),
+        tag("y", &["mostro", "Example Node"]),
+        tag("z", &["order"]),
+        tag("bond", &["1000"]),
+        tag("lang", &["es", "en"]),
+    ]
+}
+
+#[test]
+fn order_view_keeps_every_tag() {
+    let id = uuid::Uuid::new_v4();
+    let view = order_view_from_tags(Tags::from_list(full_order_tags(id))).unwrap();
+    assert_eq!(view.order.id, Some(id));
+    assert_eq!(view.order.payment_method, "SEPA,Bizum");
+    assert_eq!(view.order.expires_at, Some(1_900_000_000));
+    assert_eq!(view.maker_rating.unwrap().total_reviews, 2);
+    assert_eq!(view.name.as_deref(), Some("alice"));
+    assert_eq!(view.expires_at, Some(1_900_000_000));
+    assert_eq!(view.expiration, Some(1_900_086_400));
+    assert_eq!(view.network.as_deref(), Some("mainnet"));
+    assert_eq!(view.layer.as_deref(), Some("lightning"));
+    assert_eq!(view.source.as_deref(), Some("https://example.com/order"));
+    assert_eq!(view.geohash.as_deref(), Some("u4pruyd"));
+    assert_eq!(view.platform.as_deref(), Some("mostro Example Node"));
+    assert_eq!(view.document.as_deref(), Some("order"));
+    assert_eq!(view.bond.get("bond").map(String::as_str), Some("1000"));
+    assert_eq!(
+        view.other.get("lang"),
+        Some(&vec!["es".to_string(), "en".to_string()])
+    );
+}
+
+#[test]
+fn parse_order_views_and_render_details() {
+    let keys = Keys::generate();
+    let id = uuid::Uuid::new_v4();
+    let event = EventBuilder::new(nostr_sdk::Kind::TextNote, "")
+        .tags(full_order_tags(id))
+        .sign_with_keys(&keys)
+        .unwrap();
+    let mut events = Events::new(&Filter::new());
+    events.insert(event);
+    events.insert(build_order_event(
+        mostro_core::order::Kind::Buy,
+        Status::Pending,
+        "USD",
+        0,
+        10,
+    ));
+    let views = parse_order_views(events, Some("EUR".into()), None, None);
+    assert_eq!(views.len(), 1);
+    let view = &views[0];
+    assert!(view.order.created_at.is_some());
+
+    let table = print_orders_table(views.clone(), None).unwrap();
+    assert!(table.contains("3.0★"), "{}", table);
+
+    let detail = print_order_detail(view, Some(60_000.0), 1_899_996_400);
+    for expected in [
+        "alice",
+        "mainnet",
+        "lightning",
+        "u4pruyd",
+        "Example Node",
+        "in 1h 00m 00s",
+        "3.0/5 from 2 reviews, active for 5 days",
+        "bond",
+        "lang",
+        "SEPA,Bizum",
+    ] {
+        assert!(detail.contains(expected), "missing {expected}: {detail}");
+    }
+}
+
+#[test]
+fn maker_rating_and_expiry_texts() {
+    assert_eq!(maker_rating_text(None), "-");
+    let new_maker = MakerRating {
+        total_reviews: 0,
+        total_rating: 0.0,
+        days: 1,
+    };
+    assert_eq!(maker_rating_text(Some(&new_maker)), "new (1d)");
+    let one = MakerRating {
+        total_reviews: 1,
+        total_rating: 5.0,
+        days: 10,
+    };
+    assert_eq!(maker_rating_text(Some(&one)), "5.0★ (1, 10d)");
+    assert_eq!(expires_text(None, 100), "-");
+    assert_eq!(expires_text(Some(100), 100), "expired");
+    assert_eq!(expires_text(Some(190), 100), "in 1m 30s");
+    assert_eq!(
+        expires_text(Some(100 + 2 * 86_400 + 4 * 3_600), 100),
+        "in 2d 04h"
+    );
+}
+
+fn filter_order(premium: i64, created_at: i64) -> SmallOrder {
+    SmallOrder {
+        fiat_code: "USD".to_string(),
+        fiat_amount: 100,
+        payment_method: "SEPA,Revolut".to_string(),
+        premium,
+        created_at: Some(created_at),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn order_filter_premium_rating_and_age() {
+    let now = 1_700_000_000;
+    let order = filter_order(2, now - 600);
+    let rated = MakerRating {
+        total_reviews: 10,
+        total_rating: 4.2,
+        days: 30,
+    };
+
+    assert!(OrderFilter::default().matches(&order, None, now));
+
+    let premium = OrderFilter {
+        min_premium: Some(-1),
+        max_premium: Some(1),
+        ..Default::default()
+    };
+    assert!(!premium.matches(&order, None, now));
+    assert!(premium.matches(&filter_order(-1, now), None, now));
+
+    let rating = OrderFilter {
+        min_rating: Some(4.0),
+        ..Default::default()
+    };
+    assert!(rating.matches(&order, Some(&rated), now));
+    assert!(!rating.matches(&order, None, now));
+
+    let age = OrderFilter {
+        max_age_secs: Some(300),
+        ..Default::default()
+    };
+    assert!(!age.matches(&order, None, now));
+    assert!(age.matches(&filter_order(2, now - 60), None, now));
+
+    let methods = OrderFilter {
+        payment_methods: vec!["revolut".to_string()],
+        ..Default::default()
+    };
+    assert!(methods.matches(&order, None, now));
+}
+
+#[test]
+fn sort_orders_by_each_key() {
+    let mut orders: Vec<OrderView> = vec![
+        filter_order(3, 10).into(),
+        filter_order(-1, 30).into(),
+        SmallOrder {
+            min_amount: Some(10),
+            max_amount: Some(500),
+            ..filter_order(0, 20)
+        }
+        .into(),
+    ];
+    sort_orders(&mut orders, OrderSort::Age, false);
+    assert_eq!(
+        orders
+            .iter()
+            .map(|v| v.order.created_at)
+            .collect::<Vec<_>>(),
+        vec![Some(30), Some(20), Some(10)]
+    );
+    sort_orders(&mut orders, OrderSort::Premium, false);
+    assert_eq!(
+        orders.iter().map(|v| v.order.premium).collect::<Vec<_>>(),
+        vec![-1, 0, 3]
+    );
+    sort_orders(&mut orders, OrderSort::Amount, true);
+    assert_eq!(orders[0].order.max_amount, Some(500));
+}
+
+#[test]
+fn decimal_fiat_amounts_are_kept_exactly() {
+    let tag = |name: &str, values: &[&str]| {
+        Tag::custom(
+            TagKind::Custom(name.to_string().into()),
+            values.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+        )
+    };
+    let order_event = |fa: &[&str]| {
+        EventBuilder::new(nostr_sdk::Kind::TextNote, "")
+            .tags([
+                tag("d", &[&uuid::Uuid::new_v4().to_string()]),
+                tag("k", &["sell"]),
+                tag("f", &["EUR"]),
+                tag("s", &["pending"]),
+                tag("amt", &["0"]),
+                tag("fa", fa),
+                tag("pm", &["SEPA"]),
+                tag("premium", &["0"]),
+            ])
+            .sign_with_keys(&Keys::generate())
+            .unwrap()
+    };
+    let mut events = Events::new(&Filter::new());
+    events.insert(order_event(&["12.50"]));
+    events.insert(order_event(&["10.5", "20.25"]));
+
+    // Both used to be dropped for their "."
+    let orders = parse_orders_events(events.clone(), None, None, None);
+    assert_eq!(orders.len(), 2);
+    let mut views = parse_order_views(events, None, None, None);
+    // By the exact amounts: 12.50, then the range up to 20.25
+    sort_orders(&mut views, OrderSort::Amount, false);
+    let (fixed, range) = (&views[0], &views[1]);
+    // The whole-unit SmallOrder rounds each bound, halves up; the view
+    // keeps the exact amounts
+    assert_eq!(fixed.order.fiat_amount, 13);
+    assert_eq!(fixed.fiat_amount_text(), "12.50");
+    assert_eq!(
+        (range.order.min_amount, range.order.max_amount),
+        (Some(11), Some(20))
+    );
+    assert_eq!(range.fiat_amount_text(), "10.5-20.25");
+
+    let amount = |a: &str| OrderFilter {
+        fiat_amount: Some(a.parse().unwrap()),
+        ..Default::default()
+    };
+    assert!(amount("12.5").matches_view(fixed, 0));
+    assert!(!amount("13").matches_view(fixed, 0));
+    assert!(amount("20.25").matches_view(range, 0));
+    assert!(!amount("20.5").matches_view(range, 0));
+
+    let rates = mostro_client::price::BtcRates {
+        rates: [("EUR".to_string(), 50_000.0)].into(),
+        fetched_at: 0,
+    };
+    let table = print_orders_table(views.clone(), Some(&rates)).unwrap();
+    assert!(table.contains("12.50"), "{}", table);
+    assert!(table.contains("~25000"), "{}", table);
+    let detail = print_order_detail(range, Some(50_000.0), 0);
+    assert!(detail.contains("10.5-20.25 EUR"), "{}", detail);
+    assert!(detail.contains("~21000-40500"), "{}", detail);
+}
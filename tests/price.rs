@@ -0,0 +1,134 @@
+use mostro_client::price::yadio::{parse_btc_rates, parse_currencies};
+use mostro_client::price::{
+    fiat_to_sats, implied_btc_price, market_sats, sats_to_fiat, OrderEstimate, PriceProvider,
+    YadioProvider,
+};
+use mostro_core::prelude::SmallOrder;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn test_fiat_sats_conversion() {
+    assert_eq!(fiat_to_sats(100.0, 50_000.0), 200_000);
+    assert_eq!(fiat_to_sats(1.0, 100_000_000.0), 1);
+    assert!((sats_to_fiat(200_000, 50_000.0) - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_market_sats_applies_premium() {
+    assert_eq!(market_sats(100.0, 0, 50_000.0), 200_000);
+    // A positive premium means fewer sats for the same fiat
+    assert_eq!(market_sats(100.0, 5, 50_000.0), 190_000);
+    assert_eq!(market_sats(100.0, -5, 50_000.0), 210_000);
+    assert_eq!(implied_btc_price(100.0, 200_000), Some(50_000.0));
+    assert_eq!(implied_btc_price(100.0, 0), None);
+}
+
+#[test]
+fn test_order_estimates() {
+    let market = SmallOrder {
+        amount: 0,
+        fiat_code: "USD".to_string(),
+        fiat_amount: 100,
+        premium: 5,
+        ..Default::default()
+    };
+    let estimate = OrderEstimate::new(&market, Some(50_000.0));
+    assert_eq!(estimate.min_sats, Some(190_000));
+    assert_eq!(estimate.sats_text(&market), "~190000");
+    assert_eq!(estimate.price_text("USD"), "52631.58 USD (+5.3%)");
+
+    let range = SmallOrder {
+        min_amount: Some(10),
+        max_amount: Some(100),
+        fiat_amount: 0,
+        premium: 0,
+        ..market.clone()
+    };
+    let estimate = OrderEstimate::new(&range, Some(50_000.0));
+    assert_eq!(estimate.sats_text(&range), "~20000-200000");
+    assert_eq!(estimate.price_text("USD"), "50000.00 USD (+0.0%)");
+
+    let fixed = SmallOrder {
+        amount: 250_000,
+        ..market.clone()
+    };
+    let estimate = OrderEstimate::new(&fixed, Some(50_000.0));
+    assert_eq!(estimate.sats_text(&fixed), "250000");
+    assert_eq!(estimate.price_text("USD"), "40000.00 USD (-20.0%)");
+    // Without a market price the fixed order still shows its own price
+    assert_eq!(
+        OrderEstimate::new(&fixed, None).price_text("USD"),
+        "40000.00 USD"
+    );
+
+    let unknown = OrderEstimate::new(&market, None);
+    assert_eq!(unknown.sats_text(&market), "market");
+    assert_eq!(unknown.price_text("USD"), "-");
+}
+
+#[test]
+fn test_parse_yadio_rates() {
+    let rates = parse_btc_rates(
+        json!({
+            "BTC": {"USD": 67000.5, "eur": 61000, "BTC": 1, "XYZ": "n/a"},
+            "base": "BTC",
+            "timestamp": 1700000000000u64
+        }),
+        42,
+    )
+    .unwrap();
+    assert_eq!(rates.fetched_at, 42);
+    assert_eq!(rates.rates.len(), 2);
+    assert_eq!(rates.price("usd"), Some(67000.5));
+    assert_eq!(rates.price("EUR"), Some(61000.0));
+    assert_eq!(rates.price("BTC"), None);
+
+    assert!(parse_btc_rates(json!({"error": "down"}), 0).is_err());
+    assert!(parse_btc_rates(json!({"BTC": {}}), 0).is_err());
+}
+
+#[test]
+fn test_parse_yadio_currencies() {
+    let names = parse_currencies(json!({"usd": "US Dollar", "VES": "Bolívar"})).unwrap();
+    assert_eq!(names.get("USD").map(String::as_str), Some("US Dollar"));
+    assert!(names.contains_key("VES"));
+    assert!(parse_currencies(json!(["USD"])).is_err());
+}
+
+#[tokio::test]
+async fn test_yadio_provider_against_local_server() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/currencies"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"USD": "US Dollar"})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/exrates/BTC"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"BTC": {"USD": 50000.0}})))
+        .mount(&server)
+        .await;
+
+    let provider = YadioProvider::new(&format!("{}/", server.uri()));
+    assert_eq!(provider.base_url(), server.uri());
+    assert!(provider.name().contains(&server.uri()));
+    assert!(provider.currencies().await.unwrap().contains_key("USD"));
+    assert_eq!(
+        provider.btc_rates().await.unwrap().price("USD"),
+        Some(50000.0)
+    );
+}
+
+#[tokio::test]
+async fn test_yadio_provider_reports_http_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    let provider = YadioProvider::new(&server.uri());
+    let err = provider.btc_rates().await.unwrap_err().to_string();
+    assert!(err.contains("503"), "{}", err);
+}
@@ -0,0 +1,132 @@
+use mostro_client::cli::template::{
+    new_order_template, normalize_payment_methods, NewOrderTerms, OrderTerms,
+};
+use mostro_client::fiat::FiatRange;
+
+fn terms(kind: &str, fiat: &str, amount: i64, premium: i64) -> NewOrderTerms {
+    NewOrderTerms {
+        kind: kind.to_string(),
+        fiat_code: "usd".to_string(),
+        fiat_amount: FiatRange::parse(fiat).unwrap(),
+        amount,
+        payment_method: " zelle, bank transfer ,".to_string(),
+        premium,
+        invoice: None,
+        expiration_days: 0,
+    }
+}
+
+#[test]
+fn test_normalize_payment_methods() {
+    assert_eq!(
+        normalize_payment_methods(" zelle, bank transfer ,"),
+        "zelle,bank transfer"
+    );
+    assert_eq!(normalize_payment_methods("zelle"), "zelle");
+    assert_eq!(normalize_payment_methods(" , "), "");
+}
+
+#[test]
+fn test_new_order_template_validation() {
+    let template = new_order_template(" usd-zelle ", terms("Sell", "50-500", 0, 2)).unwrap();
+    assert_eq!(template.name, "usd-zelle");
+    assert_eq!(template.kind, "sell");
+    assert_eq!(template.fiat_code, "USD");
+    assert_eq!(template.fiat_amount, "50-500");
+    assert_eq!(template.payment_method, "zelle,bank transfer");
+
+    assert!(new_order_template("", terms("sell", "100", 0, 0)).is_err());
+    assert!(new_order_template("t", terms("swap", "100", 0, 0)).is_err());
+    // Shape problems are caught before the template is stored
+    assert!(new_order_template("t", terms("sell", "50-500", 20_000, 0)).is_err());
+    assert!(new_order_template("t", terms("sell", "100", 20_000, 2)).is_err());
+    assert!(new_order_template("t", terms("sell", "100", 20_000, 0)).is_ok());
+
+    let mut no_method = terms("sell", "100", 0, 0);
+    no_method.payment_method = " , ".to_string();
+    assert!(new_order_template("t", no_method).is_err());
+
+    // A payout destination is for buy templates, and must be reusable
+    let with_invoice = |kind: &str, invoice: &str| NewOrderTerms {
+        invoice: Some(invoice.to_string()),
+        ..terms(kind, "100", 0, 0)
+    };
+    assert!(new_order_template("t", with_invoice("sell", "me@example.com")).is_err());
+    assert!(new_order_template("t", with_invoice("buy", "lnbc1invoice")).is_err());
+    assert!(new_order_template("t", with_invoice("buy", "me@example.com")).is_ok());
+}
+
+#[test]
+fn test_template_overrides() {
+    let template = new_order_template("usd", terms("sell", "100", 0, 2)).unwrap();
+
+    let plain = OrderTerms::default().with_template(&template).unwrap();
+    assert_eq!(plain.kind, "sell");
+    assert_eq!(plain.fiat_code, "USD");
+    assert_eq!(plain.fiat_amount, FiatRange::parse("100").unwrap());
+    assert_eq!(plain.payment_method, "zelle,bank transfer");
+    assert_eq!((plain.amount, plain.premium), (0, 2));
+
+    let overridden = OrderTerms {
+        fiat_amount: Some(FiatRange::parse("250").unwrap()),
+        payment_method: Some("wise".to_string()),
+        expiration_days: Some(2),
+        ..Default::default()
+    }
+    .with_template(&template)
+    .unwrap();
+    assert_eq!(overridden.fiat_amount, FiatRange::parse("250").unwrap());
+    assert_eq!(overridden.payment_method, "wise");
+    assert_eq!(overridden.expiration_days, 2);
+    assert_eq!(overridden.premium, 2);
+
+    // Fixing the sats drops the template premium
+    let fixed = OrderTerms {
+        amount: Some(20_000),
+        ..Default::default()
+    }
+    .with_template(&template)
+    .unwrap();
+    assert_eq!((fixed.amount, fixed.premium), (20_000, 0));
+
+    // ... and a premium or a range drops the template sats
+    let fixed_template = new_order_template("fixed", terms("sell", "100", 20_000, 0)).unwrap();
+    let premium = OrderTerms {
+        premium: Some(-1),
+        ..Default::default()
+    }
+    .with_template(&fixed_template)
+    .unwrap();
+    assert_eq!((premium.amount, premium.premium), (0, -1));
+    let range = OrderTerms {
+        fiat_amount: Some(FiatRange::parse("50-500").unwrap()),
+        ..Default::default()
+    }
+    .with_template(&fixed_template)
+    .unwrap();
+    assert_eq!(range.amount, 0);
+}
+
+#[test]
+fn test_terms_without_template() {
+    assert!(OrderTerms {
+        kind: Some("buy".to_string()),
+        ..Default::default()
+    }
+    .complete()
+    .is_err());
+
+    let complete = OrderTerms {
+        kind: Some("buy".to_string()),
+        fiat_code: Some("EUR".to_string()),
+        fiat_amount: Some(FiatRange::parse("40").unwrap()),
+        payment_method: Some("sepa".to_string()),
+        ..Default::default()
+    }
+    .complete()
+    .unwrap();
+    assert_eq!(complete.amount, 0);
+    assert_eq!(complete.premium, 0);
+    assert_eq!(complete.expiration_days, 0);
+    assert_eq!(complete.invoice, None);
+}
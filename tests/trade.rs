@@ -0,0 +1,293 @@
+use mostro_client::trade::{
+    action_problem, is_final, role_from_messages, role_from_payloads, status_after, step_after,
+    step_for_status, ActionProblem, TradeChoice, TradeParty, TradeRole, TradeStep,
+};
+use mostro_core::order::Kind;
+use mostro_core::prelude::*;
+use nostr_sdk::prelude::Keys;
+
+fn message(action: Action, payload: Option<Payload>) -> MessageKind {
+    MessageKind::new(Some(uuid::Uuid::new_v4()), None, None, action, payload)
+}
+
+#[test]
+fn test_trade_role() {
+    assert_eq!(TradeRole::new(Kind::Buy, true), TradeRole::Buyer);
+    assert_eq!(TradeRole::new(Kind::Sell, false), TradeRole::Buyer);
+    assert_eq!(TradeRole::new(Kind::Sell, true), TradeRole::Seller);
+    assert_eq!(TradeRole::new(Kind::Buy, false), TradeRole::Seller);
+}
+
+#[test]
+fn test_steps_follow_messages() {
+    use TradeRole::*;
+    // A sell order taken by a buyer, from both sides
+    let flow = [
+        (
+            Action::NewOrder,
+            TradeStep::WaitingForTaker,
+            TradeStep::WaitingForTaker,
+        ),
+        (
+            Action::AddInvoice,
+            TradeStep::AddInvoice,
+            TradeStep::AddInvoice,
+        ),
+        (
+            Action::WaitingSellerToPay,
+            TradeStep::WaitingForCounterparty,
+            TradeStep::WaitingForCounterparty,
+        ),
+        (
+            Action::PayInvoice,
+            TradeStep::PayInvoice,
+            TradeStep::PayInvoice,
+        ),
+        (
+            Action::HoldInvoicePaymentAccepted,
+            TradeStep::SendFiat,
+            TradeStep::WaitingForFiat,
+        ),
+        (
+            Action::FiatSentOk,
+            TradeStep::WaitingForRelease,
+            TradeStep::Release,
+        ),
+        (Action::PurchaseCompleted, TradeStep::Rate, TradeStep::Rate),
+        (Action::RateReceived, TradeStep::Done, TradeStep::Done),
+    ];
+    for (action, buyer, seller) in flow {
+        assert_eq!(step_after(Buyer, &action), Some(buyer), "{action}");
+        assert_eq!(step_after(Seller, &action), Some(seller), "{action}");
+    }
+    assert_eq!(
+        step_after(Buyer, &Action::DisputeInitiatedByPeer),
+        Some(TradeStep::Dispute)
+    );
+    assert_eq!(
+        step_after(Seller, &Action::AdminSettled),
+        Some(TradeStep::Done)
+    );
+    // Messages that don't move the trade
+    assert_eq!(
+        step_after(Buyer, &Action::CooperativeCancelInitiatedByYou),
+        None
+    );
+    assert_eq!(step_after(Seller, &Action::CantDo), None);
+}
+
+#[test]
+fn test_steps_from_status() {
+    use TradeRole::*;
+    assert_eq!(
+        step_for_status(Buyer, Status::WaitingBuyerInvoice),
+        TradeStep::AddInvoice
+    );
+    assert_eq!(
+        step_for_status(Seller, Status::WaitingBuyerInvoice),
+        TradeStep::WaitingForCounterparty
+    );
+    assert_eq!(
+        step_for_status(Seller, Status::WaitingPayment),
+        TradeStep::PayInvoice
+    );
+    assert_eq!(step_for_status(Buyer, Status::Active), TradeStep::SendFiat);
+    assert_eq!(
+        step_for_status(Seller, Status::FiatSent),
+        TradeStep::Release
+    );
+    assert_eq!(step_for_status(Buyer, Status::Success), TradeStep::Rate);
+    assert_eq!(step_for_status(Buyer, Status::Expired), TradeStep::Done);
+}
+
+#[test]
+fn test_step_choices() {
+    use TradeChoice::*;
+    assert_eq!(
+        TradeStep::SendFiat.choices(),
+        vec![FiatSent, Dispute, Cancel]
+    );
+    assert_eq!(TradeStep::Release.choices(), vec![Release, Dispute, Cancel]);
+    assert_eq!(TradeStep::AddInvoice.choices(), vec![AddInvoice, Cancel]);
+    assert_eq!(TradeStep::WaitingForTaker.choices(), vec![Cancel]);
+    assert_eq!(TradeStep::Rate.choices(), vec![Rate]);
+    assert!(TradeStep::Dispute.choices().is_empty());
+    assert!(TradeStep::Done.choices().is_empty());
+
+    let choices = TradeStep::Release.choices();
+    assert_eq!(TradeChoice::pick("1", &choices), Some(Release));
+    assert_eq!(TradeChoice::pick(" Dispute ", &choices), Some(Dispute));
+    assert_eq!(TradeChoice::pick("4", &choices), None);
+    assert_eq!(TradeChoice::pick("0", &choices), None);
+    // Only what the step offers
+    assert_eq!(TradeChoice::pick("fiat-sent", &choices), None);
+    assert_eq!(
+        TradeChoice::pick("fiat-sent", &TradeStep::SendFiat.choices()),
+        Some(FiatSent)
+    );
+}
+
+#[test]
+fn test_role_from_messages() {
+    let keys = Keys::generate();
+    let ours = keys.public_key();
+
+    // Payload trade pubkeys name our side
+    let order = SmallOrder {
+        kind: Some(Kind::Sell),
+        seller_trade_pubkey: Some(Keys::generate().public_key().to_hex()),
+        buyer_trade_pubkey: Some(ours.to_hex()),
+        ..Default::default()
+    };
+    let messages = [
+        message(Action::NewOrder, None),
+        message(
+            Action::HoldInvoicePaymentAccepted,
+            Some(Payload::Order(order)),
+        ),
+    ];
+    assert_eq!(
+        role_from_messages(Some(Kind::Sell), None, &ours, &messages),
+        Some(TradeRole::Buyer)
+    );
+
+    // Without them, a trade we opened with new-order makes us the maker
+    let made = [message(Action::NewOrder, None)];
+    assert_eq!(
+        role_from_messages(Some(Kind::Sell), None, &ours, &made),
+        Some(TradeRole::Seller)
+    );
+    let taken = [message(Action::PayInvoice, None)];
+    assert_eq!(
+        role_from_messages(Some(Kind::Buy), None, &ours, &taken),
+        Some(TradeRole::Seller)
+    );
+    assert_eq!(role_from_payloads(&ours, &taken), None);
+    assert_eq!(role_from_messages(None, None, &ours, &taken), None);
+    assert_eq!(role_from_messages(Some(Kind::Buy), None, &ours, &[]), None);
+
+    // A take message makes us the taker
+    let took = [
+        message(Action::TakeSell, None),
+        message(Action::AddInvoice, None),
+    ];
+    assert_eq!(
+        role_from_messages(Some(Kind::Sell), None, &ours, &took),
+        Some(TradeRole::Buyer)
+    );
+
+    // The messages win over the local order row, e.g. a taker row saved
+    // with is_mine set by an earlier version
+    assert_eq!(
+        role_from_messages(Some(Kind::Sell), Some(false), &ours, &made),
+        Some(TradeRole::Seller)
+    );
+    assert_eq!(
+        role_from_messages(Some(Kind::Sell), Some(true), &ours, &took),
+        Some(TradeRole::Buyer)
+    );
+    // which only decides without a take or new-order message
+    assert_eq!(
+        role_from_messages(Some(Kind::Buy), Some(true), &ours, &[]),
+        Some(TradeRole::Buyer)
+    );
+    assert_eq!(
+        role_from_messages(Some(Kind::Buy), Some(true), &ours, &taken),
+        Some(TradeRole::Buyer)
+    );
+}
+
+#[test]
+fn test_trade_party() {
+    let taker = TradeParty::new(Kind::Sell, false);
+    assert_eq!(taker.role, TradeRole::Buyer);
+    assert_eq!(TradeParty::with_role(Kind::Sell, TradeRole::Buyer), taker);
+    assert!(TradeParty::with_role(Kind::Buy, TradeRole::Buyer).maker);
+    assert_eq!(taker.to_string(), "the buyer (taker)");
+}
+
+#[test]
+fn test_status_after() {
+    let mut status = Status::Pending;
+    for action in [
+        Action::PayInvoice,
+        Action::HoldInvoicePaymentAccepted,
+        Action::FiatSentOk,
+        Action::HoldInvoicePaymentSettled,
+        Action::PurchaseCompleted,
+    ] {
+        status = status_after(status, &action);
+    }
+    assert_eq!(status, Status::Success);
+    assert!(is_final(status));
+    // Final statuses never move, and unrelated messages change nothing
+    assert_eq!(
+        status_after(Status::Success, &Action::Canceled),
+        Status::Success
+    );
+    assert_eq!(
+        status_after(Status::Active, &Action::CantDo),
+        Status::Active
+    );
+    assert_eq!(
+        status_after(Status::FiatSent, &Action::DisputeInitiatedByPeer),
+        Status::Dispute
+    );
+    assert!(!is_final(Status::Dispute));
+}
+
+#[test]
+fn test_action_problem() {
+    let buyer = Some(TradeParty::new(Kind::Sell, false));
+    let seller = Some(TradeParty::new(Kind::Sell, true));
+
+    // Releasing a pending order
+    let problem = action_problem(&Action::Release, Status::Pending, seller).unwrap();
+    assert!(matches!(problem, ActionProblem::Status { .. }));
+    assert_eq!(
+        problem.to_string(),
+        "release needs an order that is active, fiat-sent, dispute, but this order is pending"
+    );
+    assert_eq!(
+        action_problem(&Action::Release, Status::FiatSent, seller),
+        None
+    );
+
+    // The wrong side of the trade
+    let problem = action_problem(&Action::Release, Status::FiatSent, buyer).unwrap();
+    assert_eq!(
+        problem.to_string(),
+        "only the seller can send release on this order, and you are the buyer (taker)"
+    );
+    assert!(action_problem(&Action::FiatSent, Status::Active, seller).is_some());
+    assert_eq!(
+        action_problem(&Action::FiatSent, Status::Active, buyer),
+        None
+    );
+    assert!(action_problem(&Action::AddInvoice, Status::WaitingBuyerInvoice, seller).is_some());
+
+    // Only the maker cancels a pending order; either side once taken
+    assert!(action_problem(&Action::Cancel, Status::Pending, buyer).is_some());
+    assert_eq!(
+        action_problem(&Action::Cancel, Status::Pending, seller),
+        None
+    );
+    assert_eq!(action_problem(&Action::Cancel, Status::Active, buyer), None);
+    assert!(action_problem(&Action::Cancel, Status::Dispute, buyer).is_some());
+
+    // Without a known party only the status is checked
+    assert_eq!(
+        action_problem(&Action::FiatSent, Status::Active, None),
+        None
+    );
+    assert!(action_problem(&Action::RateUser, Status::Active, None).is_some());
+    assert_eq!(
+        action_problem(&Action::RateUser, Status::Success, None),
+        None
+    );
+    // Actions outside a trade are not checked
+    assert_eq!(
+        action_problem(&Action::AdminCancel, Status::Pending, None),
+        None
+    );
+}
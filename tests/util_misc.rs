@@ -162,3 +162,24 @@ fn test_ensure_private_dir_rejects_non_directory() {
 
     std::fs::remove_file(&path).ok();
 }
+
+#[test]
+fn test_node_info_table_shows_published_settings() {
+    use mostro_client::parser::common::create_node_info_table;
+    use mostro_client::util::NodeInfo;
+
+    let info = NodeInfo {
+        mostro_version: Some("0.15.2".to_string()),
+        fee: Some(0.006),
+        max_order_amount: Some(1_000_000),
+        fiat_currencies_accepted: vec!["USD".to_string(), "EUR".to_string()],
+        ..Default::default()
+    };
+    let table = create_node_info_table(&info).to_string();
+    assert!(table.contains("0.15.2"));
+    assert!(table.contains("0.6% (0.3% per side)"));
+    assert!(table.contains("1000000 sats"));
+    assert!(table.contains("USD, EUR"));
+    // Settings the node does not publish are left out
+    assert!(!table.contains("LND Version"));
+}
@@ -0,0 +1,81 @@
+use mostro_client::util::qr::{
+    invoice_qr_payload, render_qr_png, render_qr_svg, render_qr_terminal, write_qr_file, QrOptions,
+};
+use std::path::PathBuf;
+
+const INVOICE: &str = "lnbcrt10u1pjexampleinvoicestringforqrtests";
+
+#[test]
+fn test_invoice_qr_payload_is_uppercase_uri() {
+    assert_eq!(
+        invoice_qr_payload(INVOICE),
+        format!("LIGHTNING:{}", INVOICE.to_uppercase())
+    );
+    // An existing scheme is not doubled
+    assert_eq!(
+        invoice_qr_payload(&format!("lightning:{}", INVOICE)),
+        invoice_qr_payload(INVOICE)
+    );
+}
+
+#[test]
+fn test_render_qr_terminal_uses_half_blocks() {
+    let qr = render_qr_terminal(&invoice_qr_payload(INVOICE)).unwrap();
+    let lines: Vec<&str> = qr.lines().collect();
+    assert!(lines.len() > 10);
+    // Half-blocks pack two module rows per line, so the code is about twice
+    // as wide as it is tall
+    let width = lines[0].chars().count();
+    assert!(width > lines.len());
+    assert!(qr.chars().any(|c| matches!(c, '▀' | '▄' | '█')));
+}
+
+#[test]
+fn test_render_qr_png_is_square_png() {
+    let png = render_qr_png(&invoice_qr_payload(INVOICE)).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+    assert_eq!(width, height);
+    assert_eq!(width % 8, 0);
+}
+
+#[test]
+fn test_render_qr_svg_is_svg_document() {
+    let svg = render_qr_svg(&invoice_qr_payload(INVOICE)).unwrap();
+    assert!(svg.contains("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[test]
+fn test_write_qr_file_by_extension() {
+    let dir = std::env::temp_dir().join(format!("mcli-qr-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let payload = invoice_qr_payload(INVOICE);
+
+    let png_path = dir.join("invoice.png");
+    write_qr_file(&payload, &png_path).unwrap();
+    assert!(std::fs::read(&png_path).unwrap().starts_with(b"\x89PNG"));
+
+    let svg_path = dir.join("invoice.SVG");
+    write_qr_file(&payload, &svg_path).unwrap();
+    assert!(std::fs::read_to_string(&svg_path).unwrap().contains("<svg"));
+
+    assert!(write_qr_file(&payload, &dir.join("invoice.jpg")).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_qr_options_enabled() {
+    assert!(!QrOptions::default().is_enabled());
+    let terminal = QrOptions {
+        terminal: true,
+        file: None,
+    };
+    assert!(terminal.is_enabled());
+    let file = QrOptions {
+        terminal: false,
+        file: Some(PathBuf::from("invoice.png")),
+    };
+    assert!(file.is_enabled());
+}